@@ -0,0 +1,66 @@
+use anyhow::Result;
+
+use crate::math::Rational;
+use crate::parser::{Expr, Op};
+use crate::polynomial::{PartialFraction, Polynomial, RationalFunction};
+
+/// Decomposes a rational function of `variable` into partial fractions,
+/// e.g. `(X+3)/(X^2-1)` into `2/(X-1) - 1/(X+1)`. Linear, repeated and
+/// irreducible quadratic factors of the denominator each get their terms.
+pub fn apart(expr: &Expr, variable: &str) -> Result<Expr> {
+    let decomposition = RationalFunction::from_expr(expr)?.apart(variable)?;
+
+    let mut terms = Vec::new();
+    if !decomposition.polynomial.is_zero() {
+        terms.push((false, decomposition.polynomial.to_expr()));
+    }
+    terms.extend(decomposition.fractions.iter().map(fraction_expr));
+
+    let mut terms = terms.into_iter();
+    let Some((negative, first)) = terms.next() else {
+        return Ok(Expr::Number(0.0));
+    };
+    let first = if negative {
+        Expr::UnaryMinus(Box::new(first))
+    } else {
+        first
+    };
+    Ok(terms.fold(first, |sum, (negative, term)| Expr::BinOp {
+        lhs: Box::new(sum),
+        op: if negative { Op::Subtract } else { Op::Add },
+        rhs: Box::new(term),
+    }))
+}
+
+/// The magnitude of a fraction as `n/(d*factor^power)` with integers `n`
+/// and `d`, and whether it is negative.
+fn fraction_expr(fraction: &PartialFraction) -> (bool, Expr) {
+    let content = fraction.numerator.content();
+    let numerator = fraction.numerator.primitive_part()
+        * Polynomial::constant(Rational::integer(content.numerator().abs()));
+
+    let mut denominator = fraction.factor.to_expr();
+    if fraction.factor.len() == 1 {
+        denominator = fraction.factor.pow(fraction.power).to_expr();
+    } else if fraction.power > 1 {
+        denominator = Expr::BinOp {
+            lhs: Box::new(denominator),
+            op: Op::Power,
+            rhs: Box::new(Expr::Number(fraction.power as f64)),
+        };
+    }
+    if content.denominator() != 1 {
+        denominator = Expr::BinOp {
+            lhs: Box::new(Expr::Number(content.denominator() as f64)),
+            op: Op::Multiply,
+            rhs: Box::new(denominator),
+        };
+    }
+
+    let expr = Expr::BinOp {
+        lhs: Box::new(numerator.to_expr()),
+        op: Op::Divide,
+        rhs: Box::new(denominator),
+    };
+    (content.is_negative(), expr)
+}
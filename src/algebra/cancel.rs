@@ -0,0 +1,84 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::parser::Expr;
+use crate::polynomial::{Polynomial, RationalFunction};
+use crate::solver::{solve_polynomial, Solution};
+
+/// A point removed from the domain when a common factor is cancelled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exclusion {
+    /// `variable ≠ value`
+    Point { variable: String, value: Expr },
+    /// `expr ≠ 0`, for factors in several variables
+    Zero(Expr),
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exclusion::Point { variable, value } => write!(f, "{variable}≠{}", value.to_string()),
+            Exclusion::Zero(expr) => write!(f, "{}≠0", expr.to_string()),
+        }
+    }
+}
+
+/// The result of [`cancel`], equal to the input everywhere except at the
+/// excluded points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cancelled {
+    pub expr: Expr,
+    pub excluded: Vec<Exclusion>,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr.to_string())?;
+        for exclusion in &self.excluded {
+            write!(f, ", {exclusion}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Cancels the common factors of a rational expression, so that
+/// `(X^2-1)/(X-1)` becomes `X+1` with `X≠1` recorded as excluded.
+pub fn cancel(expr: &Expr) -> Result<Cancelled> {
+    let (reduced, mut common) = RationalFunction::from_expr(expr)?.cancel();
+
+    // Roots of the removed factor that are still roots of the denominator
+    // stay visible in the result, so they don't need recording
+    loop {
+        let shared = common.gcd(&reduced.denominator);
+        if shared.is_constant() {
+            break;
+        }
+        common = common.exact_div(&shared).expect("gcd divides");
+    }
+
+    Ok(Cancelled {
+        expr: reduced.to_expr(),
+        excluded: exclusions(&common)?,
+    })
+}
+
+fn exclusions(factor: &Polynomial) -> Result<Vec<Exclusion>> {
+    Ok(match factor.variables().as_slice() {
+        [] => Vec::new(),
+        [variable] => {
+            let zero = Expr::Number(0.0);
+            match solve_polynomial(&factor.to_expr(), &zero, variable)? {
+                Solution::Finite(roots) => roots
+                    .into_iter()
+                    .map(|value| Exclusion::Point {
+                        variable: variable.to_owned(),
+                        value,
+                    })
+                    .collect(),
+                Solution::Empty | Solution::All | Solution::Intervals(_) => Vec::new(),
+            }
+        }
+        _ => vec![Exclusion::Zero(factor.to_expr())],
+    })
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use crate::parser::{Expr, Op};
+use crate::polynomial::{Monomial, MonomialOrder, Polynomial};
+
+use super::expand::{sum, Atoms, Summand};
+
+/// Expands `expr` and groups its terms by powers of `variable`, highest
+/// first, e.g. `X*Y + X + 2` becomes `(Y + 1)*X + 2`.
+pub fn collect(expr: &Expr, variable: &str) -> Expr {
+    if let Expr::BinOp { lhs, op, rhs } = expr {
+        if op.is_relation() {
+            return Expr::BinOp {
+                lhs: Box::new(collect(lhs, variable)),
+                op: *op,
+                rhs: Box::new(collect(rhs, variable)),
+            };
+        }
+    }
+
+    let mut atoms = Atoms::new();
+    let poly = atoms.polynomial_of(expr);
+
+    let mut groups: BTreeMap<u32, Polynomial> = BTreeMap::new();
+    for (monomial, coefficient) in poly.terms(MonomialOrder::GrLex) {
+        let power = monomial.power(variable);
+        let rest = monomial
+            .checked_div(&Monomial::variable(variable, power))
+            .unwrap();
+        let group = groups.entry(power).or_default();
+        *group = group.clone() + Polynomial::term(coefficient, rest);
+    }
+
+    let summands = groups
+        .into_iter()
+        .rev()
+        .map(|(power, coefficient)| {
+            let power_of = Monomial::variable(variable, power);
+            match coefficient.terms(MonomialOrder::GrLex).as_slice() {
+                [(monomial, coefficient)] => Summand::Term(*coefficient, *monomial * &power_of),
+                _ if power == 0 => Summand::Expr(atoms.to_expr(&coefficient)),
+                _ => Summand::Expr(Expr::BinOp {
+                    lhs: Box::new(atoms.to_expr(&coefficient)),
+                    op: Op::Multiply,
+                    rhs: Box::new(Expr::Monomial {
+                        coefficient: 1.0,
+                        variable: variable.to_owned(),
+                        exponent: power as f64,
+                    }),
+                }),
+            }
+        })
+        .collect();
+
+    sum(summands, &atoms)
+}
@@ -0,0 +1,194 @@
+use std::f64::consts::PI;
+
+use anyhow::{bail, Result};
+
+use crate::error::SymbolicError;
+use crate::parser::{func, num, pow, Expr, Op, Optimize};
+use crate::solver::contains_variable;
+
+/// Differentiates `expr` with respect to `variable`, e.g. `X^2 * sin(X)`
+/// into `2X*sin(X) + X^2*cos(X)*(pi/180)`. Trigonometric functions work in
+/// degrees like the evaluator.
+///
+/// `floor`, `ceil`, `round`, `trunc` and `sign` are piecewise constant, and
+/// their derivative is taken to be 0 everywhere, even at the jumps where it
+/// is undefined. Likewise `abs(u)` gives `sign(u)` times the derivative of
+/// `u`, which is 0 at the kink.
+pub fn diff(expr: &Expr, variable: &str) -> Result<Expr> {
+    Ok(derivative(expr, variable)?.optimize_expression())
+}
+
+/// The factor that converts the derivative of a trigonometric function of
+/// degrees, `pi/180`.
+fn degree() -> Expr {
+    Expr::Constant {
+        name: "pi".to_string(),
+        value: PI,
+    } / 180
+}
+
+fn derivative(expr: &Expr, variable: &str) -> Result<Expr> {
+    if !contains_variable(expr, variable) && !matches!(expr, Expr::List(_)) {
+        return Ok(num(0.0));
+    }
+
+    Ok(match expr {
+        Expr::Monomial {
+            coefficient,
+            variable: name,
+            exponent,
+        } => {
+            debug_assert_eq!(name, variable);
+            if *exponent == 1.0 {
+                num(*coefficient)
+            } else {
+                Expr::Monomial {
+                    coefficient: coefficient * exponent,
+                    variable: name.to_owned(),
+                    exponent: exponent - 1.0,
+                }
+            }
+        }
+        Expr::UnaryMinus(inner) => -derivative(inner, variable)?,
+        Expr::BinOp { lhs, op, rhs } => {
+            let (u, v) = (lhs.as_ref().clone(), rhs.as_ref().clone());
+            let du = derivative(lhs, variable)?;
+            let dv = derivative(rhs, variable)?;
+            match op {
+                Op::Add => du + dv,
+                Op::Subtract => du - dv,
+                Op::Multiply => du * v.clone() + u * dv,
+                Op::Divide => (du * v.clone() - u * dv) / pow(v, 2),
+                // a % b = |a - b*trunc(a/b)|, where trunc(a/b) is piecewise
+                // constant. The sign is taken before the absolute value, as
+                // `sign(a % b)` is always 1.
+                Op::Modulo => {
+                    let quotient = func("trunc", [u.clone() / v.clone()]);
+                    let remainder = u - v * quotient.clone();
+                    func("sign", [remainder]) * (du - dv * quotient)
+                }
+                Op::Power => power_derivative(u, v, du, dv, variable),
+                Op::Equals => Expr::BinOp {
+                    lhs: Box::new(du),
+                    op: Op::Equals,
+                    rhs: Box::new(dv),
+                },
+                // Inequalities don't carry over to the derivatives
+                _ => bail!(SymbolicError::UnknownDerivative(expr.to_string())),
+            }
+        }
+        Expr::Function { name, args } => {
+            let args: Vec<Expr> = args.iter().map(|arg| arg.as_ref().clone()).collect();
+            let derivatives = args
+                .iter()
+                .map(|arg| derivative(arg, variable))
+                .collect::<Result<Vec<Expr>>>()?;
+            function_derivative(name, &args, &derivatives, variable)?
+        }
+        Expr::Number(_) | Expr::Constant { .. } => num(0.0),
+        Expr::List(items) => Expr::List(
+            items
+                .iter()
+                .map(|item| derivative(item, variable).map(Box::new))
+                .collect::<Result<Vec<Box<Expr>>>>()?,
+        ),
+        Expr::Wildcard(_) => unreachable!("wildcards are rejected by the parser"),
+    })
+}
+
+fn power_derivative(u: Expr, v: Expr, du: Expr, dv: Expr, variable: &str) -> Expr {
+    // d(u^n) = n*u^(n-1)*u'
+    if !contains_variable(&v, variable) {
+        let lowered = match &v {
+            Expr::Number(n) => pow(u, *n - 1.0),
+            _ => pow(u, v.clone() - 1),
+        };
+        return v * lowered * du;
+    }
+
+    let power = pow(u.clone(), v.clone());
+    // d(a^v) = a^v*ln(a)*v'
+    if !contains_variable(&u, variable) {
+        return match &u {
+            Expr::Constant { name, .. } if name == "e" => power * dv,
+            _ => power * func("ln", [u]) * dv,
+        };
+    }
+
+    // d(u^v) = u^v*(v'*ln(u) + v*u'/u)
+    power * (dv * func("ln", [u.clone()]) + v * du / u)
+}
+
+fn function_derivative(
+    name: &str,
+    args: &[Expr],
+    derivatives: &[Expr],
+    variable: &str,
+) -> Result<Expr> {
+    let arity = match (name, args.len()) {
+        ("pow" | "min" | "max", _) => 2,
+        // log(b, x) as well as log(x) in base 10
+        ("log", 2) => 2,
+        _ => 1,
+    };
+    if args.len() != arity {
+        bail!(SymbolicError::WrongArity(
+            name.to_string(),
+            arity,
+            args.len()
+        ));
+    }
+
+    let u = args[0].clone();
+    let du = derivatives[0].clone();
+    Ok(match name {
+        "sin" => func("cos", [u]) * degree() * du,
+        "cos" => -(func("sin", [u]) * degree()) * du,
+        "tan" => degree() * du / pow(func("cos", [u]), 2),
+        "atan" => du / (degree() * (1 + pow(u, 2))),
+        "sqrt" => du / (2 * func("sqrt", [u])),
+        "cbrt" => du / (3 * pow(func("cbrt", [u]), 2)),
+        "ln" => du / u,
+        "exp" => func("exp", [u]) * du,
+        "log2" => du / (u * func("ln", [num(2.0)])),
+        "log10" => du / (u * func("ln", [num(10.0)])),
+        "log" if args.len() == 1 => du / (u * func("ln", [num(10.0)])),
+        // log(b, x) = ln(x)/ln(b)
+        "log" => {
+            let (x, dx) = (args[1].clone(), derivatives[1].clone());
+            let base = func("ln", [u.clone()]);
+            let along_x = dx / (x.clone() * base.clone());
+            let along_base = func("ln", [x]) * du / (u.clone() * pow(base, 2));
+            match (
+                contains_variable(&u, variable),
+                contains_variable(&args[1], variable),
+            ) {
+                (false, _) => along_x,
+                (true, false) => -along_base,
+                (true, true) => along_x - along_base,
+            }
+        }
+        "abs" => func("sign", [u]) * du,
+        // Piecewise constant, so the derivative is 0 between the jumps and
+        // by convention at them
+        "floor" | "ceil" | "round" | "trunc" | "sign" => num(0.0),
+        // fract(u) = u - trunc(u)
+        "fract" => du,
+        "pow" => {
+            let (v, dv) = (args[1].clone(), derivatives[1].clone());
+            power_derivative(u, v, du, dv, variable)
+        }
+        // min(a, b) = (a + b - |a - b|)/2 and max(a, b) = (a + b + |a - b|)/2
+        "min" | "max" => {
+            let (v, dv) = (args[1].clone(), derivatives[1].clone());
+            let jump = func("sign", [u - v]) * (du.clone() - dv.clone());
+            let sum = du + dv;
+            if name == "min" {
+                (sum - jump) / 2
+            } else {
+                (sum + jump) / 2
+            }
+        }
+        name => bail!(SymbolicError::UnknownDerivative(name.to_string())),
+    })
+}
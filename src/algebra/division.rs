@@ -0,0 +1,28 @@
+use anyhow::Result;
+
+use crate::parser::Expr;
+use crate::polynomial::Polynomial;
+
+/// Quotient of polynomial long division in `variable`, e.g.
+/// `quo(X^2 + 1, X - 1, X)` is `X + 1`.
+pub fn quo(dividend: &Expr, divisor: &Expr, variable: &str) -> Result<Expr> {
+    let dividend = Polynomial::from_expr(dividend)?;
+    let divisor = Polynomial::from_expr(divisor)?;
+    Ok(dividend.div_rem(&divisor, variable)?.0.to_expr())
+}
+
+/// Remainder of polynomial long division in `variable`, e.g.
+/// `rem(X^2 + 1, X - 1, X)` is `2`.
+pub fn rem(dividend: &Expr, divisor: &Expr, variable: &str) -> Result<Expr> {
+    let dividend = Polynomial::from_expr(dividend)?;
+    let divisor = Polynomial::from_expr(divisor)?;
+    Ok(dividend.div_rem(&divisor, variable)?.1.to_expr())
+}
+
+/// Greatest common divisor of two polynomials, e.g. `gcd(X^2 - 1, X^2 +
+/// 2X + 1)` is `X + 1`.
+pub fn gcd(a: &Expr, b: &Expr) -> Result<Expr> {
+    let a = Polynomial::from_expr(a)?;
+    let b = Polynomial::from_expr(b)?;
+    Ok(a.gcd(&b).to_expr())
+}
@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+
+use crate::dag::{ExprId, ExprPool};
+use crate::math::Rational;
+use crate::parser::{Expr, Op};
+use crate::polynomial::{Monomial, MonomialOrder, Polynomial};
+
+/// Highest integer power that gets multiplied out.
+const MAX_POWER: u32 = 64;
+
+/// Placeholders start with a character the parser never accepts in a
+/// variable name, so they can't clash with real variables.
+const PLACEHOLDER: char = '#';
+
+/// Subexpressions that aren't polynomial, such as `sin(X)` or `1/Y`. Each
+/// one stands in as a placeholder variable while the rest is expanded as a
+/// [`Polynomial`], and is put back when converting to an expression again.
+/// Atoms with the same canonical form share a placeholder.
+#[derive(Debug, Default)]
+pub(super) struct Atoms {
+    atoms: Vec<(Expr, bool)>,
+    pool: ExprPool,
+    keys: Vec<(ExprId, bool)>,
+    ids: HashMap<(ExprId, bool), usize>,
+}
+
+impl Atoms {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    fn placeholder(&mut self, expr: Expr, reciprocal: bool) -> Polynomial {
+        let key = (self.key(&expr), reciprocal);
+        let id = match self.ids.get(&key) {
+            Some(id) => *id,
+            None => {
+                self.atoms.push((expr, reciprocal));
+                self.keys.push(key);
+                self.ids.insert(key, self.atoms.len() - 1);
+                self.atoms.len() - 1
+            }
+        };
+        Polynomial::variable(&format!("{PLACEHOLDER}{id}"))
+    }
+
+    fn key(&mut self, expr: &Expr) -> ExprId {
+        let id = self.pool.intern(expr);
+        self.pool.canonical(id)
+    }
+
+    /// A placeholder for `expr` as a whole, which is never expanded.
+    pub(super) fn atom(&mut self, expr: Expr) -> Polynomial {
+        self.placeholder(expr, false)
+    }
+
+    /// `1/poly`. A single term is inverted factor by factor, so that
+    /// [`Atoms::cancel`] can cancel each factor against its reciprocal.
+    pub(super) fn reciprocal(&mut self, poly: &Polynomial) -> Polynomial {
+        let terms = poly.terms(MonomialOrder::GrLex);
+        let [(monomial, coefficient)] = terms.as_slice() else {
+            let expr = self.to_expr(poly);
+            return self.placeholder(expr, true);
+        };
+        let mut inverse = Polynomial::constant(Rational::ONE / *coefficient);
+        for (name, power) in monomial.powers() {
+            let factor = match self.get(name).cloned() {
+                Some((atom, reciprocal)) => self.placeholder(atom, !reciprocal),
+                None => {
+                    let variable = Polynomial::variable(name);
+                    let variable = self.to_expr(&variable);
+                    self.placeholder(variable, true)
+                }
+            };
+            inverse = inverse * factor.pow(power);
+        }
+        inverse
+    }
+
+    /// Cancels the atoms in each term against their reciprocals.
+    pub(super) fn cancel(&self, poly: &Polynomial) -> Polynomial {
+        let mut cancelled = Polynomial::zero();
+        for (monomial, coefficient) in poly.terms(MonomialOrder::GrLex) {
+            let mut powers: Vec<(&str, u32)> = monomial.powers().collect();
+            for i in 0..powers.len() {
+                let Some(index) = Self::index(powers[i].0) else {
+                    continue;
+                };
+                let Some((key, reciprocal)) = self.keys.get(index) else {
+                    continue;
+                };
+                let Some(inverse) = self.ids.get(&(*key, !reciprocal)) else {
+                    continue;
+                };
+                let inverse = format!("{PLACEHOLDER}{inverse}");
+                if let Some(j) = powers.iter().position(|(name, _)| *name == inverse) {
+                    let common = powers[i].1.min(powers[j].1);
+                    powers[i].1 -= common;
+                    powers[j].1 -= common;
+                }
+            }
+            cancelled = cancelled + Polynomial::term(coefficient, Monomial::new(powers));
+        }
+        cancelled
+    }
+
+    /// The atom behind a placeholder name and whether it is a reciprocal.
+    fn get(&self, name: &str) -> Option<&(Expr, bool)> {
+        self.atoms.get(Self::index(name)?)
+    }
+
+    fn index(name: &str) -> Option<usize> {
+        name.strip_prefix(PLACEHOLDER)?.parse::<usize>().ok()
+    }
+
+    /// Expands `expr` into a polynomial over its variables and atoms.
+    pub(super) fn polynomial_of(&mut self, expr: &Expr) -> Polynomial {
+        match expr {
+            Expr::Number(value) => match Rational::from_f64(*value) {
+                Some(value) => Polynomial::constant(value),
+                None => self.placeholder(expr.clone(), false),
+            },
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => {
+                let coefficient = self.polynomial_of(&Expr::Number(*coefficient));
+                let power = if exponent.fract() == 0.0 && exponent.abs() <= u32::MAX as f64 {
+                    let monomial = Polynomial::term(
+                        Rational::ONE,
+                        Monomial::variable(variable, exponent.abs() as u32),
+                    );
+                    if *exponent >= 0.0 {
+                        monomial
+                    } else {
+                        self.placeholder(self.to_expr(&monomial), true)
+                    }
+                } else {
+                    let power = Expr::Monomial {
+                        coefficient: 1.0,
+                        variable: variable.to_owned(),
+                        exponent: *exponent,
+                    };
+                    self.placeholder(power, false)
+                };
+                coefficient * power
+            }
+            Expr::UnaryMinus(inner) => -self.polynomial_of(inner),
+            Expr::BinOp {
+                lhs,
+                op: Op::Add,
+                rhs,
+            } => {
+                let (lhs, rhs) = (self.polynomial_of(lhs), self.polynomial_of(rhs));
+                let sum = lhs.checked_add(&rhs);
+                self.checked(sum, &lhs, Op::Add, &rhs)
+            }
+            Expr::BinOp {
+                lhs,
+                op: Op::Subtract,
+                rhs,
+            } => {
+                let (lhs, rhs) = (self.polynomial_of(lhs), self.polynomial_of(rhs));
+                let difference = lhs.checked_sub(&rhs);
+                self.checked(difference, &lhs, Op::Subtract, &rhs)
+            }
+            Expr::BinOp {
+                lhs,
+                op: Op::Multiply,
+                rhs,
+            } => {
+                let (lhs, rhs) = (self.polynomial_of(lhs), self.polynomial_of(rhs));
+                let product = lhs.checked_mul(&rhs);
+                self.checked(product, &lhs, Op::Multiply, &rhs)
+            }
+            Expr::BinOp {
+                lhs,
+                op: Op::Divide,
+                rhs,
+            } => {
+                let numerator = self.polynomial_of(lhs);
+                let denominator = self.polynomial_of(rhs);
+                match denominator.constant_value().and_then(|value| value.recip()) {
+                    Some(inverse) => {
+                        let quotient = numerator.checked_mul(&Polynomial::constant(inverse));
+                        self.checked(quotient, &numerator, Op::Divide, &denominator)
+                    }
+                    None => {
+                        let denominator = self.to_expr(&denominator);
+                        numerator * self.placeholder(denominator, true)
+                    }
+                }
+            }
+            Expr::BinOp {
+                lhs,
+                op: Op::Power,
+                rhs,
+            } => {
+                let base = self.polynomial_of(lhs);
+                let exponent = self.polynomial_of(rhs);
+                let integer = exponent
+                    .constant_value()
+                    .filter(|exponent| exponent.is_integer())
+                    .and_then(|exponent| i32::try_from(exponent.numerator()).ok());
+
+                let power = integer
+                    .filter(|exponent| exponent.unsigned_abs() <= MAX_POWER)
+                    .and_then(|exponent| {
+                        Some((exponent, base.checked_pow(exponent.unsigned_abs())?))
+                    });
+                match power {
+                    Some((exponent, power)) => {
+                        if exponent >= 0 {
+                            power
+                        } else if let Some(inverse) =
+                            power.constant_value().and_then(|value| value.recip())
+                        {
+                            Polynomial::constant(inverse)
+                        } else {
+                            let power = self.to_expr(&power);
+                            self.placeholder(power, true)
+                        }
+                    }
+                    _ => {
+                        let power = Expr::BinOp {
+                            lhs: Box::new(self.to_expr(&base)),
+                            op: Op::Power,
+                            rhs: Box::new(self.to_expr(&exponent)),
+                        };
+                        self.placeholder(power, false)
+                    }
+                }
+            }
+            Expr::BinOp { lhs, op, rhs } => {
+                let expr = Expr::BinOp {
+                    lhs: Box::new(expand(lhs)),
+                    op: *op,
+                    rhs: Box::new(expand(rhs)),
+                };
+                self.placeholder(expr, false)
+            }
+            Expr::Function { name, args } => {
+                let expr = Expr::Function {
+                    name: name.to_owned(),
+                    args: args.iter().map(|arg| Box::new(expand(arg))).collect(),
+                };
+                self.placeholder(expr, false)
+            }
+            Expr::List(items) => {
+                let expr = Expr::List(items.iter().map(|item| Box::new(expand(item))).collect());
+                self.placeholder(expr, false)
+            }
+            Expr::Constant { .. } | Expr::Wildcard(_) => self.placeholder(expr.clone(), false),
+        }
+    }
+
+    /// The result of `lhs op rhs`, or the operation kept unexpanded as an
+    /// atom if a coefficient overflowed.
+    fn checked(
+        &mut self,
+        result: Option<Polynomial>,
+        lhs: &Polynomial,
+        op: Op,
+        rhs: &Polynomial,
+    ) -> Polynomial {
+        match result {
+            Some(poly) => poly,
+            None => {
+                let expr = Expr::BinOp {
+                    lhs: Box::new(self.to_expr(lhs)),
+                    op,
+                    rhs: Box::new(self.to_expr(rhs)),
+                };
+                self.placeholder(expr, false)
+            }
+        }
+    }
+
+    /// Builds a single term. A coefficient of a finite decimal expansion is
+    /// folded into the first variable, e.g. `-2X^(2)`.
+    pub(super) fn term(&self, coefficient: Rational, monomial: &Monomial) -> Expr {
+        let mut variables = Vec::new();
+        let mut numerator = Vec::new();
+        let mut denominator = Vec::new();
+        for (name, power) in monomial.powers() {
+            match self.get(name) {
+                Some((atom, reciprocal)) => {
+                    let factor = match power {
+                        1 => atom.clone(),
+                        power => Expr::BinOp {
+                            lhs: Box::new(atom.clone()),
+                            op: Op::Power,
+                            rhs: Box::new(Expr::Number(power as f64)),
+                        },
+                    };
+                    if *reciprocal {
+                        denominator.push(factor);
+                    } else {
+                        numerator.push(factor);
+                    }
+                }
+                None => variables.push(Expr::Monomial {
+                    coefficient: 1.0,
+                    variable: name.to_owned(),
+                    exponent: power as f64,
+                }),
+            }
+        }
+
+        let mut negate = false;
+        match variables.first_mut() {
+            _ if coefficient == Rational::ONE => (),
+            Some(Expr::Monomial {
+                coefficient: first, ..
+            }) if coefficient.is_decimal() => *first = coefficient.to_f64(),
+            _ if coefficient == -Rational::ONE && monomial.degree() > 0 => negate = true,
+            _ => variables.insert(0, Expr::from(coefficient)),
+        }
+        variables.extend(numerator);
+
+        let product = |factors: Vec<Expr>| {
+            factors.into_iter().reduce(|lhs, rhs| Expr::BinOp {
+                lhs: Box::new(lhs),
+                op: Op::Multiply,
+                rhs: Box::new(rhs),
+            })
+        };
+        let numerator = product(variables).unwrap_or(Expr::Number(1.0));
+        let term = match product(denominator) {
+            Some(denominator) => Expr::BinOp {
+                lhs: Box::new(numerator),
+                op: Op::Divide,
+                rhs: Box::new(denominator),
+            },
+            None => numerator,
+        };
+
+        if negate {
+            Expr::UnaryMinus(Box::new(term))
+        } else {
+            term
+        }
+    }
+
+    /// Converts back to an expression, terms in graded lexicographic order.
+    pub(super) fn to_expr(&self, poly: &Polynomial) -> Expr {
+        sum(
+            poly.terms(MonomialOrder::GrLex)
+                .into_iter()
+                .map(|(monomial, coefficient)| Summand::Term(coefficient, monomial.clone()))
+                .collect(),
+            self,
+        )
+    }
+}
+
+/// A part of a sum: either a signed term or an expression that is always
+/// added.
+pub(super) enum Summand {
+    Term(Rational, Monomial),
+    Expr(Expr),
+}
+
+/// Adds the summands up, subtracting terms with negative coefficients.
+pub(super) fn sum(summands: Vec<Summand>, atoms: &Atoms) -> Expr {
+    let mut summands = summands.into_iter();
+    let first = match summands.next() {
+        Some(Summand::Term(coefficient, monomial)) => atoms.term(coefficient, &monomial),
+        Some(Summand::Expr(expr)) => expr,
+        None => return Expr::Number(0.0),
+    };
+
+    summands.fold(first, |sum, summand| {
+        let (op, rhs) = match summand {
+            Summand::Term(coefficient, monomial) if coefficient.is_negative() => {
+                (Op::Subtract, atoms.term(-coefficient, &monomial))
+            }
+            Summand::Term(coefficient, monomial) => (Op::Add, atoms.term(coefficient, &monomial)),
+            Summand::Expr(expr) => (Op::Add, expr),
+        };
+        Expr::BinOp {
+            lhs: Box::new(sum),
+            op,
+            rhs: Box::new(rhs),
+        }
+    })
+}
+
+/// Distributes products over sums and multiplies out integer powers, e.g.
+/// `(X+1)^2 - X^2` becomes `2X + 1`. Non-polynomial parts such as functions
+/// are kept as factors, with their arguments expanded.
+/// Parts whose coefficients would overflow are left unexpanded.
+pub fn expand(expr: &Expr) -> Expr {
+    if let Expr::BinOp { lhs, op, rhs } = expr {
+        if op.is_relation() {
+            return Expr::BinOp {
+                lhs: Box::new(expand(lhs)),
+                op: *op,
+                rhs: Box::new(expand(rhs)),
+            };
+        }
+    }
+
+    let mut atoms = Atoms::new();
+    let poly = atoms.polynomial_of(expr);
+    atoms.to_expr(&poly)
+}
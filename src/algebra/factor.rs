@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+use crate::parser::Expr;
+use crate::polynomial::Polynomial;
+
+/// Factors a polynomial expression over the integers, such as
+/// `X^2 - 5X + 6` into `(X-2)*(X-3)`. Both sides of an equation or an
+/// inequality are factored separately.
+pub fn factor(expr: &Expr) -> Result<Expr> {
+    if let Expr::BinOp { lhs, op, rhs } = expr {
+        if op.is_relation() {
+            return Ok(Expr::BinOp {
+                lhs: Box::new(factor(lhs)?),
+                op: *op,
+                rhs: Box::new(factor(rhs)?),
+            });
+        }
+    }
+
+    Ok(Polynomial::from_expr(expr)?.factor().to_expr())
+}
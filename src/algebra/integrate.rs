@@ -0,0 +1,557 @@
+use std::f64::consts::PI;
+
+use anyhow::{bail, Result};
+
+use crate::error::{NumericError, SymbolicError};
+use crate::math::Rational;
+use crate::numeric_evaluator::{evaluate_expr, nintegrate};
+use crate::parser::{func, num, pow, var, Expr, Op, Optimize};
+use crate::polynomial::{Monomial, PartialFraction, Polynomial, RationalFunction};
+use crate::solver::{contains_variable, roots, solve_polynomial, Solution};
+
+use super::diff::diff;
+use super::substitute::{replace, substitute};
+
+/// How deeply substitution and integration by parts may nest.
+const MAX_DEPTH: usize = 6;
+
+/// Stands in for the inner function during u-substitution. The parser
+/// never produces it, so it can't clash with a real variable.
+const PLACEHOLDER: &str = "#u";
+
+/// Finds an antiderivative of `expr` in `variable`, leaving out the
+/// constant of integration. Trigonometric functions work in degrees like
+/// the evaluator.
+pub fn integrate(expr: &Expr, variable: &str) -> Result<Expr> {
+    match antiderivative(expr, variable, 0) {
+        Some(integral) => Ok(integral.optimize_expression()),
+        None => bail!(SymbolicError::NoAntiderivative(expr.to_string())),
+    }
+}
+
+/// Integrates `expr` from `lower` to `upper` through an antiderivative,
+/// falling back to numeric quadrature when there is none, it can't be
+/// evaluated at numeric bounds or either has a pole in between. Quadrature
+/// then reports whether the integral diverges.
+pub fn integrate_definite(expr: &Expr, variable: &str, lower: &Expr, upper: &Expr) -> Result<Expr> {
+    let numeric_bounds = evaluate_expr(lower).ok().zip(evaluate_expr(upper).ok());
+    let integral = antiderivative(expr, variable, 0).filter(|integral| match numeric_bounds {
+        Some((a, b)) => !has_pole(expr, variable, a, b) && !has_pole(integral, variable, a, b),
+        None => true,
+    });
+    if let Some(integral) = integral {
+        let difference =
+            substitute(&integral, variable, upper) - substitute(&integral, variable, lower);
+        match evaluate_expr(&difference) {
+            Ok(value) if value.is_finite() => return Ok(num(value)),
+            _ if numeric_bounds.is_none() => return Ok(difference.optimize_expression()),
+            _ => {}
+        }
+    }
+
+    let Some((a, b)) = numeric_bounds else {
+        let bound = if evaluate_expr(lower).is_err() {
+            lower
+        } else {
+            upper
+        };
+        bail!(NumericError::NonNumericBound(bound.to_string()));
+    };
+    Ok(num(nintegrate(expr, variable, a, b)?.value))
+}
+
+/// Whether `expr` has a pole from `a` to `b`, found as a real root of a
+/// divisor, base of a negative power, logarithm argument or cosine of a
+/// tangent argument. Divisors that aren't polynomials are searched
+/// numerically, and count as a pole when that fails.
+fn has_pole(expr: &Expr, x: &str, a: f64, b: f64) -> bool {
+    let (a, b) = (a.min(b), a.max(b));
+    let vanishes = |u: &Expr| match solve_polynomial(u, &num(0.0), x) {
+        Ok(Solution::Finite(roots)) => roots.iter().any(|root| {
+            evaluate_expr(root).is_ok_and(|root| root.is_finite() && a <= root && root <= b)
+        }),
+        Ok(Solution::All) => true,
+        Ok(Solution::Empty | Solution::Intervals(_)) => false,
+        Err(_) => roots(u, x, a, b).map_or(true, |roots| !roots.is_empty()),
+    };
+
+    match expr {
+        Expr::Monomial {
+            variable, exponent, ..
+        } => variable == x && *exponent < 0.0 && a <= 0.0 && 0.0 <= b,
+        Expr::UnaryMinus(inner) => has_pole(inner, x, a, b),
+        Expr::BinOp { lhs, op, rhs } => {
+            let singular = match op {
+                Op::Divide => vanishes(rhs),
+                Op::Power => evaluate_expr(rhs).is_ok_and(|n| n < 0.0) && vanishes(lhs),
+                _ => false,
+            };
+            singular || has_pole(lhs, x, a, b) || has_pole(rhs, x, a, b)
+        }
+        Expr::Function { name, args } => {
+            let singular = match (name.as_str(), args.as_slice()) {
+                ("ln" | "log" | "log2" | "log10", [.., arg]) => vanishes(arg),
+                ("tan", [arg]) => vanishes(&func("cos", [arg.as_ref().clone()])),
+                _ => false,
+            };
+            singular || args.iter().any(|arg| has_pole(arg, x, a, b))
+        }
+        Expr::List(items) => items.iter().any(|item| has_pole(item, x, a, b)),
+        Expr::Number(_) | Expr::Constant { .. } | Expr::Wildcard(_) => false,
+    }
+}
+
+fn antiderivative(expr: &Expr, x: &str, depth: usize) -> Option<Expr> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    if !contains_variable(expr, x) {
+        return Some(expr.clone() * var(x));
+    }
+
+    match expr {
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } => {
+            return Some(if *exponent == -1.0 {
+                num(*coefficient) * ln_abs(var(variable))
+            } else {
+                Expr::Monomial {
+                    coefficient: coefficient / (exponent + 1.0),
+                    variable: variable.to_owned(),
+                    exponent: exponent + 1.0,
+                }
+            })
+        }
+        Expr::UnaryMinus(inner) => return Some(-antiderivative(inner, x, depth)?),
+        Expr::BinOp { lhs, op, rhs } => match op {
+            Op::Add => {
+                return Some(antiderivative(lhs, x, depth)? + antiderivative(rhs, x, depth)?)
+            }
+            Op::Subtract => {
+                return Some(antiderivative(lhs, x, depth)? - antiderivative(rhs, x, depth)?)
+            }
+            Op::Multiply if !contains_variable(lhs, x) => {
+                return Some(lhs.as_ref().clone() * antiderivative(rhs, x, depth)?)
+            }
+            Op::Multiply if !contains_variable(rhs, x) => {
+                return Some(antiderivative(lhs, x, depth)? * rhs.as_ref().clone())
+            }
+            Op::Divide if !contains_variable(rhs, x) => {
+                return Some(antiderivative(lhs, x, depth)? / rhs.as_ref().clone())
+            }
+            op if op.is_relation() => return None,
+            _ => {}
+        },
+        _ => {}
+    }
+
+    // Constant factors anywhere in a product, e.g. in a divisor
+    let (constant, dependent): (Vec<Expr>, Vec<Expr>) = factors(expr)
+        .into_iter()
+        .filter(|factor| *factor != Expr::Number(1.0))
+        .partition(|factor| !contains_variable(factor, x));
+    if !constant.is_empty() {
+        return Some(product(constant) * antiderivative(&product(dependent), x, depth)?);
+    }
+
+    table(expr, x)
+        .or_else(|| substitution(expr, x, depth))
+        .or_else(|| polynomial(expr, x))
+        .or_else(|| rational(expr, x))
+        .or_else(|| by_parts(expr, x, depth))
+}
+
+/// Splits `u` into `a*x + b` when it is linear in `x`.
+fn linear(u: &Expr, x: &str) -> Option<(Expr, Expr)> {
+    let polynomial = Polynomial::from_expr(u).ok()?;
+    (polynomial.degree_in(x) == Some(1)).then(|| {
+        (
+            polynomial.coefficient_in(x, 1).to_expr(),
+            polynomial.coefficient_in(x, 0).to_expr(),
+        )
+    })
+}
+
+/// `ln|u|`, the antiderivative of `1/u` on both sides of its pole.
+fn ln_abs(u: Expr) -> Expr {
+    func("ln", [func("abs", [u])])
+}
+
+/// The factor `180/pi` that integrating a trigonometric function of
+/// degrees brings in.
+fn radians() -> Expr {
+    180 / Expr::Constant {
+        name: "pi".to_string(),
+        value: PI,
+    }
+}
+
+/// Standard integrals of functions and powers of a linear argument
+/// `a*x + b`, divided by `a`.
+fn table(expr: &Expr, x: &str) -> Option<Expr> {
+    let (integral, inner) = match expr {
+        Expr::Function { name, args } if args.len() == 1 => {
+            let u = args[0].as_ref().clone();
+            let integral = match name.as_str() {
+                "sin" => -(func("cos", [u.clone()]) * radians()),
+                "cos" => func("sin", [u.clone()]) * radians(),
+                "tan" => -(ln_abs(func("cos", [u.clone()])) * radians()),
+                "sqrt" => 2 * pow(func("sqrt", [u.clone()]), 3) / 3,
+                "cbrt" => 3 * pow(func("cbrt", [u.clone()]), 4) / 4,
+                "ln" => u.clone() * func("ln", [u.clone()]) - u.clone(),
+                "exp" => func("exp", [u.clone()]),
+                // u*log_b(u) - u/ln(b)
+                "log" | "log10" => {
+                    u.clone() * func(name, [u.clone()]) - u.clone() / func("ln", [num(10.0)])
+                }
+                "log2" => {
+                    u.clone() * func("log2", [u.clone()]) - u.clone() / func("ln", [num(2.0)])
+                }
+                "abs" => u.clone() * func("abs", [u.clone()]) / 2,
+                "sign" => func("abs", [u.clone()]),
+                _ => return None,
+            };
+            (integral, u)
+        }
+        Expr::Function { name, args } if name == "pow" && args.len() == 2 => {
+            return table(&pow(args[0].as_ref().clone(), args[1].as_ref().clone()), x)
+        }
+        // log(b, u) = ln(u)/ln(b) for a constant base
+        Expr::Function { name, args }
+            if name == "log" && args.len() == 2 && !contains_variable(&args[0], x) =>
+        {
+            let (base, u) = (args[0].as_ref().clone(), args[1].as_ref().clone());
+            let integral =
+                u.clone() * func("log", [base.clone(), u.clone()]) - u.clone() / func("ln", [base]);
+            (integral, u)
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Power,
+            rhs,
+        } => {
+            let (base, exponent) = (lhs.as_ref().clone(), rhs.as_ref().clone());
+            if !contains_variable(&exponent, x) {
+                // u^n = u^(n+1)/(n+1), or ln|u| for n = -1
+                let integral = match evaluate_expr(&exponent) {
+                    Ok(-1.0) => ln_abs(base.clone()),
+                    Ok(n) => pow(base.clone(), num(n + 1.0)) / num(n + 1.0),
+                    Err(_) => pow(base.clone(), exponent.clone() + 1) / (exponent + 1),
+                };
+                (integral, base)
+            } else if !contains_variable(&base, x) {
+                // c^u = c^u/ln(c)
+                let integral = match &base {
+                    Expr::Constant { name, .. } if name == "e" => pow(base, exponent.clone()),
+                    _ => pow(base.clone(), exponent.clone()) / func("ln", [base]),
+                };
+                (integral, exponent)
+            } else {
+                return None;
+            }
+        }
+        // c/u = c*ln|u|
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } if !contains_variable(lhs, x) => {
+            let integral = lhs.as_ref().clone() * ln_abs(rhs.as_ref().clone());
+            (integral, rhs.as_ref().clone())
+        }
+        _ => return None,
+    };
+
+    let (a, _) = linear(&inner, x)?;
+    Some(integral / a)
+}
+
+/// The factors of a product, with divisors as reciprocals `1/d`.
+fn factors(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => {
+            let mut factors = factors(lhs);
+            factors.extend(self::factors(rhs));
+            factors
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } => {
+            let mut factors = factors(lhs);
+            factors.extend(self::factors(rhs).into_iter().map(|factor| 1 / factor));
+            factors
+        }
+        expr => vec![expr.clone()],
+    }
+}
+
+fn product(factors: Vec<Expr>) -> Expr {
+    factors
+        .into_iter()
+        .reduce(|product, factor| product * factor)
+        .unwrap_or(num(1.0))
+}
+
+/// `a / b` when it doesn't depend on `x`.
+fn constant_ratio(a: &Expr, b: &Expr, x: &str) -> Option<Expr> {
+    if let (Ok(a), Ok(b)) = (
+        RationalFunction::from_expr(a),
+        RationalFunction::from_expr(b),
+    ) {
+        let numerator = a.numerator * b.denominator;
+        let (ratio, _) = RationalFunction::new(numerator, a.denominator * b.numerator)
+            .ok()?
+            .cancel();
+        let depends = |p: &Polynomial| p.degree_in(x).unwrap_or(0) > 0;
+        return (!depends(&ratio.numerator) && !depends(&ratio.denominator))
+            .then(|| ratio.to_expr());
+    }
+
+    let split = |expr: &Expr| -> (Vec<Expr>, Vec<String>) {
+        let (constant, mut dependent): (Vec<Expr>, Vec<Expr>) = factors(expr)
+            .into_iter()
+            .partition(|factor| !contains_variable(factor, x));
+        dependent.sort_by_key(|factor| factor.to_string());
+        (constant, dependent.iter().map(|f| f.to_string()).collect())
+    };
+    let (a_constant, a_dependent) = split(a);
+    let (b_constant, b_dependent) = split(b);
+    (a_dependent == b_dependent).then(|| product(a_constant) / product(b_constant))
+}
+
+/// Every subexpression that could serve as the inner function `u` of a
+/// substitution: function arguments, bases and exponents of powers, and
+/// the factors themselves. Linear ones are left to the table.
+fn candidates(expr: &Expr, x: &str, found: &mut Vec<Expr>) {
+    let mut push = |u: &Expr| {
+        if contains_variable(u, x) && linear(u, x).is_none() && !found.contains(u) {
+            found.push(u.clone());
+        }
+    };
+    match expr {
+        Expr::Function { args, .. } => {
+            push(expr);
+            for arg in args {
+                push(arg);
+            }
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Power,
+            rhs,
+        } => {
+            push(expr);
+            push(lhs);
+            push(rhs);
+        }
+        _ => {}
+    }
+
+    match expr {
+        Expr::UnaryMinus(inner) => candidates(inner, x, found),
+        Expr::BinOp { lhs, rhs, .. } => {
+            candidates(lhs, x, found);
+            candidates(rhs, x, found);
+        }
+        Expr::Function { args, .. } => {
+            for arg in args {
+                candidates(arg, x, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// u-substitution: writes the integrand as `f(u) * c*u'` for a constant
+/// `c` and integrates `f` instead.
+fn substitution(expr: &Expr, x: &str, depth: usize) -> Option<Expr> {
+    let mut found = Vec::new();
+    candidates(expr, x, &mut found);
+
+    let placeholder = var(PLACEHOLDER);
+    for u in found {
+        let Ok(du) = diff(&u, x) else {
+            continue;
+        };
+        let replaced = replace(expr, &u, &placeholder);
+        let (outer, rest): (Vec<Expr>, Vec<Expr>) = factors(&replaced)
+            .into_iter()
+            .partition(|factor| !contains_variable(factor, x));
+        let outer = product(outer);
+        if !contains_variable(&outer, PLACEHOLDER) {
+            continue;
+        }
+
+        let Some(ratio) = constant_ratio(&product(rest), &du, x) else {
+            continue;
+        };
+        if let Some(integral) = antiderivative(&outer, PLACEHOLDER, depth + 1) {
+            return Some(ratio * substitute(&integral, PLACEHOLDER, &u));
+        }
+    }
+    None
+}
+
+/// Polynomials with integer powers of sums, integrated term by term once
+/// multiplied out.
+fn polynomial(expr: &Expr, x: &str) -> Option<Expr> {
+    Some(polynomial_integral(&Polynomial::from_expr(expr).ok()?, x).to_expr())
+}
+
+fn polynomial_integral(polynomial: &Polynomial, x: &str) -> Polynomial {
+    let mut integral = Polynomial::zero();
+    for power in 0..=polynomial.degree_in(x).unwrap_or(0) {
+        let scale = Rational::ONE / Rational::integer(power as i128 + 1);
+        integral = integral
+            + polynomial.coefficient_in(x, power)
+                * Polynomial::term(scale, Monomial::variable(x, power + 1));
+    }
+    integral
+}
+
+/// Rational functions through their partial fractions.
+fn rational(expr: &Expr, x: &str) -> Option<Expr> {
+    let decomposition = RationalFunction::from_expr(expr).ok()?.apart(x).ok()?;
+    let mut integral = polynomial_integral(&decomposition.polynomial, x).to_expr();
+    for fraction in &decomposition.fractions {
+        integral = integral + fraction_integral(fraction, x)?;
+    }
+    Some(integral)
+}
+
+fn fraction_integral(fraction: &PartialFraction, x: &str) -> Option<Expr> {
+    let PartialFraction {
+        numerator,
+        factor,
+        power,
+    } = fraction;
+    let k = *power as f64;
+    let q = factor.to_expr();
+
+    match factor.degree_in(x)? {
+        // n/(a*x + b)^k
+        1 => {
+            let a = Expr::from(factor.coefficient_in(x, 1).constant_value()?);
+            let n = numerator.to_expr();
+            Some(if *power == 1 {
+                n * ln_abs(q) / a
+            } else {
+                n * pow(q, 1.0 - k) / (a * (1.0 - k))
+            })
+        }
+        // (B*x + C)/(a*x^2 + b*x + c)^k, split into a multiple of Q'/Q^k
+        // and a multiple of 1/Q^k
+        2 => {
+            let coefficient = |p: u32| factor.coefficient_in(x, p).constant_value();
+            let (a, b) = (coefficient(2)?, coefficient(1)?);
+            let big_b = numerator.coefficient_in(x, 1);
+            let big_c = numerator.coefficient_in(x, 0);
+
+            let log_part = Polynomial::constant(Rational::ONE / (Rational::integer(2) * a));
+            let rest = big_c - big_b.clone() * Polynomial::constant(b / (Rational::integer(2) * a));
+            // Q stays positive without real roots and a > 0
+            let positive = a > Rational::ZERO && b * b < Rational::integer(4) * a * coefficient(0)?;
+            let derivative_part = if *power == 1 && positive {
+                func("ln", [q.clone()])
+            } else if *power == 1 {
+                ln_abs(q.clone())
+            } else {
+                pow(q.clone(), 1.0 - k) / (1.0 - k)
+            };
+
+            Some(
+                (big_b * log_part).to_expr() * derivative_part
+                    + rest.to_expr() * reciprocal_quadratic(factor, x, *power)?,
+            )
+        }
+        _ => None,
+    }
+}
+
+/// The integral of `1/Q^k` for a quadratic `Q = a*x^2 + b*x + c` without
+/// rational roots, reducing `k` one step at a time.
+fn reciprocal_quadratic(factor: &Polynomial, x: &str, power: u32) -> Option<Expr> {
+    let coefficient = |p: u32| factor.coefficient_in(x, p).constant_value();
+    let (a, b, c) = (coefficient(2)?, coefficient(1)?, coefficient(0)?);
+    let delta = Rational::integer(4) * a * c - b * b;
+    let q = factor.to_expr();
+    let slope = (Polynomial::constant(Rational::integer(2) * a) * Polynomial::variable(x)
+        + Polynomial::constant(b))
+    .to_expr();
+
+    if power == 1 {
+        let root = square_root(delta.abs());
+        return Some(if delta.is_negative() {
+            // 1/sqrt(-D) * ln|(2a*x + b - sqrt(-D))/(2a*x + b + sqrt(-D))|
+            let ratio = (slope.clone() - root.clone()) / (slope + root.clone());
+            ln_abs(ratio) / root
+        } else {
+            // 2/sqrt(D) * atan((2a*x + b)/sqrt(D)), atan giving degrees
+            2 * func("atan", [slope / root.clone()]) / (root * radians())
+        });
+    }
+
+    // J_k = (2a*x + b)/((k-1)*D*Q^(k-1)) + 2(2k-3)*a/((k-1)*D) * J_(k-1)
+    let k = Rational::integer(power as i128);
+    let scale = (k - Rational::ONE) * delta;
+    let first = slope / (Expr::from(scale) * pow(q, (power - 1) as f64));
+    let factor_next =
+        Rational::integer(2) * (Rational::integer(2) * k - Rational::integer(3)) * a / scale;
+    Some(first + Expr::from(factor_next) * reciprocal_quadratic(factor, x, power - 1)?)
+}
+
+/// `sqrt(r)`, exact when `r` is the square of a rational.
+fn square_root(r: Rational) -> Expr {
+    let root = |n: i128| {
+        let root = (n as f64).sqrt().round() as i128;
+        (root * root == n).then_some(root)
+    };
+    match (root(r.numerator()), root(r.denominator())) {
+        (Some(numerator), Some(denominator)) => {
+            Expr::from(Rational::new(numerator, denominator).expect("non-zero denominator"))
+        }
+        _ => func("sqrt", [Expr::from(r)]),
+    }
+}
+
+/// Integration by parts for a polynomial times a function. Logarithms and
+/// arctangents are differentiated, everything else is integrated.
+fn by_parts(expr: &Expr, x: &str, depth: usize) -> Option<Expr> {
+    let (polynomials, others): (Vec<Expr>, Vec<Expr>) = factors(expr)
+        .into_iter()
+        .partition(|factor| Polynomial::from_expr(factor).is_ok());
+    let [other] = others.as_slice() else {
+        return None;
+    };
+    let p = product(polynomials);
+
+    let differentiated = match other {
+        Expr::Function { name, .. } => {
+            matches!(name.as_str(), "ln" | "log" | "log2" | "log10" | "atan")
+        }
+        _ => false,
+    };
+    if differentiated {
+        // ∫p*g = g*∫p - ∫(∫p)*g'
+        let integral = polynomial(&p, x)?;
+        let rest = antiderivative(&(integral.clone() * diff(other, x).ok()?), x, depth + 1)?;
+        Some(other.clone() * integral - rest)
+    } else {
+        // ∫p*g = p*∫g - ∫p'*∫g
+        if Polynomial::from_expr(&p).ok()?.degree_in(x).unwrap_or(0) == 0 {
+            return None;
+        }
+        let integral = antiderivative(other, x, depth + 1)?;
+        let rest = antiderivative(&(diff(&p, x).ok()? * integral.clone()), x, depth + 1)?;
+        Some(p * integral - rest)
+    }
+}
@@ -0,0 +1,490 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::error::SymbolicError;
+use crate::math::round;
+use crate::numeric_evaluator::{evaluate_expr, function_of};
+use crate::parser::{func, num, pow, var, Expr, Op};
+use crate::solver::contains_variable;
+
+use super::diff::diff;
+use super::series::{fold, is_smooth, Expander};
+use super::substitute::substitute;
+
+/// Terms expanded before dividing, see [`Expander::new`].
+const PRECISION: i32 = 4;
+
+/// How many times L'Hôpital's rule is applied in a row.
+const MAX_DEPTH: usize = 6;
+
+/// Number of points sampled on each side, at distances `0.1/2^k`.
+const SAMPLES: i32 = 24;
+
+/// The first distance from the point that gets sampled.
+const FIRST_STEP: f64 = 0.1;
+
+/// Sampled values beyond this are taken to diverge.
+const DIVERGENCE: f64 = 1e8;
+
+/// Digits kept from a sampled estimate.
+const DIGITS: u32 = 9;
+
+/// The side a limit is approached from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Both,
+    /// From larger values, `X -> a+`.
+    Above,
+    /// From smaller values, `X -> a-`.
+    Below,
+}
+
+impl Direction {
+    /// 1 for [`Direction::Above`], -1 for [`Direction::Below`] and 0 for both
+    /// sides.
+    pub fn from_number(direction: f64) -> Option<Direction> {
+        match direction {
+            0.0 => Some(Direction::Both),
+            1.0 => Some(Direction::Above),
+            -1.0 => Some(Direction::Below),
+            _ => None,
+        }
+    }
+}
+
+/// The value an expression tends to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Limit {
+    Value(Expr),
+    Infinity,
+    NegativeInfinity,
+    /// The sides disagree, or the expression keeps oscillating.
+    DoesNotExist,
+}
+
+impl Limit {
+    /// `None` when the limit does not exist.
+    pub fn to_expr(&self) -> Option<Expr> {
+        let infinity = || Expr::Constant {
+            name: "inf".to_string(),
+            value: f64::INFINITY,
+        };
+        match self {
+            Limit::Value(value) => Some(value.clone()),
+            Limit::Infinity => Some(infinity()),
+            Limit::NegativeInfinity => Some(Expr::UnaryMinus(Box::new(infinity()))),
+            Limit::DoesNotExist => None,
+        }
+    }
+
+    fn infinity(positive: bool) -> Limit {
+        if positive {
+            Limit::Infinity
+        } else {
+            Limit::NegativeInfinity
+        }
+    }
+
+    /// The value on the extended real line, `None` when it does not exist or
+    /// can't be evaluated.
+    fn approximate(&self) -> Option<f64> {
+        match self {
+            Limit::Value(value) => evaluate_expr(value).ok().filter(|value| value.is_finite()),
+            Limit::Infinity => Some(f64::INFINITY),
+            Limit::NegativeInfinity => Some(f64::NEG_INFINITY),
+            Limit::DoesNotExist => None,
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Value(value) => write!(f, "{}", value.to_string()),
+            Limit::Infinity => write!(f, "inf"),
+            Limit::NegativeInfinity => write!(f, "-inf"),
+            Limit::DoesNotExist => write!(f, "does not exist"),
+        }
+    }
+}
+
+/// Where the variable goes.
+enum Point {
+    Finite(Expr),
+    /// `inf` for a positive sign, `-(inf)` otherwise.
+    Infinite(f64),
+}
+
+/// A variable approaching a point.
+struct Approach<'a> {
+    variable: &'a str,
+    point: Point,
+    direction: Direction,
+}
+
+impl Approach<'_> {
+    /// The same limit with the variable going to a finite point, since
+    /// `X -> inf` is `1/X -> 0` from above.
+    fn finite(&self, expr: &Expr) -> (Expr, Expr, Direction) {
+        match &self.point {
+            Point::Finite(point) => (expr.clone(), point.clone(), self.direction),
+            Point::Infinite(sign) => {
+                let inverse = num(*sign) / var(self.variable);
+                let expr = substitute(expr, self.variable, &inverse);
+                (expr, num(0.0), Direction::Above)
+            }
+        }
+    }
+
+    /// The limit from the leading term `c*t^v` of the series around the
+    /// point: 0 for a positive power, `c` for a constant and infinite for a
+    /// pole, whose sign depends on the side for odd powers. `None` when
+    /// there is no series or the sign of `c` is unknown.
+    fn by_series(&self, expr: &Expr) -> Option<Limit> {
+        let (expr, point, direction) = self.finite(expr);
+        // Some precision to spare so that dividing by `X^k` leaves a term
+        let mut expander = Expander::new(self.variable, &point, PRECISION);
+        let series = expander.expand(&expr, 1).ok()?;
+        let Some((power, coefficient)) = expander.leading_term(&series) else {
+            return Some(Limit::Value(num(0.0)));
+        };
+        if power > 0 {
+            return Some(Limit::Value(num(0.0)));
+        }
+        if power == 0 {
+            return Some(Limit::Value(coefficient));
+        }
+
+        let sign = evaluate_expr(&coefficient).ok()?;
+        if sign == 0.0 || !sign.is_finite() {
+            return None;
+        }
+        let odd = power % 2 != 0;
+        Some(match direction {
+            Direction::Both if odd => Limit::DoesNotExist,
+            Direction::Below if odd => Limit::infinity(sign < 0.0),
+            _ => Limit::infinity(sign > 0.0),
+        })
+    }
+
+    /// L'Hôpital's rule for `numerator/denominator`, when both tend to 0 or
+    /// both to infinity.
+    fn by_lhopital(
+        &self,
+        numerator: &Expr,
+        denominator: &Expr,
+        depth: usize,
+    ) -> Result<Option<Limit>> {
+        if depth >= MAX_DEPTH {
+            return Ok(None);
+        }
+        let quotient = diff(numerator, self.variable)? / diff(denominator, self.variable)?;
+        match self.limit(&quotient, depth + 1)? {
+            Limit::DoesNotExist => Ok(None),
+            limit => Ok(Some(limit)),
+        }
+    }
+
+    /// `u^v` from the limits of `u` and `v`, going through `e^(v*ln(u))`
+    /// for the forms `1^inf`, `0^0` and `inf^0`.
+    fn power(&self, u: &Expr, v: &Expr, depth: usize) -> Result<Option<Limit>> {
+        let (base, exponent) = (self.limit(u, depth)?, self.limit(v, depth)?);
+        let (Some(a), Some(b)) = (base.approximate(), exponent.approximate()) else {
+            return Ok(None);
+        };
+        let limit = match (a, b) {
+            (a, b)
+                if (a == 1.0 && b.is_infinite()) || (a == 0.0 || a.is_infinite()) && b == 0.0 =>
+            {
+                let exponent = v.clone() * func("ln", [u.clone()]);
+                match self.limit(&exponent, depth)? {
+                    Limit::Value(value) => Some(Limit::Value(fold(&pow(
+                        Expr::Constant {
+                            name: "e".to_string(),
+                            value: std::f64::consts::E,
+                        },
+                        value,
+                    )))),
+                    Limit::Infinity => Some(Limit::Infinity),
+                    Limit::NegativeInfinity => Some(Limit::Value(num(0.0))),
+                    Limit::DoesNotExist => None,
+                }
+            }
+            (a, b) if a.is_finite() && b.is_finite() => {
+                if a < 0.0 && b.fract() != 0.0 || a == 0.0 && b < 0.0 {
+                    None
+                } else {
+                    let (Limit::Value(a), Limit::Value(b)) = (base, exponent) else {
+                        unreachable!("finite limits are values")
+                    };
+                    Some(Limit::Value(fold(&pow(a, b))))
+                }
+            }
+            (a, _) if a < 0.0 => None,
+            // Either a base above 1 or an exponent at infinity
+            (a, b) if (a > 1.0) == (b > 0.0) => Some(Limit::Infinity),
+            _ => Some(Limit::Value(num(0.0))),
+        };
+        Ok(limit)
+    }
+
+    /// A builtin function of an argument tending to `inner`.
+    fn function(&self, name: &str, inner: Limit) -> Option<Limit> {
+        let value = inner.approximate()?;
+        if value.is_finite() {
+            let Limit::Value(inner) = inner else {
+                return None;
+            };
+            let result = func(name, [inner]);
+            let continuous =
+                is_smooth(name, value) && evaluate_expr(&result).is_ok_and(f64::is_finite);
+            return match name {
+                "ln" if value == 0.0 => Some(Limit::NegativeInfinity),
+                _ => continuous.then(|| Limit::Value(fold(&result))),
+            };
+        }
+
+        let positive = value > 0.0;
+        match name {
+            "sqrt" | "ln" if positive => Some(Limit::Infinity),
+            "cbrt" | "floor" | "ceil" | "round" | "trunc" => Some(Limit::infinity(positive)),
+            "abs" => Some(Limit::Infinity),
+            "sign" => Some(Limit::Value(num(value.signum()))),
+            "atan" => Some(Limit::Value(num(90.0 * value.signum()))),
+            _ => None,
+        }
+    }
+
+    /// Limit laws: the limits of the parts combine unless they form an
+    /// indeterminate form, which goes to L'Hôpital's rule.
+    fn by_parts(&self, expr: &Expr, depth: usize) -> Result<Option<Limit>> {
+        let (lhs, op, rhs) = match expr {
+            Expr::UnaryMinus(inner) => {
+                return Ok(match self.limit(inner, depth)? {
+                    Limit::Value(value) => Some(Limit::Value(fold(&-value))),
+                    Limit::Infinity => Some(Limit::NegativeInfinity),
+                    Limit::NegativeInfinity => Some(Limit::Infinity),
+                    Limit::DoesNotExist => None,
+                });
+            }
+            Expr::Function { name, args } if name == "pow" && args.len() == 2 => {
+                return self.power(&args[0], &args[1], depth);
+            }
+            Expr::Function { name, args } if args.len() == 1 => {
+                let inner = self.limit(&args[0], depth)?;
+                return Ok(self.function(name, inner));
+            }
+            Expr::BinOp {
+                op: Op::Power,
+                lhs,
+                rhs,
+            } => return self.power(lhs, rhs, depth),
+            Expr::BinOp { lhs, op, rhs } => (lhs.as_ref(), *op, rhs.as_ref()),
+            _ => return Ok(None),
+        };
+        let (left, right) = (self.limit(lhs, depth)?, self.limit(rhs, depth)?);
+        let (a, b) = (left.approximate(), right.approximate());
+
+        // Squeezed between bounds that tend to 0, as in `X*sin(1/X)`
+        let vanishes = match op {
+            Op::Multiply => a == Some(0.0) && is_bounded(rhs) || b == Some(0.0) && is_bounded(lhs),
+            Op::Divide => b.is_some_and(f64::is_infinite) && is_bounded(lhs),
+            _ => false,
+        };
+        if vanishes {
+            return Ok(Some(Limit::Value(num(0.0))));
+        }
+        let (Some(a), Some(b)) = (a, b) else {
+            return Ok(None);
+        };
+
+        if a.is_finite() && b.is_finite() {
+            let (Limit::Value(a_expr), Limit::Value(b_expr)) = (left, right) else {
+                unreachable!("finite limits are values")
+            };
+            return match op {
+                Op::Divide if b == 0.0 && a == 0.0 => self.by_lhopital(lhs, rhs, depth),
+                // A pole whose side the series didn't settle
+                Op::Divide if b == 0.0 => Ok(None),
+                Op::Add | Op::Subtract | Op::Multiply | Op::Divide => {
+                    let value = Expr::BinOp {
+                        lhs: Box::new(a_expr),
+                        op,
+                        rhs: Box::new(b_expr),
+                    };
+                    Ok(Some(Limit::Value(fold(&value))))
+                }
+                _ => Ok(None),
+            };
+        }
+
+        let limit = match op {
+            Op::Add if a + b == 0.0 || (a + b).is_nan() => None,
+            Op::Add => Some(Limit::infinity(a + b > 0.0)),
+            Op::Subtract if (a - b).is_nan() => None,
+            Op::Subtract => Some(Limit::infinity(a - b > 0.0)),
+            // 0*inf, as a quotient with both orders of the factors
+            Op::Multiply if a == 0.0 || b == 0.0 => {
+                let (zero, infinite) = if a == 0.0 { (lhs, rhs) } else { (rhs, lhs) };
+                let reciprocal = |expr: &Expr| num(1.0) / expr.clone();
+                match self.by_lhopital(infinite, &reciprocal(zero), depth)? {
+                    Some(limit) => Some(limit),
+                    None => self.by_lhopital(zero, &reciprocal(infinite), depth)?,
+                }
+            }
+            Op::Multiply => Some(Limit::infinity((a > 0.0) == (b > 0.0))),
+            Op::Divide if a.is_infinite() && b.is_infinite() => {
+                self.by_lhopital(lhs, rhs, depth)?
+            }
+            Op::Divide if b.is_infinite() => Some(Limit::Value(num(0.0))),
+            // Infinity over a value that may vanish on either side
+            Op::Divide if b == 0.0 => None,
+            Op::Divide => Some(Limit::infinity((a > 0.0) == (b > 0.0))),
+            _ => None,
+        };
+        Ok(limit)
+    }
+
+    /// Estimates the limit by evaluating the expression closer and closer
+    /// to the point, on both sides unless a direction is given.
+    fn by_sampling(&self, expr: &Expr) -> Result<Limit> {
+        let (expr, point, direction) = self.finite(expr);
+        let point = evaluate_expr(&point)?;
+        let above = || sample(&expr, self.variable, point, 1.0);
+        let below = || sample(&expr, self.variable, point, -1.0);
+        Ok(match direction {
+            Direction::Above => above()?,
+            Direction::Below => below()?,
+            Direction::Both => match (above()?, below()?) {
+                (Limit::Value(a), Limit::Value(b)) => {
+                    let (a_value, b_value) = (evaluate_expr(&a)?, evaluate_expr(&b)?);
+                    if (a_value - b_value).abs() <= 1e-6 * a_value.abs().max(1.0) {
+                        Limit::Value(a)
+                    } else {
+                        Limit::DoesNotExist
+                    }
+                }
+                (above, below) if above == below => above,
+                _ => Limit::DoesNotExist,
+            },
+        })
+    }
+
+    fn limit(&self, expr: &Expr, depth: usize) -> Result<Limit> {
+        if !contains_variable(expr, self.variable) {
+            return Ok(Limit::Value(fold(expr)));
+        }
+        if let Some(limit) = self.by_series(expr) {
+            return Ok(limit);
+        }
+        if let Some(limit) = self.by_parts(expr, depth)? {
+            return Ok(limit);
+        }
+        self.by_sampling(expr)
+    }
+}
+
+/// Whether `expr` stays between fixed bounds wherever it is defined.
+fn is_bounded(expr: &Expr) -> bool {
+    match expr {
+        Expr::Function { name, .. } => {
+            matches!(name.as_str(), "sin" | "cos" | "atan" | "sign" | "fract")
+        }
+        Expr::UnaryMinus(inner) => is_bounded(inner),
+        Expr::BinOp {
+            lhs,
+            op: Op::Add | Op::Subtract | Op::Multiply,
+            rhs,
+        } => is_bounded(lhs) && is_bounded(rhs),
+        expr => !matches!(expr, Expr::Monomial { .. } | Expr::BinOp { .. }),
+    }
+}
+
+/// Samples one side of `point` at distances `0.1/2^k`. The values converge
+/// when their differences shrink, and Richardson extrapolation then removes
+/// the error terms in powers of the distance.
+fn sample(expr: &Expr, variable: &str, point: f64, side: f64) -> Result<Limit> {
+    let f = function_of(expr, variable);
+    let mut values = Vec::new();
+    for k in 0..SAMPLES {
+        let value = f(point + side * FIRST_STEP / 2f64.powi(k))?;
+        if value.is_nan() {
+            return Ok(Limit::DoesNotExist);
+        }
+        values.push(value);
+    }
+
+    let last = &values[values.len() - 4..];
+    let growing = last.windows(2).all(|pair| pair[1].abs() > pair[0].abs());
+    if growing && last.iter().all(|value| value.abs() > DIVERGENCE) {
+        return Ok(if last.iter().all(|value| *value > 0.0) {
+            Limit::Infinity
+        } else if last.iter().all(|value| *value < 0.0) {
+            Limit::NegativeInfinity
+        } else {
+            Limit::DoesNotExist
+        });
+    }
+
+    let differences: Vec<f64> = values
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .collect();
+    let scale = values[values.len() - 1].abs().max(1.0);
+    let settled = differences[differences.len() - 1] <= 1e-6 * scale;
+    let shrinking = differences[differences.len() - 5..]
+        .windows(2)
+        .all(|pair| pair[1] <= 0.75 * pair[0]);
+    if !settled && !shrinking {
+        return Ok(Limit::DoesNotExist);
+    }
+
+    // Richardson extrapolation on the last few samples
+    let mut table: Vec<f64> = values[values.len() - 6..].to_vec();
+    let mut estimate = table[table.len() - 1];
+    let mut error = differences[differences.len() - 1];
+    for j in 1..table.len() {
+        let factor = 2f64.powi(j as i32) - 1.0;
+        let next: Vec<f64> = table
+            .windows(2)
+            .map(|pair| pair[1] + (pair[1] - pair[0]) / factor)
+            .collect();
+        let change = (next[next.len() - 1] - table[table.len() - 1]).abs();
+        if change >= error {
+            break;
+        }
+        (estimate, error) = (next[next.len() - 1], change);
+        table = next;
+    }
+    Ok(Limit::Value(num(round(estimate, DIGITS) + 0.0)))
+}
+
+/// Finds the limit of `expr` as `variable` approaches `point`, which may be
+/// `inf` or `-(inf)`. The leading term of the series settles indeterminate
+/// forms such as `sin(X)/X`. Otherwise the limits of the parts are combined,
+/// with L'Hôpital's rule for `0/0` and `inf/inf`, and as a last resort the
+/// limit is estimated numerically by sampling.
+pub fn limit(expr: &Expr, variable: &str, point: &Expr, direction: Direction) -> Result<Limit> {
+    if contains_variable(point, variable) {
+        bail!(SymbolicError::LimitPoint(point.to_string()));
+    }
+    let point = match evaluate_expr(point) {
+        Ok(value) if value.is_infinite() => Point::Infinite(value.signum()),
+        _ => Point::Finite(point.clone()),
+    };
+    let approach = Approach {
+        variable,
+        point,
+        direction,
+    };
+    approach.limit(expr, 0)
+}
+
+/// Like [`limit`], but fails when the limit doesn't exist.
+pub fn limit_expr(expr: &Expr, variable: &str, point: &Expr, direction: Direction) -> Result<Expr> {
+    match limit(expr, variable, point, direction)?.to_expr() {
+        Some(limit) => Ok(limit),
+        None => bail!(SymbolicError::NoLimit(expr.to_string())),
+    }
+}
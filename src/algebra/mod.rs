@@ -0,0 +1,261 @@
+mod apart;
+mod cancel;
+mod collect;
+mod diff;
+mod division;
+mod expand;
+mod factor;
+mod integrate;
+mod limit;
+mod series;
+mod substitute;
+mod summation;
+
+use anyhow::{bail, Result};
+
+use crate::error::{SolverError, SymbolicError};
+use crate::numeric_evaluator::evaluate_expr;
+use crate::optimizer::{combinelog, expandlog, trigexpand, trigreduce};
+use crate::parser::Expr;
+use crate::solver::{solve_system, solve_system_with, SystemStrategy};
+
+pub use apart::apart;
+pub use cancel::{cancel, Cancelled, Exclusion};
+pub use collect::collect;
+pub use diff::diff;
+pub use division::{gcd, quo, rem};
+pub use expand::expand;
+pub use factor::factor;
+pub use integrate::{integrate, integrate_definite};
+pub use limit::{limit, limit_expr, Direction, Limit};
+pub use series::series;
+pub use substitute::substitute;
+pub use summation::{product, sum};
+
+/// Reads an argument that has to be a plain variable such as `X`.
+fn variable_arg(name: &str, arg: &Expr) -> Result<String> {
+    match arg {
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } if *coefficient == 1.0 && *exponent == 1.0 => Ok(variable.to_owned()),
+        _ => bail!(SymbolicError::ExpectedVariable(
+            name.to_string(),
+            arg.to_string()
+        )),
+    }
+}
+
+/// Reads the variable from the argument after the first `count`, or when
+/// it is left out, takes the only variable those arguments have.
+fn optional_variable(name: &str, args: &[Expr], count: usize) -> Result<String> {
+    if let Some(variable) = args.get(count) {
+        return variable_arg(name, variable);
+    }
+
+    let mut variables = Vec::new();
+    for arg in &args[..count] {
+        collect_variables(arg, &mut variables);
+    }
+    variables.sort();
+    variables.dedup();
+    match variables.as_slice() {
+        [] => Ok("X".to_string()),
+        [variable] => Ok(variable.to_owned()),
+        _ => bail!(SymbolicError::AmbiguousVariable(name.to_string())),
+    }
+}
+
+fn collect_variables(expr: &Expr, variables: &mut Vec<String>) {
+    match expr {
+        Expr::Monomial {
+            variable, exponent, ..
+        } if *exponent != 0.0 => variables.push(variable.to_owned()),
+        Expr::UnaryMinus(inner) => collect_variables(inner, variables),
+        Expr::BinOp { lhs, rhs, .. } => {
+            collect_variables(lhs, variables);
+            collect_variables(rhs, variables);
+        }
+        Expr::Function { args, .. } | Expr::List(args) => {
+            for arg in args {
+                collect_variables(arg, variables);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Like [`check_arity`] for functions whose variable argument can be left
+/// out.
+fn check_optional_arity(name: &str, args: &[Expr], expected: usize) -> Result<()> {
+    if args.len() != expected && args.len() + 1 != expected {
+        bail!(SymbolicError::WrongArity(
+            name.to_string(),
+            expected,
+            args.len()
+        ));
+    }
+    Ok(())
+}
+
+fn check_arity(name: &str, args: &[Expr], expected: usize) -> Result<()> {
+    if args.len() != expected {
+        bail!(SymbolicError::WrongArity(
+            name.to_string(),
+            expected,
+            args.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Applies a symbolic function to already evaluated arguments, or returns
+/// `None` if `name` is not one.
+fn call_function(name: &str, args: &[Expr]) -> Result<Option<Expr>> {
+    Ok(Some(match name {
+        "expand" => {
+            check_arity(name, args, 1)?;
+            expand(&args[0])
+        }
+        "collect" => {
+            check_arity(name, args, 2)?;
+            collect(&args[0], &variable_arg(name, &args[1])?)
+        }
+        "trigexpand" => {
+            check_arity(name, args, 1)?;
+            trigexpand(&args[0])
+        }
+        "trigreduce" => {
+            check_arity(name, args, 1)?;
+            trigreduce(&args[0])
+        }
+        "expandlog" => {
+            check_arity(name, args, 1)?;
+            expandlog(&args[0])
+        }
+        "combinelog" => {
+            check_arity(name, args, 1)?;
+            combinelog(&args[0])
+        }
+        "factor" => {
+            check_arity(name, args, 1)?;
+            factor(&args[0])?
+        }
+        "quo" | "rem" => {
+            check_optional_arity(name, args, 3)?;
+            let variable = optional_variable(name, args, 2)?;
+            match name {
+                "quo" => quo(&args[0], &args[1], &variable)?,
+                _ => rem(&args[0], &args[1], &variable)?,
+            }
+        }
+        "gcd" => {
+            check_arity(name, args, 2)?;
+            gcd(&args[0], &args[1])?
+        }
+        "cancel" => {
+            check_arity(name, args, 1)?;
+            cancel(&args[0])?.expr
+        }
+        "diff" => {
+            check_optional_arity(name, args, 2)?;
+            diff(&args[0], &optional_variable(name, args, 1)?)?
+        }
+        "apart" => {
+            check_optional_arity(name, args, 2)?;
+            apart(&args[0], &optional_variable(name, args, 1)?)?
+        }
+        "integrate" if args.len() == 4 => {
+            let variable = variable_arg(name, &args[1])?;
+            integrate_definite(&args[0], &variable, &args[2], &args[3])?
+        }
+        "integrate" => {
+            check_optional_arity(name, args, 2)?;
+            integrate(&args[0], &optional_variable(name, args, 1)?)?
+        }
+        "series" => {
+            check_arity(name, args, 4)?;
+            let variable = variable_arg(name, &args[1])?;
+            let order = evaluate_expr(&args[3])?;
+            if order.fract() != 0.0 || order.abs() > i32::MAX as f64 {
+                bail!(SymbolicError::SeriesOrder(args[3].to_string()));
+            }
+            series(&args[0], &variable, &args[2], order as i32)?
+        }
+        "limit" => {
+            check_optional_arity(name, args, 4)?;
+            let variable = variable_arg(name, &args[1])?;
+            let direction = match args.get(3).map(evaluate_expr).transpose()? {
+                None => Direction::Both,
+                Some(direction) => match Direction::from_number(direction) {
+                    Some(direction) => direction,
+                    None => bail!(SymbolicError::LimitDirection(args[3].to_string())),
+                },
+            };
+            limit_expr(&args[0], &variable, &args[2], direction)?
+        }
+        "sum" | "product" => {
+            check_arity(name, args, 4)?;
+            let variable = variable_arg(name, &args[1])?;
+            match name {
+                "sum" => sum(&args[0], &variable, &args[2], &args[3])?,
+                _ => product(&args[0], &variable, &args[2], &args[3])?,
+            }
+        }
+        "solve" if matches!(args.first(), Some(Expr::List(_))) => {
+            check_optional_arity(name, args, 3)?;
+            let solution = match args.get(2) {
+                // Starting values pick Newton's method
+                Some(Expr::List(guess)) => {
+                    let guess = guess
+                        .iter()
+                        .map(|value| evaluate_expr(value))
+                        .collect::<Result<Vec<f64>>>()?;
+                    solve_system_with(&args[0], &args[1], &SystemStrategy::Newton(guess))?
+                }
+                Some(other) => bail!(SolverError::ExpectedList(other.to_string())),
+                None => solve_system(&args[0], &args[1])?,
+            };
+            match solution.to_expr() {
+                Some(solution) => solution,
+                None => bail!(SolverError::NoSolution),
+            }
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// Evaluates the symbolic functions in an expression, innermost first, so
+/// that `expand((X+1)^2)` turns into `X^2 + 2X + 1`. Every other node is
+/// left untouched.
+pub fn evaluate(expr: &Expr) -> Result<Expr> {
+    Ok(match expr {
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(Box::new(evaluate(inner)?)),
+        Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+            lhs: Box::new(evaluate(lhs)?),
+            op: *op,
+            rhs: Box::new(evaluate(rhs)?),
+        },
+        Expr::Function { name, args } => {
+            let args = args
+                .iter()
+                .map(|arg| evaluate(arg))
+                .collect::<Result<Vec<Expr>>>()?;
+            match call_function(name, &args)? {
+                Some(result) => result,
+                None => Expr::Function {
+                    name: name.to_owned(),
+                    args: args.into_iter().map(Box::new).collect(),
+                },
+            }
+        }
+        Expr::List(items) => Expr::List(
+            items
+                .iter()
+                .map(|item| evaluate(item).map(Box::new))
+                .collect::<Result<Vec<Box<Expr>>>>()?,
+        ),
+        expr => expr.clone(),
+    })
+}
@@ -0,0 +1,522 @@
+use std::f64::consts::E;
+
+use anyhow::{bail, Result};
+
+use crate::error::SymbolicError;
+use crate::math::{round, Rational};
+use crate::numeric_evaluator::evaluate_expr;
+use crate::parser::{func, pow, var, Expr, Op};
+use crate::polynomial::{MonomialOrder, Polynomial};
+use crate::solver::contains_variable;
+
+use super::diff::diff;
+use super::expand::{sum, Atoms, Summand};
+use super::substitute::substitute;
+
+/// Highest order accepted, so the factorials stay exact.
+const MAX_ORDER: i32 = 30;
+
+/// Stands in for the argument of a function while it is differentiated. The
+/// parser never accepts it as a variable name.
+const ARGUMENT: &str = "#";
+
+/// Largest denominator of a value that is taken to be exact, such as
+/// `sin(30) = 1/2`.
+const SIMPLE_DENOMINATOR: i128 = 1000;
+
+/// How many times the expansion is redone with more terms when
+/// cancellations in quotients cost precision.
+const RETRIES: usize = 8;
+
+/// A truncated Laurent series `c0*t^start + c1*t^(start+1) + ... + O(t^order)`
+/// in `t = X - point`. The coefficients are polynomials over the atoms of an
+/// [`Atoms`], so `pi/180` stays exact.
+#[derive(Debug, Clone)]
+pub(super) struct Series {
+    start: i32,
+    coefficients: Vec<Polynomial>,
+    order: i32,
+}
+
+impl Series {
+    fn new(start: i32, coefficients: Vec<Polynomial>, order: i32) -> Series {
+        let mut coefficients: Vec<Polynomial> = coefficients
+            .into_iter()
+            .take((order - start).max(0) as usize)
+            .collect();
+        let leading = coefficients
+            .iter()
+            .position(|c| !c.is_zero())
+            .unwrap_or(coefficients.len());
+        coefficients.drain(..leading);
+        while coefficients.last().is_some_and(Polynomial::is_zero) {
+            coefficients.pop();
+        }
+        Series {
+            start: start + leading as i32,
+            coefficients,
+            order,
+        }
+    }
+
+    fn constant(value: Polynomial, order: i32) -> Series {
+        Series::new(0, vec![value], order)
+    }
+
+    /// The lowest power with a nonzero coefficient, or the order when the
+    /// series vanishes up to it.
+    pub(super) fn valuation(&self) -> i32 {
+        if self.coefficients.is_empty() {
+            self.order
+        } else {
+            self.start
+        }
+    }
+
+    pub(super) fn coefficient(&self, power: i32) -> Polynomial {
+        usize::try_from(power - self.start)
+            .ok()
+            .and_then(|index| self.coefficients.get(index))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn add(&self, other: &Series) -> Series {
+        let order = self.order.min(other.order);
+        let start = self.valuation().min(other.valuation()).min(order);
+        let coefficients = (start..order)
+            .map(|power| self.coefficient(power) + other.coefficient(power))
+            .collect();
+        Series::new(start, coefficients, order)
+    }
+
+    fn neg(&self) -> Series {
+        let coefficients = self.coefficients.iter().map(|c| -c.clone()).collect();
+        Series::new(self.start, coefficients, self.order)
+    }
+
+    fn sub(&self, other: &Series) -> Series {
+        self.add(&other.neg())
+    }
+
+    fn mul(&self, other: &Series) -> Series {
+        let order = (self.order + other.valuation()).min(other.order + self.valuation());
+        let start = self.valuation() + other.valuation();
+        let mut coefficients = vec![Polynomial::zero(); (order - start).max(0) as usize];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                if let Some(c) = coefficients.get_mut(i + j) {
+                    *c = c.clone() + a.clone() * b.clone();
+                }
+            }
+        }
+        Series::new(start, coefficients, order)
+    }
+
+    fn scale(&self, factor: &Polynomial) -> Series {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| c.clone() * factor.clone())
+            .collect();
+        Series::new(self.start, coefficients, self.order)
+    }
+
+    /// Multiplies by `t^power`.
+    fn shift(&self, power: i32) -> Series {
+        Series::new(
+            self.start + power,
+            self.coefficients.clone(),
+            self.order + power,
+        )
+    }
+}
+
+/// The value of `expr` if it is a number with a small denominator, such as
+/// `cos(0)` or `sin(30)`.
+fn exact_value(expr: &Expr) -> Option<Rational> {
+    let value = evaluate_expr(expr).ok()?;
+    let value = Rational::from_f64(round(value, 12) + 0.0)?;
+    (value.denominator() <= SIMPLE_DENOMINATOR).then_some(value)
+}
+
+/// Replaces the parts of `expr` that have an exact value by it.
+pub(super) fn fold(expr: &Expr) -> Expr {
+    if let Some(value) = exact_value(expr) {
+        return Expr::from(value);
+    }
+    match expr {
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(Box::new(fold(inner))),
+        Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+            lhs: Box::new(fold(lhs)),
+            op: *op,
+            rhs: Box::new(fold(rhs)),
+        },
+        Expr::Function { name, args } => Expr::Function {
+            name: name.to_owned(),
+            args: args.iter().map(|arg| Box::new(fold(arg))).collect(),
+        },
+        expr => expr.clone(),
+    }
+}
+
+/// Whether the builtin `name` has derivatives of every order at `value`,
+/// where a kink, a jump or a pole would make its Taylor series wrong. The
+/// poles of `tan` at odd multiples of 90 degrees don't evaluate to
+/// infinity, so they are caught here.
+pub(super) fn is_smooth(name: &str, value: f64) -> bool {
+    match name {
+        "tan" => {
+            let turns = (value - 90.0) / 180.0;
+            (turns - turns.round()).abs() > 1e-12
+        }
+        "abs" | "sign" => value != 0.0,
+        "floor" | "ceil" | "trunc" | "fract" => value.fract() != 0.0,
+        "round" => value.fract().abs() != 0.5,
+        "cbrt" => value != 0.0,
+        _ => true,
+    }
+}
+
+/// Expands expressions into series around `point`.
+pub(super) struct Expander<'a> {
+    variable: &'a str,
+    point: Expr,
+    precision: i32,
+    atoms: Atoms,
+}
+
+impl<'a> Expander<'a> {
+    pub(super) fn new(variable: &'a str, point: &Expr, precision: i32) -> Expander<'a> {
+        Expander {
+            variable,
+            point: point.clone(),
+            precision,
+            atoms: Atoms::new(),
+        }
+    }
+
+    fn no_series(&self, expr: &Expr) -> anyhow::Error {
+        SymbolicError::NoSeries(expr.to_string(), self.point.to_string()).into()
+    }
+
+    /// Like [`Atoms::polynomial_of`], but divides through
+    /// [`Atoms::reciprocal`] so that factors can cancel.
+    fn polynomial_of(&mut self, expr: &Expr) -> Polynomial {
+        match expr {
+            Expr::UnaryMinus(inner) => -self.polynomial_of(inner),
+            Expr::BinOp { lhs, op, rhs } => match op {
+                Op::Add => self.polynomial_of(lhs) + self.polynomial_of(rhs),
+                Op::Subtract => self.polynomial_of(lhs) - self.polynomial_of(rhs),
+                Op::Multiply => self.polynomial_of(lhs) * self.polynomial_of(rhs),
+                Op::Divide => {
+                    let denominator = self.polynomial_of(rhs);
+                    self.polynomial_of(lhs) * self.reciprocal(&denominator)
+                }
+                _ => self.atoms.polynomial_of(expr),
+            },
+            expr => self.atoms.polynomial_of(expr),
+        }
+    }
+
+    fn constant(&mut self, expr: &Expr) -> Series {
+        let value = self.polynomial_of(&fold(expr));
+        Series::constant(value, self.precision)
+    }
+
+    /// `X` itself, `point + t`.
+    fn variable(&mut self) -> Series {
+        let point = self.polynomial_of(&self.point.clone());
+        Series::new(
+            0,
+            vec![point, Polynomial::constant(Rational::ONE)],
+            self.precision,
+        )
+    }
+
+    /// `1/c` for a nonzero coefficient.
+    fn reciprocal(&mut self, c: &Polynomial) -> Polynomial {
+        let expr = self.atoms.to_expr(c);
+        match exact_value(&expr).and_then(|value| value.recip()) {
+            Some(inverse) => Polynomial::constant(inverse),
+            None => self.atoms.reciprocal(c),
+        }
+    }
+
+    /// `1/u` as `1/(c*t^v) * (1 - w + w^2 - ...)` with `u = c*t^v*(1 + w)`.
+    fn recip(&mut self, u: &Series, expr: &Expr) -> Result<Series> {
+        let coefficients = u.coefficients.iter().map(|c| self.atoms.cancel(c));
+        let u = Series::new(u.start, coefficients.collect(), u.order);
+        let Some(leading) = u.coefficients.first() else {
+            bail!(self.no_series(expr));
+        };
+        let inverse = self.reciprocal(leading);
+        let w = Series::new(1, u.coefficients[1..].to_vec(), u.order - u.start).scale(&inverse);
+
+        let mut geometric = Series::constant(Polynomial::constant(Rational::ONE), w.order);
+        let mut power = geometric.clone();
+        for _ in 1..w.order {
+            power = power.mul(&w).neg();
+            geometric = geometric.add(&power);
+        }
+        Ok(geometric.scale(&inverse).shift(-u.start))
+    }
+
+    fn powi(&mut self, u: &Series, exponent: i32, expr: &Expr) -> Result<Series> {
+        let base = if exponent < 0 {
+            self.recip(u, expr)?
+        } else {
+            u.clone()
+        };
+        let mut power = Series::constant(Polynomial::constant(Rational::ONE), self.precision);
+        for _ in 0..exponent.unsigned_abs() {
+            power = power.mul(&base);
+        }
+        Ok(power)
+    }
+
+    /// `f(u)` as `f(u0) + f'(u0)*r + f''(u0)/2*r^2 + ...` with `u = u0 + r`,
+    /// where `f` builds the expression of the function from its argument.
+    fn compose(
+        &mut self,
+        f: impl Fn(Expr) -> Expr,
+        u: &Series,
+        name: Option<&str>,
+        expr: &Expr,
+    ) -> Result<Series> {
+        if u.valuation() < 0 {
+            bail!(self.no_series(expr));
+        }
+        let value = self.atoms.to_expr(&u.coefficient(0));
+        if let (Some(name), Ok(value)) = (name, evaluate_expr(&value)) {
+            if !is_smooth(name, value) {
+                bail!(self.no_series(expr));
+            }
+        }
+
+        let rest = u.sub(&Series::constant(u.coefficient(0), u.order));
+        let mut derivative = f(var(ARGUMENT));
+        let mut factorial = 1i128;
+        let mut power = Series::constant(Polynomial::constant(Rational::ONE), u.order);
+        let mut result = Series::new(0, Vec::new(), u.order);
+        for k in 0..u.order.max(1) {
+            if k > 0 {
+                power = power.mul(&rest);
+                if power.valuation() >= u.order {
+                    break;
+                }
+                derivative = diff(&derivative, ARGUMENT)?;
+                factorial *= k as i128;
+            }
+
+            let coefficient = fold(&substitute(&derivative, ARGUMENT, &value));
+            if evaluate_expr(&coefficient).is_ok_and(|value| !value.is_finite()) {
+                bail!(self.no_series(expr));
+            }
+            let coefficient = self.polynomial_of(&coefficient)
+                * Polynomial::constant(Rational::new(1, factorial).unwrap());
+            result = result.add(&power.scale(&coefficient));
+        }
+        Ok(result)
+    }
+
+    /// `u^v`, by repeated products for integer exponents.
+    fn power(&mut self, base: &Expr, exponent: &Expr, expr: &Expr) -> Result<Series> {
+        if !contains_variable(exponent, self.variable) {
+            let integer = exact_value(exponent)
+                .filter(|value| value.is_integer() && value.abs().numerator() <= MAX_ORDER as i128);
+            let u = self.series_of(base)?;
+            return match integer {
+                Some(n) => self.powi(&u, n.numerator() as i32, expr),
+                None => self.compose(|t| pow(t, exponent.clone()), &u, Some("pow"), expr),
+            };
+        }
+
+        let v = self.series_of(exponent)?;
+        if !contains_variable(base, self.variable) {
+            return self.compose(|t| pow(base.clone(), t), &v, None, expr);
+        }
+
+        // u^v = e^(v*ln(u))
+        let u = self.series_of(base)?;
+        let logarithm = self.compose(|t| func("ln", [t]), &u, Some("ln"), expr)?;
+        let e = Expr::Constant {
+            name: "e".to_string(),
+            value: E,
+        };
+        self.compose(|t| pow(e.clone(), t), &v.mul(&logarithm), None, expr)
+    }
+
+    pub(super) fn series_of(&mut self, expr: &Expr) -> Result<Series> {
+        if !contains_variable(expr, self.variable) {
+            return match expr {
+                Expr::List(_) | Expr::Wildcard(_) => bail!(self.no_series(expr)),
+                Expr::BinOp { op, .. } if op.is_relation() => bail!(self.no_series(expr)),
+                expr => Ok(self.constant(expr)),
+            };
+        }
+
+        Ok(match expr {
+            Expr::Monomial {
+                coefficient,
+                exponent,
+                ..
+            } => {
+                let x = var(self.variable);
+                let power = if *exponent == 1.0 {
+                    self.variable()
+                } else {
+                    self.power(&x, &Expr::Number(*exponent), expr)?
+                };
+                power.scale(&self.atoms.polynomial_of(&Expr::Number(*coefficient)))
+            }
+            Expr::UnaryMinus(inner) => self.series_of(inner)?.neg(),
+            Expr::BinOp { lhs, op, rhs } => match op {
+                Op::Add => self.series_of(lhs)?.add(&self.series_of(rhs)?),
+                Op::Subtract => self.series_of(lhs)?.sub(&self.series_of(rhs)?),
+                Op::Multiply => self.series_of(lhs)?.mul(&self.series_of(rhs)?),
+                Op::Divide => {
+                    let denominator = self.series_of(rhs)?;
+                    let inverse = self.recip(&denominator, expr)?;
+                    self.series_of(lhs)?.mul(&inverse)
+                }
+                Op::Power => self.power(lhs, rhs, expr)?,
+                _ => bail!(self.no_series(expr)),
+            },
+            Expr::Function { name, args } if name == "pow" && args.len() == 2 => {
+                self.power(&args[0], &args[1], expr)?
+            }
+            Expr::Function { name, args } if (name == "min" || name == "max") => {
+                let [lhs, rhs] = args.as_slice() else {
+                    bail!(SymbolicError::WrongArity(name.to_owned(), 2, args.len()));
+                };
+                let (u, v) = (self.series_of(lhs)?, self.series_of(rhs)?);
+                let at_point = |series: &Series, atoms: &Atoms| {
+                    evaluate_expr(&atoms.to_expr(&series.coefficient(0)))
+                };
+                let (a, b) = (at_point(&u, &self.atoms)?, at_point(&v, &self.atoms)?);
+                if a == b || u.valuation() < 0 || v.valuation() < 0 {
+                    bail!(self.no_series(expr));
+                }
+                if (a < b) == (name == "min") {
+                    u
+                } else {
+                    v
+                }
+            }
+            Expr::Function { name, args } => {
+                let [arg] = args.as_slice() else {
+                    bail!(SymbolicError::WrongArity(name.to_owned(), 1, args.len()));
+                };
+                let u = self.series_of(arg)?;
+                self.compose(|t| func(name, [t]), &u, Some(name), expr)?
+            }
+            _ => bail!(self.no_series(expr)),
+        })
+    }
+
+    /// Expands `expr` to at least `order`, redoing it with more terms when
+    /// divisions lose some. Terms from `order` on are dropped.
+    pub(super) fn expand(&mut self, expr: &Expr, order: i32) -> Result<Series> {
+        let mut series = self.series_of(expr)?;
+        for _ in 0..RETRIES {
+            if series.order >= order {
+                break;
+            }
+            self.precision += order - series.order;
+            series = self.series_of(expr)?;
+        }
+        let order = order.min(series.order);
+        Ok(Series::new(series.start, series.coefficients, order))
+    }
+
+    /// `t^power` as an expression.
+    fn power_expr(&self, power: i32) -> Expr {
+        let t = match &self.point {
+            Expr::Number(point) if *point == 0.0 => var(self.variable),
+            point => Expr::BinOp {
+                lhs: Box::new(var(self.variable)),
+                op: Op::Subtract,
+                rhs: Box::new(point.clone()),
+            },
+        };
+        match (power, t) {
+            (1, t) => t,
+            (
+                power,
+                Expr::Monomial {
+                    coefficient,
+                    variable,
+                    ..
+                },
+            ) => Expr::Monomial {
+                coefficient,
+                variable,
+                exponent: power as f64,
+            },
+            (power, t) => pow(t, power),
+        }
+    }
+
+    /// Cancels atoms against their reciprocals and folds exact values.
+    fn simplify(&self, coefficient: &Polynomial) -> Polynomial {
+        let coefficient = self.atoms.cancel(coefficient);
+        match exact_value(&self.atoms.to_expr(&coefficient)) {
+            Some(value) => Polynomial::constant(value),
+            None => coefficient,
+        }
+    }
+
+    /// The lowest power of `series` and its coefficient, or `None` when
+    /// every coefficient below the order vanishes.
+    pub(super) fn leading_term(&self, series: &Series) -> Option<(i32, Expr)> {
+        let coefficient = self.simplify(series.coefficients.first()?);
+        Some((series.start, self.atoms.to_expr(&coefficient)))
+    }
+
+    /// The series as a sum in ascending powers, followed by its `O` term.
+    pub(super) fn expr_of(&mut self, series: &Series) -> Expr {
+        let mut summands = Vec::new();
+        for (k, coefficient) in series.coefficients.iter().enumerate() {
+            let power = series.start + k as i32;
+            let coefficient = self.simplify(coefficient);
+            let term = match power {
+                0 => coefficient,
+                power => {
+                    let power = self.power_expr(power);
+                    let power = match power {
+                        Expr::Monomial { exponent, .. } if exponent > 0.0 => {
+                            self.atoms.polynomial_of(&power)
+                        }
+                        power => self.atoms.atom(power),
+                    };
+                    coefficient * power
+                }
+            };
+            summands.extend(
+                term.terms(MonomialOrder::GrLex)
+                    .into_iter()
+                    .map(|(monomial, c)| Summand::Term(c, monomial.clone())),
+            );
+        }
+        summands.push(Summand::Expr(func("O", [self.power_expr(series.order)])));
+        sum(summands, &self.atoms)
+    }
+}
+
+/// Expands `expr` into a power series in `variable` around `point`, with
+/// every power below `order` and an `O((X - point)^order)` term for the
+/// rest. Negative powers show up for poles, such as `1/X` around 0. Products,
+/// quotients and powers are multiplied out term by term and the builtin
+/// functions are composed through their derivatives.
+pub fn series(expr: &Expr, variable: &str, point: &Expr, order: i32) -> Result<Expr> {
+    if contains_variable(point, variable) {
+        bail!(SymbolicError::SeriesPoint(point.to_string()));
+    }
+    if !(0..=MAX_ORDER).contains(&order) {
+        bail!(SymbolicError::SeriesOrder(order.to_string()));
+    }
+    let mut expander = Expander::new(variable, point, order);
+    let series = expander.expand(expr, order)?;
+    Ok(expander.expr_of(&series))
+}
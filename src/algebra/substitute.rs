@@ -0,0 +1,50 @@
+use crate::parser::{num, pow, Expr};
+
+/// Replaces every occurrence of `variable` with `value`, so `3X^2` becomes
+/// `3*value^2`.
+pub fn substitute(expr: &Expr, variable: &str, value: &Expr) -> Expr {
+    match expr {
+        Expr::Monomial {
+            coefficient,
+            variable: name,
+            exponent,
+        } if name == variable => {
+            let power = if *exponent == 1.0 {
+                value.clone()
+            } else {
+                pow(value.clone(), num(*exponent))
+            };
+            if *coefficient == 1.0 {
+                power
+            } else {
+                num(*coefficient) * power
+            }
+        }
+        expr => map_children(expr, |child| substitute(child, variable, value)),
+    }
+}
+
+/// Replaces every subexpression equal to `pattern` with `replacement`.
+pub(crate) fn replace(expr: &Expr, pattern: &Expr, replacement: &Expr) -> Expr {
+    if expr == pattern {
+        return replacement.clone();
+    }
+    map_children(expr, |child| replace(child, pattern, replacement))
+}
+
+fn map_children(expr: &Expr, mut f: impl FnMut(&Expr) -> Expr) -> Expr {
+    match expr {
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(Box::new(f(inner))),
+        Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+            lhs: Box::new(f(lhs)),
+            op: *op,
+            rhs: Box::new(f(rhs)),
+        },
+        Expr::Function { name, args } => Expr::Function {
+            name: name.to_owned(),
+            args: args.iter().map(|arg| Box::new(f(arg))).collect(),
+        },
+        Expr::List(items) => Expr::List(items.iter().map(|item| Box::new(f(item))).collect()),
+        expr => expr.clone(),
+    }
+}
@@ -0,0 +1,609 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::error::NumericError;
+use crate::math::Rational;
+use crate::numeric_evaluator::{evaluate_expr, evaluate_with, function_of, nproduct, nsum};
+use crate::parser::{func, num, pow, var, Expr, Op, Optimize};
+use crate::polynomial::{Monomial, MonomialOrder, Polynomial, RationalFunction};
+use crate::solver::contains_variable;
+
+use super::apart::apart;
+use super::collect_variables;
+use super::expand::expand;
+use super::limit::{limit, Direction, Limit};
+use super::series::fold;
+use super::substitute::substitute;
+
+/// Largest power summed with Faulhaber's formula, so the Bernoulli numbers
+/// stay exact.
+const MAX_POWER: u32 = 20;
+
+/// Largest shift between the terms of a telescoping sum, as in
+/// `1/K - 1/(K+3)`.
+const MAX_SHIFT: i32 = 4;
+
+/// Values of the summation variable where two terms are compared. They
+/// aren't integers, so they stay clear of the poles of terms like `1/K`.
+const SAMPLES: [f64; 3] = [2.37, 3.81, 5.29];
+
+/// `expr` with `variable` replaced by `variable + by`.
+fn shift(expr: &Expr, variable: &str, by: i32) -> Expr {
+    substitute(expr, variable, &(var(variable) + num(by as f64)))
+}
+
+/// `expr` at a sample point, with made-up values for its other variables.
+fn sample(expr: &Expr, variable: &str, index: usize) -> Option<f64> {
+    let mut variables = Vec::new();
+    collect_variables(expr, &mut variables);
+    let values: HashMap<String, f64> = variables
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let value = if name == variable {
+                SAMPLES[index]
+            } else {
+                1.3 + 0.7 * (i + index) as f64
+            };
+            (name, value)
+        })
+        .collect();
+    evaluate_with(expr, &values)
+        .ok()
+        .filter(|value| value.is_finite() && *value != 0.0)
+}
+
+/// The constant `c` with `a(K) = c*b(K + by)`, if there is one.
+fn proportion(a: &Expr, b: &Expr, variable: &str, by: i32) -> Option<f64> {
+    let b = shift(b, variable, by);
+    let mut ratios =
+        (0..SAMPLES.len()).map(|i| Some(sample(a, variable, i)? / sample(&b, variable, i)?));
+    let first = ratios.next()??;
+    for ratio in ratios {
+        if (ratio? - first).abs() > 1e-9 * first.abs() {
+            return None;
+        }
+    }
+    Some(first)
+}
+
+/// A term `c*g(K + by)` of a [`Chain`], which appears `weight` times: as
+/// the exponent of a factor in a product.
+struct Member {
+    term: Expr,
+    weight: i32,
+    c: f64,
+    by: i32,
+}
+
+/// Terms that are constant multiples of the same `g` up to a shift, the
+/// smallest of which is 0.
+struct Chain {
+    g: Expr,
+    members: Vec<Member>,
+}
+
+/// Sorts the terms into [`Chain`]s, comparing each one to the first term
+/// of every chain so far.
+fn chains(terms: Vec<(Expr, i32)>, variable: &str) -> Vec<Chain> {
+    let mut chains: Vec<Chain> = Vec::new();
+    'terms: for (term, weight) in terms {
+        for chain in &mut chains {
+            for by in -MAX_SHIFT..=MAX_SHIFT {
+                if let Some(c) = proportion(&term, &chain.g, variable, by) {
+                    chain.members.push(Member {
+                        term,
+                        weight,
+                        c,
+                        by,
+                    });
+                    continue 'terms;
+                }
+            }
+        }
+        chains.push(Chain {
+            g: term.clone(),
+            members: vec![Member {
+                term,
+                weight,
+                c: 1.0,
+                by: 0,
+            }],
+        });
+    }
+
+    for chain in &mut chains {
+        let lowest = chain
+            .members
+            .iter()
+            .map(|member| member.by)
+            .min()
+            .unwrap_or(0);
+        chain.g = shift(&chain.g, variable, lowest);
+        for member in &mut chain.members {
+            member.by -= lowest;
+        }
+    }
+    chains
+}
+
+/// The terms of a sum, with the signs of subtractions applied.
+fn terms(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Add,
+            rhs,
+        } => [terms(lhs), terms(rhs)].concat(),
+        Expr::BinOp {
+            lhs,
+            op: Op::Subtract,
+            rhs,
+        } => {
+            let negated = terms(rhs).into_iter().map(|term| -term);
+            terms(lhs).into_iter().chain(negated).collect()
+        }
+        Expr::UnaryMinus(inner) if terms(inner).len() > 1 => {
+            terms(inner).into_iter().map(|term| -term).collect()
+        }
+        expr => vec![expr.clone()],
+    }
+}
+
+/// The factors of a product with their integer exponents, negative for
+/// divisors.
+fn factors(expr: &Expr) -> Vec<(Expr, i32)> {
+    let invert = |factors: Vec<(Expr, i32)>| factors.into_iter().map(|(f, e)| (f, -e)).collect();
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => [factors(lhs), factors(rhs)].concat(),
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } => [factors(lhs), invert(factors(rhs))].concat(),
+        Expr::BinOp {
+            lhs,
+            op: Op::Power,
+            rhs,
+        } => match rhs.as_ref() {
+            Expr::Number(n) if n.fract() == 0.0 && n.abs() <= MAX_POWER as f64 => {
+                let exponent = *n as i32;
+                factors(lhs)
+                    .into_iter()
+                    .map(|(f, e)| (f, e * exponent))
+                    .collect()
+            }
+            _ => vec![(expr.clone(), 1)],
+        },
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } if *coefficient != 1.0 || *exponent != 1.0 => {
+            if exponent.fract() != 0.0 || exponent.abs() > MAX_POWER as f64 {
+                return vec![(expr.clone(), 1)];
+            }
+            let mut factors = vec![(var(variable), *exponent as i32)];
+            if *coefficient != 1.0 {
+                factors.push((num(*coefficient), 1));
+            }
+            factors
+        }
+        expr => vec![(expr.clone(), 1)],
+    }
+}
+
+/// Expands the polynomial parts of `expr`, which collects the arguments
+/// like `((N+1)+-2)+1` left by substituting the bounds.
+fn tidy(expr: &Expr) -> Expr {
+    if Polynomial::from_expr(expr).is_ok() {
+        return expand(expr);
+    }
+    match expr {
+        Expr::UnaryMinus(inner) => -tidy(inner),
+        Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+            lhs: Box::new(tidy(lhs)),
+            op: *op,
+            rhs: Box::new(tidy(rhs)),
+        },
+        Expr::Function { name, args } => func(name, args.iter().map(|arg| tidy(arg))),
+        expr => expr.clone(),
+    }
+}
+
+/// The irreducible factors of a rational function, such as `K - 1`, `K + 1`
+/// and `K` with exponent -2 for `1 - 1/K^2`.
+fn factored(rational: &RationalFunction) -> Vec<(Expr, i32)> {
+    let (numerator, denominator) = (rational.numerator.factor(), rational.denominator.factor());
+    let content = numerator.content / denominator.content;
+    let mut factors = Vec::new();
+    if content != Rational::ONE {
+        factors.push((Expr::from(content), 1));
+    }
+    for (factorization, sign) in [(numerator, 1), (denominator, -1)] {
+        for (factor, power) in factorization.factors {
+            factors.push((factor.to_expr(), sign * power as i32));
+        }
+    }
+    factors
+}
+
+/// The Bernoulli numbers `B_0..=B_n` with `B_1 = 1/2`.
+fn bernoulli(n: u32) -> Option<Vec<Rational>> {
+    let mut numbers = vec![Rational::ONE];
+    for m in 1..=n as i128 {
+        let mut total = Rational::ZERO;
+        let mut binomial = 1; // C(m + 1, i)
+        for (i, b) in numbers.iter().enumerate() {
+            total = total.checked_add(&Rational::integer(binomial).checked_mul(b)?)?;
+            binomial = binomial * (m + 1 - i as i128) / (i as i128 + 1);
+        }
+        numbers.push(-total.checked_div(&Rational::integer(m + 1))?);
+    }
+    if n >= 1 {
+        numbers[1] = -numbers[1];
+    }
+    Some(numbers)
+}
+
+/// `1^p + 2^p + ... + n^p` as a polynomial in `n`, by Faulhaber's formula.
+fn faulhaber(p: u32, n: &str) -> Option<Polynomial> {
+    let bernoulli = bernoulli(p)?;
+    let mut sum = Polynomial::zero();
+    let mut binomial = 1; // C(p + 1, i)
+    for (i, b) in bernoulli.iter().enumerate() {
+        let coefficient = Rational::integer(binomial)
+            .checked_mul(b)?
+            .checked_div(&Rational::integer(p as i128 + 1))?;
+        sum = sum + Polynomial::term(coefficient, Monomial::variable(n, p + 1 - i as u32));
+        binomial = binomial * (p as i128 + 1 - i as i128) / (i as i128 + 1);
+    }
+    Some(sum)
+}
+
+/// A sum or product from `lower` to a finite `upper`, in the summation
+/// variable.
+struct Range<'a> {
+    variable: &'a str,
+    lower: Expr,
+    upper: Expr,
+}
+
+impl Range<'_> {
+    /// The number of terms, `upper - lower + 1`.
+    fn count(&self) -> Expr {
+        self.upper.clone() - self.lower.clone() + num(1.0)
+    }
+
+    /// `g(upper + 1 + j) - g(lower + j)` summed for `j` below `by`, the
+    /// terms of `sum(g(K + by) - g(K))` that don't cancel. `combine`
+    /// subtracts, or divides for a product.
+    fn ends(&self, g: &Expr, by: i32, combine: impl Fn(Expr, Expr) -> Expr) -> Vec<Expr> {
+        (0..by)
+            .map(|j| {
+                let high = self.upper.clone() + num(1.0 + j as f64);
+                let low = self.lower.clone() + num(j as f64);
+                combine(
+                    substitute(g, self.variable, &high),
+                    substitute(g, self.variable, &low),
+                )
+            })
+            .collect()
+    }
+
+    /// Sums a polynomial in the variable, whose coefficients may have
+    /// other variables, as `S(upper) - S(lower - 1)` where `S(n)` adds up
+    /// the terms from 1 to `n`.
+    fn faulhaber(&self, polynomial: &Polynomial) -> Option<Expr> {
+        let mut total = Polynomial::zero();
+        for (monomial, coefficient) in polynomial.terms(MonomialOrder::GrLex) {
+            let power = monomial.power(self.variable);
+            if power > MAX_POWER {
+                return None;
+            }
+            let rest = Monomial::new(monomial.powers().filter(|(name, _)| *name != self.variable));
+            total = total + Polynomial::term(coefficient, rest) * faulhaber(power, self.variable)?;
+        }
+        let total = total.to_expr();
+        let before = self.lower.clone() - num(1.0);
+        Some(expand(
+            &(substitute(&total, self.variable, &self.upper)
+                - substitute(&total, self.variable, &before)),
+        ))
+    }
+
+    /// `c^(a*K + b)` as the geometric series `(f(lower) - f(upper + 1))/(1 - c^a)`.
+    fn geometric(&self, expr: &Expr) -> Option<Expr> {
+        let Expr::BinOp {
+            lhs: base,
+            op: Op::Power,
+            rhs: exponent,
+        } = expr
+        else {
+            return None;
+        };
+        let exponent = Polynomial::from_expr(exponent).ok()?;
+        if contains_variable(base, self.variable) || exponent.degree_in(self.variable) != Some(1) {
+            return None;
+        }
+        let ratio = pow(
+            base.as_ref().clone(),
+            exponent.coefficient_in(self.variable, 1).to_expr(),
+        );
+        if evaluate_expr(&ratio).is_ok_and(|ratio| ratio == 1.0) {
+            return None;
+        }
+        let after = self.upper.clone() + num(1.0);
+        let first = substitute(expr, self.variable, &self.lower);
+        let last = substitute(expr, self.variable, &after);
+        Some((first - last) / (num(1.0) - ratio))
+    }
+
+    /// Adds up terms, telescoping the chains whose multiples add up to 0.
+    fn telescope(&self, terms: Vec<Expr>) -> Option<Expr> {
+        let mut sums = Vec::new();
+        let terms = terms.into_iter().map(|term| (term, 1)).collect();
+        for chain in chains(terms, self.variable) {
+            let total: f64 = chain.members.iter().map(|member| member.c).sum();
+            if total.abs() > 1e-9 || chain.members.len() == 1 {
+                for member in &chain.members {
+                    sums.push(self.sum(&member.term)?);
+                }
+                continue;
+            }
+            for member in &chain.members {
+                let ends = self.ends(&chain.g, member.by, |high, low| high - low);
+                sums.extend(ends.into_iter().map(|end| fold(&num(member.c)) * end));
+            }
+        }
+        sums.into_iter().reduce(|total, sum| total + sum)
+    }
+
+    /// The closed form of the sum, if one of the known ones applies.
+    fn sum(&self, expr: &Expr) -> Option<Expr> {
+        if !contains_variable(expr, self.variable) {
+            return Some(self.count() * expr.clone());
+        }
+        if let Ok(polynomial) = Polynomial::from_expr(expr) {
+            return self.faulhaber(&polynomial);
+        }
+
+        let constant = |expr: &Expr| !contains_variable(expr, self.variable);
+        match expr {
+            Expr::UnaryMinus(inner) => return Some(-self.sum(inner)?),
+            Expr::BinOp {
+                lhs,
+                op: Op::Multiply,
+                rhs,
+            } if constant(lhs) => return Some(lhs.as_ref().clone() * self.sum(rhs)?),
+            Expr::BinOp {
+                lhs,
+                op: op @ (Op::Multiply | Op::Divide),
+                rhs,
+            } if constant(rhs) => {
+                return Some(Expr::BinOp {
+                    lhs: Box::new(self.sum(lhs)?),
+                    op: *op,
+                    rhs: rhs.clone(),
+                });
+            }
+            _ => {}
+        }
+        if let Some(sum) = self.geometric(expr) {
+            return Some(sum);
+        }
+
+        let terms = terms(expr);
+        if terms.len() > 1 {
+            return self.telescope(terms);
+        }
+        // Partial fractions turn `1/(K*(K+1))` into a telescoping sum
+        let rational = RationalFunction::from_expr(expr).ok()?;
+        if rational.denominator.is_constant() {
+            return None;
+        }
+        let fractions = apart(expr, self.variable).ok()?;
+        let terms = self::terms(&fractions);
+        (terms.len() > 1).then(|| self.telescope(terms))?
+    }
+
+    /// The closed form of the product, if one of the known ones applies.
+    fn product(&self, expr: &Expr) -> Option<Expr> {
+        if !contains_variable(expr, self.variable) {
+            return Some(pow(expr.clone(), self.count()));
+        }
+        if let Expr::BinOp {
+            lhs,
+            op: Op::Power,
+            rhs,
+        } = expr
+        {
+            if !contains_variable(lhs, self.variable) {
+                return Some(pow(lhs.as_ref().clone(), self.sum(rhs)?));
+            }
+            if !contains_variable(rhs, self.variable) {
+                return Some(pow(self.product(lhs)?, rhs.as_ref().clone()));
+            }
+        }
+
+        let mut factors = factors(expr);
+        if let (1, Ok(rational)) = (factors.len(), RationalFunction::from_expr(expr)) {
+            factors = factored(&rational);
+        }
+        let (constant, varying): (Vec<_>, Vec<_>) = factors
+            .into_iter()
+            .partition(|(factor, _)| !contains_variable(factor, self.variable));
+
+        let mut products: Vec<Expr> = constant
+            .into_iter()
+            .map(|(factor, exponent)| pow(factor, num(exponent as f64) * self.count()))
+            .collect();
+        // Telescoping needs the exponents in each chain to cancel
+        for chain in chains(varying, self.variable) {
+            let total: i32 = chain.members.iter().map(|member| member.weight).sum();
+            if total != 0 {
+                return None;
+            }
+            for member in &chain.members {
+                let exponent = num(member.weight as f64);
+                if member.c != 1.0 {
+                    let count = exponent.clone() * self.count();
+                    products.push(pow(fold(&num(member.c)), count));
+                }
+                let ends = self.ends(&chain.g, member.by, |high, low| high / low);
+                products.extend(ends.into_iter().map(|end| pow(end, exponent.clone())));
+            }
+        }
+        products
+            .into_iter()
+            .reduce(|total, product| total * product)
+    }
+}
+
+/// Whether `sum` or `product` should be computed.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Sum,
+    Product,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Sum => "sum",
+            Kind::Product => "product",
+        }
+    }
+
+    fn empty(self) -> Expr {
+        match self {
+            Kind::Sum => num(0.0),
+            Kind::Product => num(1.0),
+        }
+    }
+
+    fn combine(self, a: Expr, b: Expr) -> Expr {
+        match self {
+            Kind::Sum => a + b,
+            Kind::Product => a * b,
+        }
+    }
+}
+
+/// A bound as a number, `None` if it isn't one.
+fn numeric_bound(bound: &Expr) -> Result<Option<f64>> {
+    let Ok(value) = evaluate_expr(bound) else {
+        return Ok(None);
+    };
+    if value.is_finite() && value.fract() != 0.0 {
+        bail!(NumericError::NonIntegerBound(bound.to_string()));
+    }
+    Ok(Some(value))
+}
+
+fn closed_form(
+    kind: Kind,
+    expr: &Expr,
+    variable: &str,
+    lower: &Expr,
+    upper: &Expr,
+) -> Result<Option<Expr>> {
+    let (a, b) = (numeric_bound(lower)?, numeric_bound(upper)?);
+    if let (Some(a), Some(b)) = (a, b) {
+        if a > b {
+            return Ok(Some(kind.empty()));
+        }
+    }
+    let infinite = |bound: Option<f64>| bound.is_some_and(f64::is_infinite);
+
+    // Sums down to -inf run the other way, and over every integer they
+    // split at 0
+    if infinite(a) && infinite(b) {
+        let below = closed_form(kind, expr, variable, lower, &num(-1.0))?;
+        let above = closed_form(kind, expr, variable, &num(0.0), upper)?;
+        return Ok(below
+            .zip(above)
+            .map(|(below, above)| kind.combine(below, above)));
+    }
+    if infinite(a) {
+        let reflected = substitute(expr, variable, &-var(variable));
+        return closed_form(kind, &reflected, variable, &-upper.clone(), &-lower.clone());
+    }
+
+    let range = Range {
+        variable,
+        lower: lower.clone(),
+        upper: if infinite(b) {
+            var(variable)
+        } else {
+            upper.clone()
+        },
+    };
+    let closed = match kind {
+        Kind::Sum => range.sum(expr),
+        Kind::Product => range.product(expr),
+    };
+    let Some(closed) = closed else {
+        return Ok(None);
+    };
+    if !infinite(b) {
+        return Ok(Some(closed));
+    }
+
+    // The partial sums up to `K` have to converge as `K` grows
+    let Ok(limit) = limit(&closed, variable, upper, Direction::Both) else {
+        return Ok(None);
+    };
+    match limit {
+        Limit::Value(value) => Ok(Some(value)),
+        _ => bail!(NumericError::DivergentSeries),
+    }
+}
+
+fn evaluate(kind: Kind, expr: &Expr, variable: &str, lower: &Expr, upper: &Expr) -> Result<Expr> {
+    if let Some(closed) = closed_form(kind, expr, variable, lower, upper)? {
+        return Ok(match evaluate_expr(&closed) {
+            Ok(value) if value.is_finite() => num(value),
+            _ => tidy(&fold(&closed)).optimize_expression(),
+        });
+    }
+
+    let bounds = evaluate_expr(lower).ok().zip(evaluate_expr(upper).ok());
+    let mut variables = Vec::new();
+    collect_variables(expr, &mut variables);
+    match bounds {
+        Some((a, b)) if variables.iter().all(|name| name == variable) => {
+            let f = function_of(expr, variable);
+            Ok(num(match kind {
+                Kind::Sum => nsum(f, a, b)?,
+                Kind::Product => nproduct(f, a, b)?,
+            }))
+        }
+        _ => Ok(func(
+            kind.name(),
+            [expr.clone(), var(variable), lower.clone(), upper.clone()],
+        )),
+    }
+}
+
+/// Adds up `expr` for the integers `variable` from `lower` to `upper`,
+/// either of which may be infinite. Arithmetic and other polynomial sums
+/// use Faulhaber's formula, and geometric and telescoping sums have closed
+/// forms too. Infinite sums are the limit of those, which has to exist.
+/// Without a closed form, a sum with numeric bounds is computed numerically
+/// and any other sum is left as it is.
+pub fn sum(expr: &Expr, variable: &str, lower: &Expr, upper: &Expr) -> Result<Expr> {
+    evaluate(Kind::Sum, expr, variable, lower, upper)
+}
+
+/// Multiplies `expr` for the integers `variable` from `lower` to `upper`,
+/// like [`sum`]. Products of constants, powers with a summable exponent and
+/// telescoping products such as `(K+1)/K` have closed forms.
+pub fn product(expr: &Expr, variable: &str, lower: &Expr, upper: &Expr) -> Result<Expr> {
+    evaluate(Kind::Product, expr, variable, lower, upper)
+}
@@ -0,0 +1,344 @@
+use std::cmp::Ordering;
+
+use crate::parser::{Expr, Op};
+
+impl Expr {
+    /// Returns the canonical form of the expression: sums and products are
+    /// flattened, their operands sorted by shape, signs are pulled out of
+    /// products and numbers are normalized. Two expressions that only differ
+    /// by associativity or commutativity share the same canonical form.
+    pub fn canonicalize(&self) -> Expr {
+        match self {
+            Expr::Number(n) => Expr::Number(normalize(*n)),
+            Expr::Constant { .. } | Expr::Wildcard(_) => self.clone(),
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => {
+                if *coefficient == 0.0 {
+                    Expr::Number(0.0)
+                } else if *exponent == 0.0 {
+                    Expr::Number(normalize(*coefficient))
+                } else {
+                    Expr::Monomial {
+                        coefficient: normalize(*coefficient),
+                        variable: variable.to_owned(),
+                        exponent: normalize(*exponent),
+                    }
+                }
+            }
+            Expr::UnaryMinus(inner) => match inner.as_ref() {
+                Expr::BinOp {
+                    op: Op::Add | Op::Subtract | Op::Multiply | Op::Divide,
+                    ..
+                } => {
+                    let mut terms = Vec::new();
+                    collect_terms(self, false, &mut terms);
+                    build_sum(terms)
+                }
+                inner => negate(inner.canonicalize()),
+            },
+            Expr::BinOp {
+                op: Op::Add | Op::Subtract,
+                ..
+            } => {
+                let mut terms = Vec::new();
+                collect_terms(self, false, &mut terms);
+                build_sum(terms)
+            }
+            Expr::BinOp {
+                op: Op::Multiply | Op::Divide,
+                ..
+            } => {
+                let mut numerator = Vec::new();
+                let mut denominator = Vec::new();
+                let negative = collect_factors(self, false, &mut numerator, &mut denominator);
+                build_product(negative, numerator, denominator)
+            }
+            Expr::BinOp {
+                lhs,
+                op: op @ (Op::Equals | Op::NotEqual),
+                rhs,
+            } => {
+                let mut sides = [lhs.canonicalize(), rhs.canonicalize()];
+                sides.sort_by(structural_cmp);
+                let [lhs, rhs] = sides;
+                Expr::BinOp {
+                    lhs: Box::new(lhs),
+                    op: *op,
+                    rhs: Box::new(rhs),
+                }
+            }
+            Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+                lhs: Box::new(lhs.canonicalize()),
+                op: *op,
+                rhs: Box::new(rhs.canonicalize()),
+            },
+            Expr::Function { name, args } => Expr::Function {
+                name: name.to_owned(),
+                args: args
+                    .iter()
+                    .map(|arg| Box::new(arg.canonicalize()))
+                    .collect(),
+            },
+            Expr::List(items) => Expr::List(
+                items
+                    .iter()
+                    .map(|item| Box::new(item.canonicalize()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Whether the two expressions have the same canonical form, unlike `==`,
+    /// which compares their exact shape.
+    pub fn canonical_eq(&self, other: &Expr) -> bool {
+        self.canonical_cmp(other) == Ordering::Equal
+    }
+
+    /// Total order on canonical forms. Expressions that are equal in shape
+    /// aren't canonicalized.
+    pub fn canonical_cmp(&self, other: &Expr) -> Ordering {
+        match structural_cmp(self, other) {
+            Ordering::Equal => Ordering::Equal,
+            _ => structural_cmp(&self.canonicalize(), &other.canonicalize()),
+        }
+    }
+}
+
+fn normalize(n: f64) -> f64 {
+    // Folds -0.0 into 0.0
+    n + 0.0
+}
+
+fn negate(expr: Expr) -> Expr {
+    match expr {
+        Expr::Number(n) => Expr::Number(normalize(-n)),
+        Expr::UnaryMinus(inner) => *inner,
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } => Expr::Monomial {
+            coefficient: -coefficient,
+            variable,
+            exponent,
+        },
+        expr => Expr::UnaryMinus(Box::new(expr)),
+    }
+}
+
+/// Splits a canonical expression into its sign and magnitude.
+fn split_sign(expr: Expr) -> (bool, Expr) {
+    match expr {
+        Expr::Number(n) if n < 0.0 => (true, Expr::Number(-n)),
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } if coefficient < 0.0 => (
+            true,
+            Expr::Monomial {
+                coefficient: -coefficient,
+                variable,
+                exponent,
+            },
+        ),
+        Expr::UnaryMinus(inner) => (true, *inner),
+        expr => (false, expr),
+    }
+}
+
+fn collect_terms(expr: &Expr, negative: bool, terms: &mut Vec<(Expr, bool)>) {
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Add,
+            rhs,
+        } => {
+            collect_terms(lhs, negative, terms);
+            collect_terms(rhs, negative, terms);
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Subtract,
+            rhs,
+        } => {
+            collect_terms(lhs, negative, terms);
+            collect_terms(rhs, !negative, terms);
+        }
+        Expr::UnaryMinus(inner) => collect_terms(inner, !negative, terms),
+        expr => {
+            let (sign, term) = split_sign(expr.canonicalize());
+            terms.push((term, negative ^ sign));
+        }
+    }
+}
+
+fn build_sum(mut terms: Vec<(Expr, bool)>) -> Expr {
+    terms.sort_by(|(a, a_negative), (b, b_negative)| {
+        structural_cmp(a, b).then(a_negative.cmp(b_negative))
+    });
+
+    let mut terms = terms.into_iter();
+    let mut sum = match terms.next() {
+        Some((term, true)) => negate(term),
+        Some((term, false)) => term,
+        None => return Expr::Number(0.0),
+    };
+
+    for (term, negative) in terms {
+        sum = Expr::BinOp {
+            lhs: Box::new(sum),
+            op: if negative { Op::Subtract } else { Op::Add },
+            rhs: Box::new(term),
+        };
+    }
+
+    sum
+}
+
+/// Flattens a product into numerator and denominator factors, returning
+/// whether an odd number of negations was pulled out.
+fn collect_factors(
+    expr: &Expr,
+    inverted: bool,
+    numerator: &mut Vec<Expr>,
+    denominator: &mut Vec<Expr>,
+) -> bool {
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => {
+            collect_factors(lhs, inverted, numerator, denominator)
+                ^ collect_factors(rhs, inverted, numerator, denominator)
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } => {
+            collect_factors(lhs, inverted, numerator, denominator)
+                ^ collect_factors(rhs, !inverted, numerator, denominator)
+        }
+        Expr::UnaryMinus(inner) => !collect_factors(inner, inverted, numerator, denominator),
+        expr => {
+            let (negative, factor) = split_sign(expr.canonicalize());
+            if inverted {
+                denominator.push(factor);
+            } else {
+                numerator.push(factor);
+            }
+            negative
+        }
+    }
+}
+
+fn build_product(negative: bool, mut numerator: Vec<Expr>, mut denominator: Vec<Expr>) -> Expr {
+    numerator.sort_by(structural_cmp);
+    denominator.sort_by(structural_cmp);
+
+    let fold = |factors: Vec<Expr>| {
+        factors.into_iter().reduce(|lhs, rhs| Expr::BinOp {
+            lhs: Box::new(lhs),
+            op: Op::Multiply,
+            rhs: Box::new(rhs),
+        })
+    };
+
+    let numerator = fold(numerator).unwrap_or(Expr::Number(1.0));
+    let product = match fold(denominator) {
+        Some(denominator) => Expr::BinOp {
+            lhs: Box::new(numerator),
+            op: Op::Divide,
+            rhs: Box::new(denominator),
+        },
+        None => numerator,
+    };
+
+    if negative {
+        negate(product)
+    } else {
+        product
+    }
+}
+
+fn rank(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Number(_) => 0,
+        Expr::Constant { .. } => 1,
+        Expr::Monomial { .. } => 2,
+        Expr::Function { .. } => 3,
+        Expr::UnaryMinus(_) => 4,
+        Expr::BinOp { .. } => 5,
+        Expr::Wildcard(_) => 6,
+        Expr::List(_) => 7,
+    }
+}
+
+/// Total order on the exact shape of two trees, without canonicalizing them.
+fn structural_cmp(a: &Expr, b: &Expr) -> Ordering {
+    match (a, b) {
+        (Expr::Number(a), Expr::Number(b)) => a.total_cmp(b),
+        (Expr::Constant { name: a, .. }, Expr::Constant { name: b, .. }) => a.cmp(b),
+        (
+            Expr::Monomial {
+                coefficient: a_coefficient,
+                variable: a_variable,
+                exponent: a_exponent,
+            },
+            Expr::Monomial {
+                coefficient: b_coefficient,
+                variable: b_variable,
+                exponent: b_exponent,
+            },
+        ) => a_variable
+            .cmp(b_variable)
+            .then(a_exponent.total_cmp(b_exponent))
+            .then(a_coefficient.total_cmp(b_coefficient)),
+        (
+            Expr::Function {
+                name: a_name,
+                args: a_args,
+            },
+            Expr::Function {
+                name: b_name,
+                args: b_args,
+            },
+        ) => a_name.cmp(b_name).then_with(|| {
+            a_args
+                .iter()
+                .zip(b_args.iter())
+                .map(|(a, b)| structural_cmp(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(a_args.len().cmp(&b_args.len()))
+        }),
+        (Expr::List(a), Expr::List(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| structural_cmp(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(a.len().cmp(&b.len())),
+        (Expr::UnaryMinus(a), Expr::UnaryMinus(b)) => structural_cmp(a, b),
+        (Expr::Wildcard(a), Expr::Wildcard(b)) => a.cmp(b),
+        (
+            Expr::BinOp {
+                lhs: a_lhs,
+                op: a_op,
+                rhs: a_rhs,
+            },
+            Expr::BinOp {
+                lhs: b_lhs,
+                op: b_op,
+                rhs: b_rhs,
+            },
+        ) => a_op
+            .cmp(b_op)
+            .then_with(|| structural_cmp(a_lhs, b_lhs))
+            .then_with(|| structural_cmp(a_rhs, b_rhs)),
+        (a, b) => rank(a).cmp(&rank(b)),
+    }
+}
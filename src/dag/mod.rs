@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::parser::{Expr, Op};
+
+/// Handle to a node stored in an [`ExprPool`]. Two ids from the same pool are
+/// equal exactly when the expressions they point to are structurally equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(u32);
+
+impl ExprId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A single expression node whose children are ids into the same pool.
+#[derive(Debug, Clone)]
+pub enum Node {
+    Number(f64),
+    UnaryMinus(ExprId),
+    BinOp {
+        lhs: ExprId,
+        op: Op,
+        rhs: ExprId,
+    },
+    Function {
+        name: String,
+        args: Vec<ExprId>,
+    },
+    Monomial {
+        coefficient: f64,
+        variable: String,
+        exponent: f64,
+    },
+    Constant {
+        name: String,
+        value: f64,
+    },
+    Wildcard(String),
+    List(Vec<ExprId>),
+}
+
+impl Node {
+    pub fn children(&self) -> Vec<ExprId> {
+        match self {
+            Node::UnaryMinus(inner) => vec![*inner],
+            Node::BinOp { lhs, rhs, .. } => vec![*lhs, *rhs],
+            Node::Function { args, .. } | Node::List(args) => args.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Floats are compared by their bit pattern so that hash-consing stays
+// consistent with `Eq`, even for NaN.
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Node::Number(a), Node::Number(b)) => a.to_bits() == b.to_bits(),
+            (Node::UnaryMinus(a), Node::UnaryMinus(b)) => a == b,
+            (
+                Node::BinOp { lhs, op, rhs },
+                Node::BinOp {
+                    lhs: other_lhs,
+                    op: other_op,
+                    rhs: other_rhs,
+                },
+            ) => lhs == other_lhs && op == other_op && rhs == other_rhs,
+            (
+                Node::Function { name, args },
+                Node::Function {
+                    name: other_name,
+                    args: other_args,
+                },
+            ) => name == other_name && args == other_args,
+            (
+                Node::Monomial {
+                    coefficient,
+                    variable,
+                    exponent,
+                },
+                Node::Monomial {
+                    coefficient: other_coefficient,
+                    variable: other_variable,
+                    exponent: other_exponent,
+                },
+            ) => {
+                coefficient.to_bits() == other_coefficient.to_bits()
+                    && variable == other_variable
+                    && exponent.to_bits() == other_exponent.to_bits()
+            }
+            (
+                Node::Constant { name, value },
+                Node::Constant {
+                    name: other_name,
+                    value: other_value,
+                },
+            ) => name == other_name && value.to_bits() == other_value.to_bits(),
+            (Node::Wildcard(a), Node::Wildcard(b)) => a == b,
+            (Node::List(a), Node::List(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Node {}
+
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Node::Number(n) => n.to_bits().hash(state),
+            Node::UnaryMinus(inner) => inner.hash(state),
+            Node::BinOp { lhs, op, rhs } => {
+                lhs.hash(state);
+                op.hash(state);
+                rhs.hash(state);
+            }
+            Node::Function { name, args } => {
+                name.hash(state);
+                args.hash(state);
+            }
+            Node::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => {
+                coefficient.to_bits().hash(state);
+                variable.hash(state);
+                exponent.to_bits().hash(state);
+            }
+            Node::Constant { name, value } => {
+                name.hash(state);
+                value.to_bits().hash(state);
+            }
+            Node::Wildcard(name) => name.hash(state),
+            Node::List(items) => items.hash(state),
+        }
+    }
+}
+
+/// Hash-consed arena of expression nodes. Every distinct subterm is stored
+/// once, so repeated subexpressions share a single id.
+#[derive(Debug, Default)]
+pub struct ExprPool {
+    nodes: Vec<Node>,
+    ids: HashMap<Node, ExprId>,
+    canonical: HashMap<ExprId, ExprId>,
+}
+
+impl ExprPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of unique nodes in the pool.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds a node, returning the existing id if an equal node is already
+    /// stored.
+    pub fn add(&mut self, node: Node) -> ExprId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+
+        let id = ExprId(self.nodes.len() as u32);
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        id
+    }
+
+    pub fn get(&self, id: ExprId) -> &Node {
+        &self.nodes[id.index()]
+    }
+
+    pub fn intern(&mut self, expr: &Expr) -> ExprId {
+        let node = match expr {
+            Expr::Number(n) => Node::Number(*n),
+            Expr::UnaryMinus(inner) => Node::UnaryMinus(self.intern(inner)),
+            Expr::BinOp { lhs, op, rhs } => Node::BinOp {
+                lhs: self.intern(lhs),
+                op: *op,
+                rhs: self.intern(rhs),
+            },
+            Expr::Function { name, args } => Node::Function {
+                name: name.to_owned(),
+                args: args.iter().map(|arg| self.intern(arg)).collect(),
+            },
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => Node::Monomial {
+                coefficient: *coefficient,
+                variable: variable.to_owned(),
+                exponent: *exponent,
+            },
+            Expr::Constant { name, value } => Node::Constant {
+                name: name.to_owned(),
+                value: *value,
+            },
+            Expr::Wildcard(name) => Node::Wildcard(name.to_owned()),
+            Expr::List(items) => Node::List(items.iter().map(|item| self.intern(item)).collect()),
+        };
+
+        self.add(node)
+    }
+
+    /// The id of the canonical form of `id`, see [`Expr::canonicalize`]. Two
+    /// nodes are canonically equal exactly when these ids are the same.
+    pub fn canonical(&mut self, id: ExprId) -> ExprId {
+        if let Some(canonical) = self.canonical.get(&id) {
+            return *canonical;
+        }
+
+        let canonical = self.intern(&self.to_expr(id).canonicalize());
+        self.canonical.insert(id, canonical);
+        self.canonical.insert(canonical, canonical);
+        canonical
+    }
+
+    /// Rebuilds the tree rooted at `id`. Shared subterms are duplicated in the
+    /// output, so prefer working on ids where possible.
+    pub fn to_expr(&self, id: ExprId) -> Expr {
+        match self.get(id) {
+            Node::Number(n) => Expr::Number(*n),
+            Node::UnaryMinus(inner) => Expr::UnaryMinus(Box::new(self.to_expr(*inner))),
+            Node::BinOp { lhs, op, rhs } => Expr::BinOp {
+                lhs: Box::new(self.to_expr(*lhs)),
+                op: *op,
+                rhs: Box::new(self.to_expr(*rhs)),
+            },
+            Node::Function { name, args } => Expr::Function {
+                name: name.to_owned(),
+                args: args
+                    .iter()
+                    .map(|arg| Box::new(self.to_expr(*arg)))
+                    .collect(),
+            },
+            Node::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => Expr::Monomial {
+                coefficient: *coefficient,
+                variable: variable.to_owned(),
+                exponent: *exponent,
+            },
+            Node::Constant { name, value } => Expr::Constant {
+                name: name.to_owned(),
+                value: *value,
+            },
+            Node::Wildcard(name) => Expr::Wildcard(name.to_owned()),
+            Node::List(items) => Expr::List(
+                items
+                    .iter()
+                    .map(|item| Box::new(self.to_expr(*item)))
+                    .collect(),
+            ),
+        }
+    }
+}
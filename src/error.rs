@@ -14,6 +14,8 @@ pub enum ParserError {
     InvalidOperator(String),
     #[error("Syntax error: unknown constant '{0}'")]
     UnknownConstant(String),
+    #[error("Syntax error: wildcards are only allowed in patterns")]
+    UnexpectedWildcard,
 }
 
 #[derive(Debug, Error)]
@@ -24,4 +26,122 @@ pub enum EvaluatorError {
     ParseFailure(ParserError),
     #[error("Equality found in evaluator")]
     EqualityInEval,
+    #[error("Inequality found in evaluator")]
+    InequalityInEval,
+    #[error("List found in evaluator")]
+    ListInEval,
+    #[error("Can't evaluate the free variable '{0}'")]
+    UnknownVariable(String),
+}
+
+#[derive(Debug, Error)]
+pub enum RuleError {
+    #[error("Rule error: wildcard '?{0}' is not bound by the pattern")]
+    UnboundWildcard(String),
+    #[error("Rule error: a bare wildcard pattern matches every expression")]
+    MatchesEverything,
+    #[error("Rule error: the rewrite contains its own pattern and would never terminate")]
+    NonTerminating,
+    #[error("Rule error: the wildcard '{0}' can't be added to an e-graph")]
+    WildcardInGraph(String),
+}
+
+#[derive(Debug, Error)]
+pub enum SolverError {
+    #[error("Solver error: expected an equation")]
+    NotAnEquation,
+    #[error("Solver error: expected an inequality such as 'X < 1'")]
+    NotAnInequality,
+    #[error("Solver error: the inequality for '{0}' also depends on '{1}'")]
+    ExtraVariable(String, String),
+    #[error("Solver error: the equation is not linear in '{0}'")]
+    NonLinear(String),
+    #[error("Solver error: the equation is not a polynomial in '{0}'")]
+    NonPolynomial(String),
+    #[error("Solver error: the polynomial has degree {0}, at most {1} is supported")]
+    DegreeTooHigh(usize, usize),
+    #[error("Solver error: the equation does not depend on '{0}'")]
+    MissingVariable(String),
+    #[error("Solver error: the equation has no solution")]
+    NoSolution,
+    #[error(
+        "Solver error: the equation has {0} solutions, which can't be written as one equation"
+    )]
+    MultipleSolutions(usize),
+    #[error("Solver error: the equation has infinitely many solutions")]
+    InfiniteSolutions,
+    #[error("Solver error: no root found starting from {0}")]
+    NoConvergence(String),
+    #[error("Solver error: roots are only accurate to 15 digits, {0} were requested")]
+    Precision(u32),
+    #[error("Solver error: expected a list such as '[X, Y]', found '{0}'")]
+    ExpectedList(String),
+    #[error("Solver error: the system depends on '{0}', which is not being solved for")]
+    UnlistedVariable(String),
+    #[error(
+        "Solver error: Newton's method needs as many equations as variables, found {0} for {1}"
+    )]
+    NotSquare(usize, usize),
+    #[error("Solver error: expected {0} starting values, found {1}")]
+    GuessCount(usize, usize),
+}
+
+#[derive(Debug, Error)]
+pub enum PolynomialError {
+    #[error("Polynomial error: '{0}' is not a polynomial")]
+    NotPolynomial(String),
+    #[error("Polynomial error: '{0}' needs a non-negative integer exponent")]
+    InvalidExponent(String),
+    #[error("Polynomial error: the coefficient {0} is not a rational number")]
+    InexactCoefficient(f64),
+    #[error("Polynomial error: '{0}' depends on variables other than '{1}'")]
+    NotUnivariate(String, String),
+    #[error("Polynomial error: division by zero")]
+    DivisionByZero,
+    #[error("Polynomial error: the coefficients of '{0}' are too large")]
+    Overflow(String),
+}
+
+#[derive(Debug, Error)]
+pub enum SymbolicError {
+    #[error("Syntax error: '{0}' takes {1} arguments but {2} were given")]
+    WrongArity(String, usize, usize),
+    #[error("Syntax error: '{0}' expects a variable, found '{1}'")]
+    ExpectedVariable(String, String),
+    #[error("Syntax error: '{0}' needs a variable when there are several")]
+    AmbiguousVariable(String),
+    #[error("Math error: the derivative of '{0}' is unknown")]
+    UnknownDerivative(String),
+    #[error("Math error: no antiderivative of '{0}' was found")]
+    NoAntiderivative(String),
+    #[error("Math error: '{0}' has no power series around {1}")]
+    NoSeries(String, String),
+    #[error("Math error: a series can't be expanded around '{0}', which depends on its variable")]
+    SeriesPoint(String),
+    #[error("Math error: the order of a series must be a whole number from 0 to 30, found '{0}'")]
+    SeriesOrder(String),
+    #[error("Math error: a limit can't be taken at '{0}', which depends on its variable")]
+    LimitPoint(String),
+    #[error(
+        "Math error: the direction of a limit must be 1 (from above), -1 (from below) or 0 (both sides), found '{0}'"
+    )]
+    LimitDirection(String),
+    #[error("Math error: the limit of '{0}' does not exist")]
+    NoLimit(String),
+}
+
+#[derive(Debug, Error)]
+pub enum NumericError {
+    #[error("Math error: the integral doesn't converge")]
+    Divergent,
+    #[error("Math error: the bound '{0}' is not a number")]
+    NonNumericBound(String),
+    #[error("Math error: the bound '{0}' of a sum or product is not a whole number")]
+    NonIntegerBound(String),
+    #[error("Math error: the series doesn't converge")]
+    DivergentSeries,
+    #[error("Math error: {0} terms are too many to add up or multiply")]
+    TooManyTerms(f64),
+    #[error("Math error: '{0}' is not defined for {1}")]
+    Domain(String, String),
 }
@@ -1,10 +1,15 @@
 use wasm_bindgen::prelude::*;
 
+pub mod algebra;
+mod canonical;
+pub mod dag;
 mod error;
-mod math;
+pub mod math;
 pub mod numeric_evaluator;
-mod optimizer;
-mod parser;
+pub mod optimizer;
+pub mod parser;
+pub mod polynomial;
+pub mod solver;
 
 #[cfg(test)]
 mod tests;
@@ -16,3 +21,265 @@ pub fn evaluate(expression: &str) -> Result<f64, String> {
         Err(err) => Err(err.to_string()),
     }
 }
+
+/// Integrates `expression` numerically in `variable` between two bounds,
+/// which may be `inf`, and returns the value followed by an error estimate.
+#[wasm_bindgen]
+pub fn nintegrate(
+    expression: &str,
+    variable: &str,
+    lower: &str,
+    upper: &str,
+) -> Result<Vec<f64>, String> {
+    let nintegrate = || -> anyhow::Result<Vec<f64>> {
+        let expr = parser::parse(expression)?;
+        let (lower, upper) = (
+            numeric_evaluator::evaluate(lower)?,
+            numeric_evaluator::evaluate(upper)?,
+        );
+        let result = numeric_evaluator::nintegrate(&expr, variable, lower, upper)?;
+        Ok(vec![result.value, result.error])
+    };
+
+    nintegrate().map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen]
+pub fn solve(equation: &str, variable: &str) -> Result<String, String> {
+    let solve = || -> anyhow::Result<String> {
+        let equation = parser::parse(equation)?;
+        Ok(solver::solve(&equation, variable)?.to_string())
+    };
+
+    solve().map_err(|err| err.to_string())
+}
+
+/// Solves a list of linear equations such as `[2X + 3Y = 5, X - Y = 1]`
+/// for a list of variables `[X, Y]`.
+#[wasm_bindgen]
+pub fn solve_system(equations: &str, variables: &str) -> Result<String, String> {
+    let solve_system = || -> anyhow::Result<String> {
+        let (equations, variables) = (parser::parse(equations)?, parser::parse(variables)?);
+        Ok(solver::solve_system(&equations, &variables)?.to_string())
+    };
+
+    solve_system().map_err(|err| err.to_string())
+}
+
+/// Solves a square system of equations with Newton's method, starting from
+/// one value per variable.
+#[wasm_bindgen]
+pub fn nsolve_system(equations: &str, variables: &str, guess: Vec<f64>) -> Result<String, String> {
+    let nsolve_system = || -> anyhow::Result<String> {
+        let (equations, variables) = (parser::parse(equations)?, parser::parse(variables)?);
+        let strategy = solver::SystemStrategy::Newton(guess);
+        Ok(solver::solve_system_with(&equations, &variables, &strategy)?.to_string())
+    };
+
+    nsolve_system().map_err(|err| err.to_string())
+}
+
+/// Finds one solution of `equation` numerically, starting from `guess`.
+#[wasm_bindgen]
+pub fn nsolve(equation: &str, variable: &str, guess: f64) -> Result<f64, String> {
+    match parser::parse(equation).and_then(|expr| solver::nsolve(&expr, variable, guess)) {
+        Ok(root) => Ok(root),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Finds every real solution of `equation` between `lower` and `upper`.
+#[wasm_bindgen]
+pub fn roots(equation: &str, variable: &str, lower: f64, upper: f64) -> Result<Vec<f64>, String> {
+    match parser::parse(equation).and_then(|expr| solver::roots(&expr, variable, lower, upper)) {
+        Ok(roots) => Ok(roots),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Approximates every complex root of a polynomial with its multiplicity,
+/// rounded to `digits` decimal places.
+#[wasm_bindgen]
+pub fn all_roots(expression: &str, variable: &str, digits: u32) -> Result<String, String> {
+    let all_roots = || -> anyhow::Result<String> {
+        let roots = solver::all_roots(&parser::parse(expression)?, variable, digits)?;
+        let roots: Vec<String> = roots.iter().map(|root| root.to_string()).collect();
+        Ok(format!("{{{}}}", roots.join(", ")))
+    };
+
+    all_roots().map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen]
+pub fn expand(expression: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(algebra::expand(&expr).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn collect(expression: &str, variable: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(algebra::collect(&expr, variable).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Expands sines and cosines of sums and multiple angles, e.g. `sin(2X)`
+/// into `2sin(X)cos(X)`.
+#[wasm_bindgen]
+pub fn trigexpand(expression: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(optimizer::trigexpand(&expr).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Rewrites products and powers of sines and cosines as multiple angles,
+/// the reverse of [`trigexpand`].
+#[wasm_bindgen]
+pub fn trigreduce(expression: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(optimizer::trigreduce(&expr).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Splits logarithms of products, quotients and powers, e.g. `ln(X*Y)`
+/// into `ln(X) + ln(Y)`. Variables are assumed to be positive.
+#[wasm_bindgen]
+pub fn expandlog(expression: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(optimizer::expandlog(&expr).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Merges sums and multiples of logarithms into one, the reverse of
+/// [`expandlog`].
+#[wasm_bindgen]
+pub fn combinelog(expression: &str) -> Result<String, String> {
+    match parser::parse(expression) {
+        Ok(expr) => Ok(optimizer::combinelog(&expr).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn factor(expression: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::factor(&expr)) {
+        Ok(expr) => Ok(expr.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn quo(dividend: &str, divisor: &str, variable: &str) -> Result<String, String> {
+    let quo = || -> anyhow::Result<String> {
+        let (dividend, divisor) = (parser::parse(dividend)?, parser::parse(divisor)?);
+        Ok(algebra::quo(&dividend, &divisor, variable)?.to_string())
+    };
+
+    quo().map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen]
+pub fn rem(dividend: &str, divisor: &str, variable: &str) -> Result<String, String> {
+    let rem = || -> anyhow::Result<String> {
+        let (dividend, divisor) = (parser::parse(dividend)?, parser::parse(divisor)?);
+        Ok(algebra::rem(&dividend, &divisor, variable)?.to_string())
+    };
+
+    rem().map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen]
+pub fn gcd(a: &str, b: &str) -> Result<String, String> {
+    let gcd = || -> anyhow::Result<String> {
+        let (a, b) = (parser::parse(a)?, parser::parse(b)?);
+        Ok(algebra::gcd(&a, &b)?.to_string())
+    };
+
+    gcd().map_err(|err| err.to_string())
+}
+
+/// Cancels common factors of a rational expression. The points removed
+/// from the domain follow the result, as in `(1X^(1)+1), X≠1`.
+#[wasm_bindgen]
+pub fn cancel(expression: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::cancel(&expr)) {
+        Ok(cancelled) => Ok(cancelled.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Differentiates an expression with respect to `variable`. The rounding
+/// functions and `sign` have derivative 0, also at their jumps where it is
+/// really undefined.
+#[wasm_bindgen]
+pub fn diff(expression: &str, variable: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::diff(&expr, variable)) {
+        Ok(expr) => Ok(expr.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn apart(expression: &str, variable: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::apart(&expr, variable)) {
+        Ok(expr) => Ok(expr.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn integrate(expression: &str, variable: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::integrate(&expr, variable)) {
+        Ok(expr) => Ok(expr.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Expands an expression into a power series around `point`, up to an
+/// `O((X - point)^order)` term.
+#[wasm_bindgen]
+pub fn series(expression: &str, variable: &str, point: &str, order: i32) -> Result<String, String> {
+    let series = || -> anyhow::Result<String> {
+        let (expr, point) = (parser::parse(expression)?, parser::parse(point)?);
+        Ok(algebra::series(&expr, variable, &point, order)?.to_string())
+    };
+
+    series().map_err(|err| err.to_string())
+}
+
+/// Finds the limit of an expression as `variable` approaches `point`, from
+/// above when `direction` is 1, from below when it is -1 and from both
+/// sides when it is 0. Fails when the limit doesn't exist.
+#[wasm_bindgen]
+pub fn limit(
+    expression: &str,
+    variable: &str,
+    point: &str,
+    direction: i32,
+) -> Result<String, String> {
+    let limit = || -> anyhow::Result<String> {
+        let (expr, point) = (parser::parse(expression)?, parser::parse(point)?);
+        let Some(direction) = algebra::Direction::from_number(direction as f64) else {
+            anyhow::bail!(error::SymbolicError::LimitDirection(direction.to_string()));
+        };
+        Ok(algebra::limit_expr(&expr, variable, &point, direction)?.to_string())
+    };
+
+    limit().map_err(|err| err.to_string())
+}
+
+/// Evaluates the symbolic functions in an expression, such as `expand`, and
+/// returns the result as a string.
+#[wasm_bindgen]
+pub fn simplify(expression: &str) -> Result<String, String> {
+    match parser::parse(expression).and_then(|expr| algebra::evaluate(&expr)) {
+        Ok(expr) => Ok(expr.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
@@ -2,4 +2,8 @@ use std::f64::consts::PI;
 
 pub fn deg_to_rad(a: f64) -> f64 {
     a*(PI/180.0)
+}
+
+pub fn rad_to_deg(a: f64) -> f64 {
+    a*(180.0/PI)
 }
\ No newline at end of file
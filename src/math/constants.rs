@@ -7,4 +7,5 @@ pub static CONSTANTS_DATABASE: phf::Map<&'static str, f64> = phf_map! {
     "tau" => TAU,
     "e" => E,
     "phi" => 1.61803398874989484820,
+    "inf" => f64::INFINITY,
 };
@@ -1,8 +1,12 @@
 mod round;
 mod angle;
+mod complex;
 mod constants;
+mod rational;
 
 pub use round::round;
-pub use angle::deg_to_rad;
+pub use angle::{deg_to_rad, rad_to_deg};
+pub use complex::Complex;
+pub use rational::{gcd, Rational};
 
 pub use constants::CONSTANTS_DATABASE;
@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::parser::{Expr, Op};
+
+/// Exact fraction in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+/// Greatest common divisor, always non-negative.
+pub fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Rational = Rational {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Builds `numerator / denominator`, or `None` if the denominator is zero
+    /// or normalizing overflows.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Rational> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator, denominator);
+        let sign = if denominator < 0 { -1 } else { 1 };
+        Some(Rational {
+            numerator: numerator.checked_div(divisor)?.checked_mul(sign)?,
+            denominator: denominator.checked_div(divisor)?.checked_mul(sign)?,
+        })
+    }
+
+    pub fn integer(value: i128) -> Rational {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    /// Converts the shortest decimal representation of `value`, so `0.1`
+    /// becomes `1/10` rather than the nearest binary fraction.
+    pub fn from_f64(value: f64) -> Option<Rational> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let text = value.to_string();
+        let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let denominator = 10i128.checked_pow(fraction.len() as u32)?;
+        let numerator = format!("{whole}{fraction}").parse::<i128>().ok()?;
+        Rational::new(numerator, denominator)
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numerator < 0
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator,
+        }
+    }
+
+    /// Whether the value has a finite decimal expansion, i.e. the denominator
+    /// only has the prime factors 2 and 5.
+    pub fn is_decimal(&self) -> bool {
+        let mut denominator = self.denominator;
+        for factor in [2, 5] {
+            while denominator % factor == 0 {
+                denominator /= factor;
+            }
+        }
+        denominator == 1
+    }
+
+    pub fn checked_add(&self, other: &Rational) -> Option<Rational> {
+        let divisor = gcd(self.denominator, other.denominator);
+        let lhs = self.numerator.checked_mul(other.denominator / divisor)?;
+        let rhs = other.numerator.checked_mul(self.denominator / divisor)?;
+        let denominator = (self.denominator / divisor).checked_mul(other.denominator)?;
+        Rational::new(lhs.checked_add(rhs)?, denominator)
+    }
+
+    pub fn checked_sub(&self, other: &Rational) -> Option<Rational> {
+        self.checked_add(&-*other)
+    }
+
+    pub fn checked_mul(&self, other: &Rational) -> Option<Rational> {
+        // Cross-cancel first to keep the intermediate products small
+        let a = gcd(self.numerator, other.denominator).max(1);
+        let b = gcd(other.numerator, self.denominator).max(1);
+        Rational::new(
+            (self.numerator / a).checked_mul(other.numerator / b)?,
+            (self.denominator / b).checked_mul(other.denominator / a)?,
+        )
+    }
+
+    pub fn checked_div(&self, other: &Rational) -> Option<Rational> {
+        self.checked_mul(&other.recip()?)
+    }
+
+    pub fn recip(&self) -> Option<Rational> {
+        Rational::new(self.denominator, self.numerator)
+    }
+
+    pub fn checked_pow(&self, exponent: i32) -> Option<Rational> {
+        let base = if exponent < 0 { self.recip()? } else { *self };
+        let exponent = exponent.unsigned_abs();
+        Rational::new(
+            base.numerator.checked_pow(exponent)?,
+            base.denominator.checked_pow(exponent)?,
+        )
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::integer(value)
+    }
+}
+
+/// Plain number when the value has a finite decimal expansion, fraction
+/// otherwise.
+impl From<Rational> for Expr {
+    fn from(value: Rational) -> Self {
+        if value.is_decimal() {
+            Expr::Number(value.to_f64())
+        } else {
+            Expr::BinOp {
+                lhs: Box::new(Expr::Number(value.numerator() as f64)),
+                op: Op::Divide,
+                rhs: Box::new(Expr::Number(value.denominator() as f64)),
+            }
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+macro_rules! impl_rational_op {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl $trait for Rational {
+            type Output = Rational;
+
+            /// Panics on overflow or division by zero, use the `checked_`
+            /// variant where that can happen.
+            fn $method(self, rhs: Rational) -> Rational {
+                self.$checked(&rhs).expect("rational overflow")
+            }
+        }
+    };
+}
+
+impl_rational_op!(Add, add, checked_add);
+impl_rational_op!(Sub, sub, checked_sub);
+impl_rational_op!(Mul, mul, checked_mul);
+impl_rational_op!(Div, div, checked_div);
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (
+            self.numerator.checked_mul(other.denominator),
+            other.numerator.checked_mul(self.denominator),
+        ) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
@@ -1,96 +1,250 @@
+use std::collections::HashMap;
+
 use anyhow::{bail, Result};
 
-use crate::error::EvaluatorError;
-use crate::math::{deg_to_rad, round};
-use crate::parser::{parse, Expr, Op};
-
-fn evaluate_expr(expr: Expr) -> Result<f64> {
-    match expr {
-        Expr::BinOp { lhs, op, rhs } => match op {
-            Op::Add => Ok(evaluate_expr(*lhs)? + evaluate_expr(*rhs)?),
-            Op::Subtract => Ok(evaluate_expr(*lhs)? - evaluate_expr(*rhs)?),
-            Op::Multiply => Ok(evaluate_expr(*lhs)? * evaluate_expr(*rhs)?),
-            Op::Divide => Ok(evaluate_expr(*lhs)? / evaluate_expr(*rhs)?),
-            Op::Modulo => Ok((evaluate_expr(*lhs)? % evaluate_expr(*rhs)?).abs()),
-            Op::Power => Ok(evaluate_expr(*lhs)?.powf(evaluate_expr(*rhs)?)),
-            Op::Equals => bail!(EvaluatorError::EqualityInEval),
-        },
-        Expr::Number(val) => Ok(val),
-        Expr::Constant { value, .. } => Ok(value),
-        Expr::UnaryMinus(op) => Ok(-1.0 * evaluate_expr(*op)?),
-        Expr::Function { name, args } => match name.as_str() {
-            "cos" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(deg_to_rad(evaluate_expr(*arg)?).cos())
-            }
-            "sin" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(deg_to_rad(evaluate_expr(*arg)?).sin())
-            }
-            "tan" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(deg_to_rad(evaluate_expr(*arg)?).tan())
-            }
-            "floor" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.floor())
-            }
-            "ceil" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.ceil())
-            }
-            "round" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.round())
-            }
-            "trunc" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.trunc())
-            }
-            "fract" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.fract())
-            }
-            "sqrt" => {
-                assert_eq!(args.len(), 1);
-                let arg = args.iter().next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg)?.sqrt())
-            }
-            "pow" => {
-                assert_eq!(args.len(), 2);
-                let mut args = args.iter();
-                let arg1 = args.next().unwrap().to_owned();
-                let arg2 = args.next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg1)?.powf(evaluate_expr(*arg2)?))
+use super::quadrature::{quadrature, Quadrature, TOLERANCE};
+use super::summation::{nproduct, nsum};
+use crate::algebra::substitute;
+use crate::dag::{ExprId, ExprPool, Node};
+use crate::error::{EvaluatorError, NumericError, SymbolicError};
+use crate::math::{deg_to_rad, rad_to_deg, round};
+use crate::parser::{num, parse, Expr, Op};
+use crate::solver::nsolve;
+
+/// Evaluates expressions stored in an [`ExprPool`], computing every shared
+/// subterm only once. Variables take their values from `variables`.
+struct Evaluator<'a> {
+    pool: &'a ExprPool,
+    cache: Vec<Option<f64>>,
+    variables: HashMap<String, f64>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(pool: &'a ExprPool, variables: HashMap<String, f64>) -> Self {
+        Self {
+            pool,
+            cache: vec![None; pool.len()],
+            variables,
+        }
+    }
+
+    /// Evaluates `id` as a function of `variable`, keeping the values of
+    /// the other variables.
+    fn bind(&self, id: ExprId, variable: &str) -> impl Fn(f64) -> Result<f64> + '_ {
+        let variable = variable.to_owned();
+        move |value| {
+            let mut variables = self.variables.clone();
+            variables.insert(variable.clone(), value);
+            Evaluator::new(self.pool, variables).evaluate(id)
+        }
+    }
+
+    /// Reads the argument of `name` that has to be a plain variable.
+    fn variable_arg(&self, name: &str, id: ExprId) -> Result<String> {
+        match self.pool.get(id) {
+            Node::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } if *coefficient == 1.0 && *exponent == 1.0 => Ok(variable.to_owned()),
+            _ => bail!(SymbolicError::ExpectedVariable(
+                name.to_string(),
+                self.pool.to_expr(id).to_string()
+            )),
+        }
+    }
+
+    /// Functions such as `nintegrate(f, X, a, b)` that can't evaluate
+    /// their first argument up front since it depends on `X`, which is only
+    /// bound inside the call.
+    fn call_numeric(&mut self, name: &str, args: &[ExprId]) -> Result<f64> {
+        let expected = if name == "nsolve" { 3 } else { 4 };
+        if args.len() != expected {
+            bail!(SymbolicError::WrongArity(
+                name.to_string(),
+                expected,
+                args.len()
+            ));
+        }
+        let variable = self.variable_arg(name, args[1])?;
+
+        if name != "nsolve" {
+            let (lower, upper) = (self.evaluate(args[2])?, self.evaluate(args[3])?);
+            let f = self.bind(args[0], &variable);
+            return match name {
+                "sum" => nsum(f, lower, upper),
+                "product" => nproduct(f, lower, upper),
+                _ => Ok(quadrature(f, lower, upper, TOLERANCE)?.value),
+            };
+        }
+
+        // The solver differentiates symbolically, so the variables bound by
+        // an enclosing call are substituted into the expression
+        let guess = self.evaluate(args[2])?;
+        let equation = self
+            .variables
+            .iter()
+            .filter(|(name, _)| **name != variable)
+            .fold(self.pool.to_expr(args[0]), |expr, (name, value)| {
+                substitute(&expr, name, &num(*value))
+            });
+        nsolve(&equation, &variable, guess)
+    }
+
+    fn evaluate(&mut self, id: ExprId) -> Result<f64> {
+        if let Some(value) = self.cache[id.index()] {
+            return Ok(value);
+        }
+
+        let value = match self.pool.get(id) {
+            Node::BinOp { lhs, op, rhs } => {
+                let (lhs, rhs) = (*lhs, *rhs);
+                match op {
+                    Op::Add => self.evaluate(lhs)? + self.evaluate(rhs)?,
+                    Op::Subtract => self.evaluate(lhs)? - self.evaluate(rhs)?,
+                    Op::Multiply => self.evaluate(lhs)? * self.evaluate(rhs)?,
+                    Op::Divide => self.evaluate(lhs)? / self.evaluate(rhs)?,
+                    Op::Modulo => (self.evaluate(lhs)? % self.evaluate(rhs)?).abs(),
+                    Op::Power => self.evaluate(lhs)?.powf(self.evaluate(rhs)?),
+                    Op::Equals => bail!(EvaluatorError::EqualityInEval),
+                    _ => bail!(EvaluatorError::InequalityInEval),
+                }
             }
-            "min" => {
-                assert_eq!(args.len(), 2);
-                let mut args = args.iter();
-                let arg1 = args.next().unwrap().to_owned();
-                let arg2 = args.next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg1)?.min(evaluate_expr(*arg2)?))
+            Node::Number(val) => *val,
+            Node::Constant { value, .. } => *value,
+            Node::UnaryMinus(op) => -self.evaluate(*op)?,
+            Node::Function { name, args }
+                if matches!(name.as_str(), "nintegrate" | "nsolve" | "sum" | "product") =>
+            {
+                let (name, args) = (name.clone(), args.clone());
+                self.call_numeric(&name, &args)?
             }
-            "max" => {
-                assert_eq!(args.len(), 2);
-                let mut args = args.iter();
-                let arg1 = args.next().unwrap().to_owned();
-                let arg2 = args.next().unwrap().to_owned();
-                Ok(evaluate_expr(*arg1)?.max(evaluate_expr(*arg2)?))
+            Node::Function { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(*arg))
+                    .collect::<Result<Vec<f64>>>()?;
+                call_function(name, &args)?
             }
-            name => bail!("Syntax error: no function name found"),
-        },
-        _ => todo!(),
+            Node::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => match self.variables.get(variable) {
+                Some(value) => coefficient * value.powf(*exponent),
+                None => bail!(EvaluatorError::UnknownVariable(variable.to_owned())),
+            },
+            Node::List(_) => bail!(EvaluatorError::ListInEval),
+            Node::Wildcard(_) => unreachable!("wildcards are rejected by the parser"),
+        };
+
+        self.cache[id.index()] = Some(value);
+        Ok(value)
     }
 }
 
+fn call_function(name: &str, args: &[f64]) -> Result<f64> {
+    Ok(match (name, args) {
+        ("cos", [x]) => deg_to_rad(*x).cos(),
+        ("sin", [x]) => deg_to_rad(*x).sin(),
+        ("tan", [x]) => deg_to_rad(*x).tan(),
+        ("atan", [x]) => rad_to_deg(x.atan()),
+        ("floor", [x]) => x.floor(),
+        ("ceil", [x]) => x.ceil(),
+        ("round", [x]) => x.round(),
+        ("trunc", [x]) => x.trunc(),
+        ("fract", [x]) => x.fract(),
+        ("sqrt", [x]) => x.sqrt(),
+        ("cbrt", [x]) => x.cbrt(),
+        ("abs", [x]) => x.abs(),
+        ("sign", [x]) => {
+            if *x == 0.0 {
+                0.0
+            } else {
+                x.signum()
+            }
+        }
+        ("ln" | "log" | "log2" | "log10", [x]) | ("log", [_, x]) if *x <= 0.0 => {
+            bail!(NumericError::Domain(name.to_string(), x.to_string()))
+        }
+        ("log", [base, _]) if *base <= 0.0 || *base == 1.0 => {
+            bail!(NumericError::Domain(
+                name.to_string(),
+                format!("base {base}")
+            ))
+        }
+        ("ln", [x]) => x.ln(),
+        // `log(X)` is the common logarithm, `log(B, X)` takes any base
+        ("log", [x]) => x.log10(),
+        ("log", [base, x]) => x.ln() / base.ln(),
+        ("log2", [x]) => x.log2(),
+        ("log10", [x]) => x.log10(),
+        ("exp", [x]) => x.exp(),
+        ("pow", [x, y]) => x.powf(*y),
+        ("min", [x, y]) => x.min(*y),
+        ("max", [x, y]) => x.max(*y),
+        ("log" | "pow" | "min" | "max", _) => {
+            bail!(SymbolicError::WrongArity(name.to_string(), 2, args.len()))
+        }
+        (
+            "cos" | "sin" | "tan" | "atan" | "floor" | "ceil" | "round" | "trunc" | "fract"
+            | "sqrt" | "cbrt" | "abs" | "sign" | "ln" | "log2" | "log10" | "exp",
+            _,
+        ) => bail!(SymbolicError::WrongArity(name.to_string(), 1, args.len())),
+        _ => bail!(EvaluatorError::UnknownFunction(name.to_string())),
+    })
+}
+
 pub fn evaluate(expression: &str) -> Result<f64> {
-    Ok(round(evaluate_expr(parse(expression)?)?, 15))
+    evaluate_expr(&parse(expression)?)
+}
+
+/// Evaluates an already parsed expression. Fails on free variables.
+pub fn evaluate_expr(expr: &Expr) -> Result<f64> {
+    evaluate_with(expr, &HashMap::new())
+}
+
+/// Evaluates an expression with values for its variables, failing on any
+/// other variable.
+pub fn evaluate_with(expr: &Expr, variables: &HashMap<String, f64>) -> Result<f64> {
+    let mut pool = ExprPool::new();
+    let root = pool.intern(expr);
+    Ok(round(
+        Evaluator::new(&pool, variables.clone()).evaluate(root)?,
+        15,
+    ))
+}
+
+/// Compiles `expr` into a function of `variable`, for evaluating it at many
+/// points. Unlike [`evaluate_expr`] the values are not rounded.
+pub fn function_of(expr: &Expr, variable: &str) -> impl Fn(f64) -> Result<f64> {
+    let mut pool = ExprPool::new();
+    let root = pool.intern(expr);
+    let variable = variable.to_owned();
+    move |value| {
+        let variables = HashMap::from([(variable.clone(), value)]);
+        Evaluator::new(&pool, variables).evaluate(root)
+    }
+}
+
+/// Like [`function_of`] for several variables, whose values are passed in
+/// the same order as `variables`.
+pub fn function_of_many(expr: &Expr, variables: &[String]) -> impl Fn(&[f64]) -> Result<f64> {
+    let mut pool = ExprPool::new();
+    let root = pool.intern(expr);
+    let variables = variables.to_vec();
+    move |values| {
+        let variables = variables
+            .iter()
+            .cloned()
+            .zip(values.iter().copied())
+            .collect();
+        Evaluator::new(&pool, variables).evaluate(root)
+    }
+}
+
+/// Integrates `expr` numerically in `variable` from `lower` to `upper`,
+/// either of which may be infinite.
+pub fn nintegrate(expr: &Expr, variable: &str, lower: f64, upper: f64) -> Result<Quadrature> {
+    quadrature(function_of(expr, variable), lower, upper, TOLERANCE)
 }
@@ -1,3 +1,9 @@
 mod evaluator;
+mod quadrature;
+mod summation;
 
-pub use evaluator::evaluate;
+pub use evaluator::{
+    evaluate, evaluate_expr, evaluate_with, function_of, function_of_many, nintegrate,
+};
+pub use quadrature::{quadrature, Quadrature};
+pub use summation::{nproduct, nsum};
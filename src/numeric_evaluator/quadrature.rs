@@ -0,0 +1,184 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::error::NumericError;
+
+/// Default tolerance, both absolute and relative to the result.
+pub(crate) const TOLERANCE: f64 = 1e-10;
+
+/// Most subintervals before the integral is considered divergent.
+const MAX_INTERVALS: usize = 1000;
+
+/// Nodes of the 15-point Kronrod rule on `[-1, 1]`, from the outside in.
+/// Every odd one is also a node of the embedded 7-point Gauss rule.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// A numeric integral and an estimate of its absolute error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quadrature {
+    pub value: f64,
+    pub error: f64,
+}
+
+impl fmt::Display for Quadrature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ± {:e}", self.value, self.error)
+    }
+}
+
+/// A subinterval with its Kronrod estimate and the difference to the
+/// Gauss estimate as error.
+struct Interval {
+    a: f64,
+    b: f64,
+    value: f64,
+    error: f64,
+}
+
+/// Integrates `f` over `[a, b]` with adaptive Gauss–Kronrod quadrature,
+/// always splitting the subinterval with the largest error until the total
+/// is within `tolerance`. Infinite bounds are mapped onto a finite interval
+/// first. The rule never evaluates the endpoints, so integrable
+/// singularities there are fine.
+pub fn quadrature(
+    f: impl Fn(f64) -> Result<f64>,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+) -> Result<Quadrature> {
+    if a.is_nan() || b.is_nan() {
+        bail!(NumericError::Divergent);
+    }
+    if a == b {
+        return Ok(Quadrature {
+            value: 0.0,
+            error: 0.0,
+        });
+    }
+    if a > b {
+        let result = quadrature(f, b, a, tolerance)?;
+        return Ok(Quadrature {
+            value: -result.value,
+            ..result
+        });
+    }
+
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) => adaptive(f, a, b, tolerance),
+        // x = a + t/(1-t), dx = 1/(1-t)^2 dt
+        (true, false) => adaptive(
+            |t| Ok(f(a + t / (1.0 - t))? / (1.0 - t).powi(2)),
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = b - (1-t)/t, dx = 1/t^2 dt
+        (false, true) => adaptive(
+            |t| Ok(f(b - (1.0 - t) / t)? / t.powi(2)),
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = t/(1-t^2), dx = (1+t^2)/(1-t^2)^2 dt
+        (false, false) => adaptive(
+            |t| {
+                let s = 1.0 - t * t;
+                Ok(f(t / s)? * (1.0 + t * t) / (s * s))
+            },
+            -1.0,
+            1.0,
+            tolerance,
+        ),
+    }
+}
+
+fn adaptive(f: impl Fn(f64) -> Result<f64>, a: f64, b: f64, tolerance: f64) -> Result<Quadrature> {
+    let mut intervals = vec![kronrod(&f, a, b)?];
+    loop {
+        let value: f64 = intervals.iter().map(|interval| interval.value).sum();
+        let error: f64 = intervals.iter().map(|interval| interval.error).sum();
+        if error <= tolerance.max(tolerance * value.abs()) {
+            return Ok(Quadrature { value, error });
+        }
+        if intervals.len() >= MAX_INTERVALS {
+            bail!(NumericError::Divergent);
+        }
+
+        let worst = intervals
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.error.total_cmp(&b.error))
+            .map(|(index, _)| index)
+            .expect("at least one interval");
+        let Interval { a, b, .. } = intervals.swap_remove(worst);
+        let m = (a + b) / 2.0;
+        if m <= a || m >= b {
+            // Too narrow to split any further
+            bail!(NumericError::Divergent);
+        }
+        intervals.push(kronrod(&f, a, m)?);
+        intervals.push(kronrod(&f, m, b)?);
+    }
+}
+
+/// Applies the 15-point Kronrod and 7-point Gauss rules to `[a, b]`.
+fn kronrod(f: &impl Fn(f64) -> Result<f64>, a: f64, b: f64) -> Result<Interval> {
+    let eval = |x: f64| -> Result<f64> {
+        let value = f(x)?;
+        if !value.is_finite() {
+            bail!(NumericError::Divergent);
+        }
+        Ok(value)
+    };
+
+    let center = (a + b) / 2.0;
+    let half = (b - a) / 2.0;
+    let mut kronrod = 0.0;
+    let mut gauss = 0.0;
+    for (i, (node, weight)) in KRONROD_NODES.iter().zip(KRONROD_WEIGHTS).enumerate() {
+        let sum = if *node == 0.0 {
+            eval(center)?
+        } else {
+            eval(center - half * node)? + eval(center + half * node)?
+        };
+        kronrod += weight * sum;
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * sum;
+        }
+    }
+
+    Ok(Interval {
+        a,
+        b,
+        value: kronrod * half,
+        error: ((kronrod - gauss) * half).abs(),
+    })
+}
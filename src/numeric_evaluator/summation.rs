@@ -0,0 +1,164 @@
+use anyhow::{bail, Result};
+
+use crate::error::NumericError;
+
+use super::quadrature::{quadrature, TOLERANCE};
+
+/// Terms added up one by one before the tail of an infinite series is
+/// estimated.
+const TERMS: usize = 1000;
+
+/// Most terms of a finite sum or product.
+const MAX_TERMS: f64 = 1e7;
+
+/// Partial sums averaged for the tail of an alternating series.
+const AVERAGED: usize = 20;
+
+/// Most pieces of the integral over the tail of a series.
+const MAX_PIECES: usize = 200;
+
+/// The terms of a convergent series have to shrink at least by this factor
+/// from halfway to the end of the first [`TERMS`].
+const DECAY: f64 = 0.9;
+
+fn check_bound(bound: f64) -> Result<()> {
+    if bound.is_nan() || (bound.is_finite() && bound.fract() != 0.0) {
+        bail!(NumericError::NonIntegerBound(bound.to_string()));
+    }
+    Ok(())
+}
+
+fn term(f: &impl Fn(f64) -> Result<f64>, k: f64) -> Result<f64> {
+    let value = f(k)?;
+    if !value.is_finite() {
+        bail!(NumericError::DivergentSeries);
+    }
+    Ok(value)
+}
+
+/// Integrates `f` from `from` to infinity over pieces that double in
+/// width. Once the ratio of consecutive pieces settles, as it does for
+/// tails like `1/x^p`, the rest is a geometric series, which diverges when
+/// the ratio reaches 1.
+fn tail(f: &impl Fn(f64) -> Result<f64>, from: f64) -> Result<f64> {
+    let mut total = 0.0;
+    let (mut a, mut width) = (from, from.abs().max(1.0));
+    let (mut previous, mut previous_ratio) = (None, None);
+    for _ in 0..MAX_PIECES {
+        let piece = quadrature(f, a, a + width, TOLERANCE)
+            .map_err(|_| NumericError::DivergentSeries)?
+            .value;
+        total += piece;
+        if piece.abs() <= f64::EPSILON * total.abs() {
+            return Ok(total);
+        }
+        if let Some(previous) = previous {
+            let ratio: f64 = piece / previous;
+            if ratio >= 1.0 - 1e-6 {
+                bail!(NumericError::DivergentSeries);
+            }
+            if previous_ratio.is_some_and(|r: f64| (ratio - r).abs() <= 1e-6) {
+                return Ok(total + piece * ratio / (1.0 - ratio));
+            }
+            previous_ratio = Some(ratio);
+        }
+        previous = Some(piece);
+        a += width;
+        width *= 2.0;
+    }
+    bail!(NumericError::DivergentSeries)
+}
+
+/// Sums `f(k)` for `k = start, start + 1, ...`. After [`TERMS`] terms the
+/// tail of an alternating series is estimated by repeatedly averaging the
+/// last partial sums. Any other tail is the integral of `f` from half a
+/// step before it, with the first Euler–Maclaurin correction.
+fn series(f: impl Fn(f64) -> Result<f64>, start: f64) -> Result<f64> {
+    let mut terms = Vec::with_capacity(TERMS);
+    let mut partial = Vec::with_capacity(TERMS);
+    let mut total = 0.0;
+    for i in 0..TERMS {
+        let value = term(&f, start + i as f64)?;
+        total += value;
+        terms.push(value);
+        partial.push(total);
+    }
+
+    let last = &terms[TERMS - AVERAGED..];
+    if last.iter().all(|value| *value == 0.0) {
+        return Ok(total);
+    }
+    if terms[TERMS - 1].abs() > DECAY * terms[TERMS / 2].abs() {
+        bail!(NumericError::DivergentSeries);
+    }
+
+    let alternating = last.windows(2).all(|pair| pair[0] * pair[1] < 0.0);
+    if alternating {
+        let mut sums = partial[TERMS - AVERAGED..].to_vec();
+        while sums.len() > 1 {
+            sums = sums
+                .windows(2)
+                .map(|pair| (pair[0] + pair[1]) / 2.0)
+                .collect();
+        }
+        return Ok(sums[0]);
+    }
+
+    let next = start + TERMS as f64;
+    // The midpoint rule is off by about f'/24 on a smooth tail
+    let slope = term(&f, next)? - terms[TERMS - 1];
+    Ok(total + tail(&f, next - 0.5)? + slope / 24.0)
+}
+
+/// Adds up `f(k)` for the integers `k` from `lower` to `upper`, either of
+/// which may be infinite. An empty range sums to 0.
+pub fn nsum(f: impl Fn(f64) -> Result<f64>, lower: f64, upper: f64) -> Result<f64> {
+    check_bound(lower)?;
+    check_bound(upper)?;
+    if lower > upper {
+        return Ok(0.0);
+    }
+
+    match (lower.is_finite(), upper.is_finite()) {
+        (true, true) => {
+            if upper - lower >= MAX_TERMS {
+                bail!(NumericError::TooManyTerms(upper - lower + 1.0));
+            }
+            let mut total = 0.0;
+            let mut k = lower;
+            while k <= upper {
+                total += term(&f, k)?;
+                k += 1.0;
+            }
+            Ok(total)
+        }
+        (true, false) => series(f, lower),
+        (false, true) => series(|k| f(-k), -upper),
+        (false, false) => Ok(series(&f, 0.0)? + series(|k| f(-k), 1.0)?),
+    }
+}
+
+/// Multiplies `f(k)` for the integers `k` from `lower` to `upper`. An empty
+/// range gives 1. An infinite product converges when the series of the
+/// logarithms of its factors does, so its factors have to be positive.
+pub fn nproduct(f: impl Fn(f64) -> Result<f64>, lower: f64, upper: f64) -> Result<f64> {
+    check_bound(lower)?;
+    check_bound(upper)?;
+    if lower > upper {
+        return Ok(1.0);
+    }
+
+    if lower.is_finite() && upper.is_finite() {
+        if upper - lower >= MAX_TERMS {
+            bail!(NumericError::TooManyTerms(upper - lower + 1.0));
+        }
+        let mut product = 1.0;
+        let mut k = lower;
+        while k <= upper {
+            product *= term(&f, k)?;
+            k += 1.0;
+        }
+        return Ok(product);
+    }
+    Ok(nsum(|k| Ok(f(k)?.ln()), lower, upper)?.exp())
+}
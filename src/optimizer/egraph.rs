@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::error::RuleError;
+use crate::parser::{Expr, Op};
+
+use super::{Bindings, Rule, RuleSet};
+
+/// Identifier of an equivalence class in an [`EGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClassId(u32);
+
+impl ClassId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The operator or leaf stored in an e-node. Floats are kept as bits so that
+/// e-nodes can be hashed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Symbol {
+    Number(u64),
+    Constant(String, u64),
+    Monomial {
+        coefficient: u64,
+        variable: String,
+        exponent: u64,
+    },
+    UnaryMinus,
+    BinOp(Op),
+    Function(String),
+    List,
+}
+
+/// A node whose children are equivalence classes rather than expressions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ENode {
+    pub symbol: Symbol,
+    pub children: Vec<ClassId>,
+}
+
+/// Cost model used to extract a single expression out of an e-graph.
+pub trait CostFunction {
+    /// Cost of `node` given the cost of the cheapest term of every child.
+    fn cost(&self, node: &ENode, children: &[f64]) -> f64;
+}
+
+/// Counts the nodes of the extracted tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AstSize;
+
+impl CostFunction for AstSize {
+    fn cost(&self, _node: &ENode, children: &[f64]) -> f64 {
+        1.0 + children.iter().sum::<f64>()
+    }
+}
+
+/// Measures the depth of the extracted tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AstDepth;
+
+impl CostFunction for AstDepth {
+    fn cost(&self, _node: &ENode, children: &[f64]) -> f64 {
+        1.0 + children.iter().cloned().fold(0.0, f64::max)
+    }
+}
+
+/// Budget and cost model for [`EGraph::saturate`] and
+/// [`Strategy::Saturation`](super::Strategy::Saturation).
+pub struct SaturationConfig {
+    pub node_limit: usize,
+    pub iteration_limit: usize,
+    /// Maximum number of matches a single rule may apply per iteration, which
+    /// keeps explosive rules such as associativity in check.
+    pub match_limit: usize,
+    pub cost: Box<dyn CostFunction>,
+}
+
+impl Default for SaturationConfig {
+    fn default() -> Self {
+        SaturationConfig {
+            node_limit: 5_000,
+            iteration_limit: 20,
+            match_limit: 500,
+            cost: Box::new(AstSize),
+        }
+    }
+}
+
+/// The symbol of a leaf, `None` for wildcards and nodes with children.
+fn leaf_symbol(expr: &Expr) -> Option<Symbol> {
+    Some(match expr {
+        Expr::Number(n) => Symbol::Number(n.to_bits()),
+        Expr::Constant { name, value } => Symbol::Constant(name.to_owned(), value.to_bits()),
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } => Symbol::Monomial {
+            coefficient: coefficient.to_bits(),
+            variable: variable.to_owned(),
+            exponent: exponent.to_bits(),
+        },
+        Expr::Wildcard(_)
+        | Expr::UnaryMinus(_)
+        | Expr::BinOp { .. }
+        | Expr::Function { .. }
+        | Expr::List(_) => return None,
+    })
+}
+
+#[derive(Debug, Default)]
+struct EClass {
+    nodes: Vec<ENode>,
+}
+
+/// Equality graph: a set of equivalence classes of expressions sharing
+/// structure through hash-consed e-nodes.
+#[derive(Debug, Default)]
+pub struct EGraph {
+    parents: Vec<ClassId>,
+    classes: Vec<EClass>,
+    memo: HashMap<ENode, ClassId>,
+}
+
+type ClassBindings = HashMap<String, ClassId>;
+
+impl EGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct e-nodes.
+    pub fn node_count(&self) -> usize {
+        self.memo.len()
+    }
+
+    pub fn find(&self, mut id: ClassId) -> ClassId {
+        while self.parents[id.index()] != id {
+            id = self.parents[id.index()];
+        }
+        id
+    }
+
+    fn canonical(&self, node: &ENode) -> ENode {
+        ENode {
+            symbol: node.symbol.clone(),
+            children: node
+                .children
+                .iter()
+                .map(|child| self.find(*child))
+                .collect(),
+        }
+    }
+
+    pub fn add(&mut self, node: ENode) -> ClassId {
+        let node = self.canonical(&node);
+        if let Some(id) = self.memo.get(&node) {
+            return self.find(*id);
+        }
+
+        let id = ClassId(self.classes.len() as u32);
+        self.parents.push(id);
+        self.classes.push(EClass {
+            nodes: vec![node.clone()],
+        });
+        self.memo.insert(node, id);
+        id
+    }
+
+    /// Adds every subterm of `expr`. Fails on wildcards, which only belong
+    /// in rule patterns.
+    pub fn add_expr(&mut self, expr: &Expr) -> Result<ClassId> {
+        let (symbol, children) = match expr {
+            Expr::UnaryMinus(inner) => (Symbol::UnaryMinus, vec![self.add_expr(inner)?]),
+            Expr::BinOp { lhs, op, rhs } => (
+                Symbol::BinOp(*op),
+                vec![self.add_expr(lhs)?, self.add_expr(rhs)?],
+            ),
+            Expr::Function { name, args } => (
+                Symbol::Function(name.to_owned()),
+                args.iter()
+                    .map(|arg| self.add_expr(arg))
+                    .collect::<Result<Vec<ClassId>>>()?,
+            ),
+            Expr::List(items) => (
+                Symbol::List,
+                items
+                    .iter()
+                    .map(|item| self.add_expr(item))
+                    .collect::<Result<Vec<ClassId>>>()?,
+            ),
+            leaf => match leaf_symbol(leaf) {
+                Some(symbol) => (symbol, Vec::new()),
+                None => bail!(RuleError::WildcardInGraph(leaf.to_string())),
+            },
+        };
+
+        Ok(self.add(ENode { symbol, children }))
+    }
+
+    /// Merges two classes, returning whether they were distinct.
+    pub fn union(&mut self, a: ClassId, b: ClassId) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if a < b { (a, b) } else { (b, a) };
+        self.parents[child.index()] = root;
+        let nodes = std::mem::take(&mut self.classes[child.index()].nodes);
+        self.classes[root.index()].nodes.extend(nodes);
+        true
+    }
+
+    /// Restores the congruence invariant: e-nodes that became identical after
+    /// a union have to live in the same class.
+    fn rebuild(&mut self) {
+        loop {
+            let mut memo: HashMap<ENode, ClassId> = HashMap::new();
+            let mut pending = Vec::new();
+
+            for index in 0..self.classes.len() {
+                let id = ClassId(index as u32);
+                if self.find(id) != id {
+                    continue;
+                }
+
+                let mut nodes: Vec<ENode> = self.classes[index]
+                    .nodes
+                    .iter()
+                    .map(|node| self.canonical(node))
+                    .collect();
+                nodes.sort();
+                nodes.dedup();
+
+                for node in &nodes {
+                    match memo.get(node) {
+                        Some(other) if *other != id => pending.push((*other, id)),
+                        Some(_) => (),
+                        None => {
+                            memo.insert(node.clone(), id);
+                        }
+                    }
+                }
+                self.classes[index].nodes = nodes;
+            }
+
+            self.memo = memo;
+            if pending.is_empty() {
+                return;
+            }
+
+            for (a, b) in pending {
+                self.union(a, b);
+            }
+        }
+    }
+
+    fn classes(&self) -> Vec<ClassId> {
+        (0..self.classes.len())
+            .map(|index| ClassId(index as u32))
+            .filter(|id| self.find(*id) == *id)
+            .collect()
+    }
+
+    fn nodes(&self, id: ClassId) -> &[ENode] {
+        &self.classes[self.find(id).index()].nodes
+    }
+
+    /// Collects up to `limit` ways in which `pattern` matches some term of
+    /// class `id`.
+    fn ematch(
+        &self,
+        pattern: &Expr,
+        id: ClassId,
+        bindings: &ClassBindings,
+        limit: usize,
+        out: &mut Vec<ClassBindings>,
+    ) {
+        let id = self.find(id);
+        let with_symbol = |symbol: Symbol| {
+            self.nodes(id)
+                .iter()
+                .filter(move |node| node.symbol == symbol)
+        };
+
+        match pattern {
+            Expr::Wildcard(name) => match bindings.get(name) {
+                Some(bound) if self.find(*bound) != id => (),
+                Some(_) => out.push(bindings.clone()),
+                None => {
+                    let mut bindings = bindings.clone();
+                    bindings.insert(name.to_owned(), id);
+                    out.push(bindings);
+                }
+            },
+            Expr::UnaryMinus(inner) => {
+                for node in with_symbol(Symbol::UnaryMinus) {
+                    self.ematch_all(&[inner], &node.children, bindings, limit, out);
+                }
+            }
+            Expr::BinOp { lhs, op, rhs } => {
+                for node in with_symbol(Symbol::BinOp(*op)) {
+                    self.ematch_all(&[lhs, rhs], &node.children, bindings, limit, out);
+                }
+            }
+            Expr::Function { name, args } => {
+                let args: Vec<&Expr> = args.iter().map(|arg| arg.as_ref()).collect();
+                for node in with_symbol(Symbol::Function(name.to_owned())) {
+                    if node.children.len() == args.len() {
+                        self.ematch_all(&args, &node.children, bindings, limit, out);
+                    }
+                }
+            }
+            Expr::List(items) => {
+                let items: Vec<&Expr> = items.iter().map(|item| item.as_ref()).collect();
+                for node in with_symbol(Symbol::List) {
+                    if node.children.len() == items.len() {
+                        self.ematch_all(&items, &node.children, bindings, limit, out);
+                    }
+                }
+            }
+            leaf => {
+                let symbol = leaf_symbol(leaf);
+                if symbol.is_some_and(|symbol| with_symbol(symbol).next().is_some()) {
+                    out.push(bindings.clone());
+                }
+            }
+        }
+        out.truncate(limit);
+    }
+
+    /// Matches each pattern against the class at the same position.
+    fn ematch_all(
+        &self,
+        patterns: &[&Expr],
+        ids: &[ClassId],
+        bindings: &ClassBindings,
+        limit: usize,
+        out: &mut Vec<ClassBindings>,
+    ) {
+        let Some((pattern, rest)) = patterns.split_first() else {
+            if out.len() < limit {
+                out.push(bindings.clone());
+            }
+            return;
+        };
+
+        let mut partial = Vec::new();
+        self.ematch(pattern, ids[0], bindings, limit - out.len(), &mut partial);
+        for bindings in partial {
+            if out.len() >= limit {
+                return;
+            }
+            self.ematch_all(rest, &ids[1..], &bindings, limit, out);
+        }
+    }
+
+    fn instantiate(&mut self, pattern: &Expr, bindings: &ClassBindings) -> Result<ClassId> {
+        let (symbol, children) = match pattern {
+            Expr::Wildcard(name) => match bindings.get(name) {
+                Some(id) => return Ok(*id),
+                None => bail!(RuleError::UnboundWildcard(name.to_owned())),
+            },
+            Expr::UnaryMinus(inner) => {
+                (Symbol::UnaryMinus, vec![self.instantiate(inner, bindings)?])
+            }
+            Expr::BinOp { lhs, op, rhs } => (
+                Symbol::BinOp(*op),
+                vec![
+                    self.instantiate(lhs, bindings)?,
+                    self.instantiate(rhs, bindings)?,
+                ],
+            ),
+            Expr::Function { name, args } => (
+                Symbol::Function(name.to_owned()),
+                args.iter()
+                    .map(|arg| self.instantiate(arg, bindings))
+                    .collect::<Result<Vec<ClassId>>>()?,
+            ),
+            Expr::List(items) => (
+                Symbol::List,
+                items
+                    .iter()
+                    .map(|item| self.instantiate(item, bindings))
+                    .collect::<Result<Vec<ClassId>>>()?,
+            ),
+            leaf => return self.add_expr(leaf),
+        };
+
+        Ok(self.add(ENode { symbol, children }))
+    }
+
+    /// Applies `rule` wherever it matches. Conditions and computed rewrites see
+    /// the cheapest term of each bound class.
+    fn apply_rule(
+        &mut self,
+        rule: &Rule,
+        matches: Vec<(ClassId, ClassBindings)>,
+        best: &HashMap<ClassId, (f64, ENode)>,
+        node_limit: usize,
+    ) -> bool {
+        let mut changed = false;
+
+        for (id, class_bindings) in matches {
+            if self.node_count() > node_limit {
+                break;
+            }
+
+            let bindings: Bindings = if rule.inspects_bindings() {
+                class_bindings
+                    .iter()
+                    .map(|(name, class)| (name.to_owned(), self.build(best, *class)))
+                    .collect()
+            } else {
+                Bindings::new()
+            };
+            if !rule.check(&bindings) {
+                continue;
+            }
+
+            let rewritten = match rule.rewrite_pattern() {
+                Some(pattern) => self.instantiate(pattern, &class_bindings),
+                None => match rule.compute(&bindings) {
+                    Some(expr) => self.add_expr(&expr),
+                    None => continue,
+                },
+            };
+            // Rules added unchecked may leave a wildcard unbound
+            if let Ok(rewritten) = rewritten {
+                changed |= self.union(id, rewritten);
+            }
+        }
+
+        changed
+    }
+
+    /// Runs the rules until nothing changes or the budget runs out.
+    pub fn saturate(&mut self, rules: &RuleSet, config: &SaturationConfig) {
+        for _ in 0..config.iteration_limit {
+            let classes = self.classes();
+            let matches: Vec<Vec<(ClassId, ClassBindings)>> = rules
+                .rules()
+                .iter()
+                .map(|rule| {
+                    let mut matches = Vec::new();
+                    for id in &classes {
+                        let limit = config.match_limit - matches.len();
+                        let mut found = Vec::new();
+                        self.ematch(
+                            rule.pattern(),
+                            *id,
+                            &ClassBindings::new(),
+                            limit,
+                            &mut found,
+                        );
+                        matches.extend(found.into_iter().map(|bindings| (*id, bindings)));
+                        if matches.len() >= config.match_limit {
+                            break;
+                        }
+                    }
+                    matches
+                })
+                .collect();
+
+            // Matches only refer to classes that existed before this round, and
+            // unions keep the oldest root, so this table covers every binding.
+            let best = self.best_nodes(&AstSize);
+            let mut changed = false;
+            for (rule, matches) in rules.rules().iter().zip(matches) {
+                changed |= self.apply_rule(rule, matches, &best, config.node_limit);
+                if self.node_count() > config.node_limit {
+                    break;
+                }
+            }
+            self.rebuild();
+
+            if !changed || self.node_count() > config.node_limit {
+                return;
+            }
+        }
+    }
+
+    fn best_nodes(&self, cost: &dyn CostFunction) -> HashMap<ClassId, (f64, ENode)> {
+        let mut best: HashMap<ClassId, (f64, ENode)> = HashMap::new();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+            for id in self.classes() {
+                for node in self.nodes(id) {
+                    let children: Option<Vec<f64>> = node
+                        .children
+                        .iter()
+                        .map(|child| best.get(&self.find(*child)).map(|(cost, _)| *cost))
+                        .collect();
+                    let Some(children) = children else {
+                        continue;
+                    };
+
+                    let node_cost = cost.cost(node, &children);
+                    if best.get(&id).is_none_or(|(old, _)| node_cost < *old) {
+                        best.insert(id, (node_cost, node.clone()));
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    fn build(&self, best: &HashMap<ClassId, (f64, ENode)>, id: ClassId) -> Expr {
+        let (_, node) = &best[&self.find(id)];
+        let mut children = node.children.iter().map(|child| self.build(best, *child));
+
+        match &node.symbol {
+            Symbol::Number(bits) => Expr::Number(f64::from_bits(*bits)),
+            Symbol::Constant(name, value) => Expr::Constant {
+                name: name.to_owned(),
+                value: f64::from_bits(*value),
+            },
+            Symbol::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => Expr::Monomial {
+                coefficient: f64::from_bits(*coefficient),
+                variable: variable.to_owned(),
+                exponent: f64::from_bits(*exponent),
+            },
+            Symbol::UnaryMinus => Expr::UnaryMinus(Box::new(children.next().unwrap())),
+            Symbol::BinOp(op) => Expr::BinOp {
+                lhs: Box::new(children.next().unwrap()),
+                op: *op,
+                rhs: Box::new(children.next().unwrap()),
+            },
+            Symbol::Function(name) => Expr::Function {
+                name: name.to_owned(),
+                args: children.map(Box::new).collect(),
+            },
+            Symbol::List => Expr::List(children.map(Box::new).collect()),
+        }
+    }
+
+    /// Extracts the cheapest term of class `id` under `cost`.
+    pub fn extract(&self, id: ClassId, cost: &dyn CostFunction) -> Expr {
+        self.build(&self.best_nodes(cost), id)
+    }
+}
@@ -0,0 +1,245 @@
+use crate::parser::{func, num, var, Expr, Op, Optimize};
+use crate::rule;
+
+use super::{standard_rules, Bindings, Rule, RuleSet};
+
+lazy_static::lazy_static! {
+    static ref EXPAND_RULES: RuleSet = log_expand_rules();
+    static ref COMBINE_RULES: RuleSet = log_combine_rules();
+}
+
+/// Builds a logarithm of `x` with the same function, and base for
+/// `log(?c, ?a)`, as the one that matched.
+fn logarithm(name: &str, bindings: &Bindings, x: Expr) -> Expr {
+    match bindings.get("c") {
+        Some(base) => func(name, [base.clone(), x]),
+        None => func(name, [x]),
+    }
+}
+
+/// `e^ln(a) = a` only holds for positive `a`, so the rules decline when
+/// `a` is a number that is not.
+fn maybe_positive(bindings: &Bindings) -> bool {
+    maybe_positive_expr(&bindings["a"])
+}
+
+/// Whether `x` is not a number known to be zero or negative.
+fn maybe_positive_expr(x: &Expr) -> bool {
+    match x {
+        Expr::Number(value) => *value > 0.0,
+        Expr::UnaryMinus(inner) => !matches!(**inner, Expr::Number(_)),
+        _ => true,
+    }
+}
+
+/// `log(b, b) = 1` needs a positive base other than 1, so the rules decline
+/// when the base `c` is a number that is not.
+fn valid_base(bindings: &Bindings) -> bool {
+    let base = &bindings["c"];
+    maybe_positive_expr(base) && *base != Expr::Number(1.0)
+}
+
+/// Evaluates logarithms of exact powers of their base, e.g. `log10(1000) =
+/// 3` or `log2(0.5) = -1`.
+fn exact_log(base: f64, bindings: &Bindings) -> Option<Expr> {
+    let Expr::Number(x) = bindings["a"] else {
+        return None;
+    };
+    if base <= 0.0 || base == 1.0 {
+        return None;
+    }
+    if x == 1.0 {
+        return Some(num(0.0));
+    }
+    if base == std::f64::consts::E || x <= 0.0 {
+        return None;
+    }
+
+    let exponent = (x.ln() / base.ln()).round();
+    (base.powf(exponent) == x).then(|| num(exponent))
+}
+
+/// Identities that always make an expression simpler: `ln(e^x) = x`, `e^ln(x)
+/// = x`, exact values and the change of base `ln(x)/ln(b) = log(b, x)`.
+/// They are part of [`standard_rules`].
+pub fn log_rules() -> RuleSet {
+    let rules = [
+        // exp(a) = e^a
+        rule!("exp(?a)" => "e^?a"),
+        // log(e, a) = ln(a), log(10, a) = log10(a), log(2, a) = log2(a)
+        rule!("log(e, ?a)" => "ln(?a)"),
+        rule!("log(10, ?a)" => "log10(?a)"),
+        rule!("log(2, ?a)" => "log2(?a)"),
+        // ln(e) = 1, log(b, b) = 1
+        rule!("ln(e)" => "1"),
+        rule!("log(?c, ?c)" => "1", if valid_base),
+        // ln(e^a) = a
+        rule!("ln(e^?a)" => "?a"),
+        rule!("log(10^?a)" => "?a"),
+        rule!("log10(10^?a)" => "?a"),
+        rule!("log2(2^?a)" => "?a"),
+        rule!("log(?c, ?c^?a)" => "?a", if valid_base),
+        // e^ln(a) = a
+        rule!("e^ln(?a)" => "?a", if maybe_positive),
+        rule!("10^log(?a)" => "?a", if maybe_positive),
+        rule!("10^log10(?a)" => "?a", if maybe_positive),
+        rule!("2^log2(?a)" => "?a", if maybe_positive),
+        rule!("?c^log(?c, ?a)" => "?a", if |b| valid_base(b) && maybe_positive(b)),
+        // log10(100) = 2 and friends
+        Rule::computed("ln(?a)", |b| exact_log(std::f64::consts::E, b)).unwrap(),
+        Rule::computed("log(?a)", |b| exact_log(10.0, b)).unwrap(),
+        Rule::computed("log10(?a)", |b| exact_log(10.0, b)).unwrap(),
+        Rule::computed("log2(?a)", |b| exact_log(2.0, b)).unwrap(),
+        Rule::computed("log(?c, ?a)", |b| match b["c"] {
+            Expr::Number(base) => exact_log(base, b),
+            _ => None,
+        })
+        .unwrap(),
+        // ln(a)/ln(b) = log(b, a)
+        rule!("ln(?a) / ln(?c)" => "log(?c, ?a)"),
+        rule!("log(?a) / log(?c)" => "log(?c, ?a)"),
+        rule!("log10(?a) / log10(?c)" => "log(?c, ?a)"),
+        rule!("log2(?a) / log2(?c)" => "log(?c, ?a)"),
+        rule!("log(?d, ?a) / log(?d, ?c)" => "log(?c, ?a)"),
+    ];
+
+    let mut set = RuleSet::new();
+    for rule in rules {
+        set.add(rule).expect("log rules must terminate");
+    }
+    set
+}
+
+/// Splits the logarithm of a product, quotient or power, e.g. `ln(A*B) =
+/// ln(A) + ln(B)`. A monomial such as `3X^2` counts as a product. The
+/// identities need positive parts, so e.g. `ln((-2)*(-3))` is left alone.
+fn expand_log(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let log = |x: &Expr| logarithm(name, bindings, x.clone());
+    Some(match &bindings["a"] {
+        Expr::BinOp { lhs, op, rhs } => match op {
+            Op::Multiply | Op::Divide if !maybe_positive_expr(lhs) || !maybe_positive_expr(rhs) => {
+                return None
+            }
+            Op::Multiply => log(lhs) + log(rhs),
+            Op::Divide => log(lhs) - log(rhs),
+            Op::Power if !maybe_positive_expr(lhs) => return None,
+            Op::Power => (**rhs).clone() * log(lhs),
+            _ => return None,
+        },
+        Expr::Function { name: root, args }
+            if root == "sqrt" && args.len() == 1 && maybe_positive_expr(&args[0]) =>
+        {
+            log(&args[0]) / 2
+        }
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } => {
+            if *coefficient > 0.0 && *coefficient != 1.0 {
+                let power = var(variable).pow(*exponent);
+                log(&num(*coefficient)) + log(&power)
+            } else if *coefficient == 1.0 && *exponent != 1.0 {
+                num(*exponent) * log(&var(variable))
+            } else {
+                return None;
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// The rules of [`expandlog`].
+pub fn log_expand_rules() -> RuleSet {
+    let rules = [
+        // ln(a*b) = ln(a) + ln(b), ln(a/b) = ln(a) - ln(b), ln(a^n) = n ln(a)
+        Rule::computed("ln(?a)", |b| expand_log("ln", b)).unwrap(),
+        Rule::computed("log(?a)", |b| expand_log("log", b)).unwrap(),
+        Rule::computed("log2(?a)", |b| expand_log("log2", b)).unwrap(),
+        Rule::computed("log10(?a)", |b| expand_log("log10", b)).unwrap(),
+        Rule::computed("log(?c, ?a)", |b| expand_log("log", b)).unwrap(),
+    ];
+
+    let mut set = standard_rules();
+    for rule in rules {
+        set.add(rule).expect("log rules must terminate");
+    }
+    set
+}
+
+/// Merges a sum or difference of two logarithms with the same base.
+fn combine_log(name: &str, op: Op, bindings: &Bindings) -> Option<Expr> {
+    let (a, b) = (bindings["a"].clone(), bindings["b"].clone());
+    let x = match op {
+        Op::Add => a * b,
+        _ => a / b,
+    };
+    Some(logarithm(name, bindings, x))
+}
+
+/// `n ln(a) = ln(a^n)` for a number `n`.
+fn combine_multiple(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let Expr::Number(n) = bindings["n"] else {
+        return None;
+    };
+    Some(logarithm(name, bindings, bindings["a"].clone().pow(n)))
+}
+
+/// The rules of [`combinelog`].
+pub fn log_combine_rules() -> RuleSet {
+    let rules = [
+        // n ln(a) = ln(a^n)
+        Rule::computed("?n * ln(?a)", |b| combine_multiple("ln", b)).unwrap(),
+        Rule::computed("?n * log(?a)", |b| combine_multiple("log", b)).unwrap(),
+        Rule::computed("?n * log2(?a)", |b| combine_multiple("log2", b)).unwrap(),
+        Rule::computed("?n * log10(?a)", |b| combine_multiple("log10", b)).unwrap(),
+        Rule::computed("?n * log(?c, ?a)", |b| combine_multiple("log", b)).unwrap(),
+        // ln(a) + ln(b) = ln(a*b)
+        Rule::computed("ln(?a) + ln(?b)", |b| combine_log("ln", Op::Add, b)).unwrap(),
+        Rule::computed("log(?a) + log(?b)", |b| combine_log("log", Op::Add, b)).unwrap(),
+        Rule::computed("log2(?a) + log2(?b)", |b| combine_log("log2", Op::Add, b)).unwrap(),
+        Rule::computed("log10(?a) + log10(?b)", |b| {
+            combine_log("log10", Op::Add, b)
+        })
+        .unwrap(),
+        Rule::computed("log(?c, ?a) + log(?c, ?b)", |b| {
+            combine_log("log", Op::Add, b)
+        })
+        .unwrap(),
+        // ln(a) - ln(b) = ln(a/b)
+        Rule::computed("ln(?a) - ln(?b)", |b| combine_log("ln", Op::Subtract, b)).unwrap(),
+        Rule::computed("log(?a) - log(?b)", |b| combine_log("log", Op::Subtract, b)).unwrap(),
+        Rule::computed("log2(?a) - log2(?b)", |b| {
+            combine_log("log2", Op::Subtract, b)
+        })
+        .unwrap(),
+        Rule::computed("log10(?a) - log10(?b)", |b| {
+            combine_log("log10", Op::Subtract, b)
+        })
+        .unwrap(),
+        Rule::computed("log(?c, ?a) - log(?c, ?b)", |b| {
+            combine_log("log", Op::Subtract, b)
+        })
+        .unwrap(),
+    ];
+
+    let mut set = standard_rules();
+    for rule in rules {
+        set.add(rule).expect("log rules must terminate");
+    }
+    set
+}
+
+/// Splits logarithms of products, quotients and powers into sums, so that
+/// `ln(X^2*Y)` becomes `2ln(X) + ln(Y)`. Symbolic parts are assumed to be
+/// positive, where these identities hold, while numbers that are not
+/// positive are left inside the logarithm.
+pub fn expandlog(expr: &Expr) -> Expr {
+    EXPAND_RULES.rewrite(expr.clone()).optimize_expression()
+}
+
+/// Merges sums and multiples of logarithms with the same base into a single
+/// logarithm, undoing [`expandlog`].
+pub fn combinelog(expr: &Expr) -> Expr {
+    COMBINE_RULES.rewrite(expr.clone()).optimize_expression()
+}
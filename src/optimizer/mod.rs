@@ -1,249 +1,210 @@
-use crate::parser::{Expr, Op, Optimize};
+mod egraph;
+mod log;
+mod rules;
+mod trig;
+
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::parser::{var, Expr, Op, Optimize};
+use crate::polynomial::Polynomial;
+use crate::rule;
+use crate::solver::{solve, Solution};
+
+pub use egraph::{
+    AstDepth, AstSize, ClassId, CostFunction, EGraph, ENode, SaturationConfig, Symbol,
+};
+pub use log::{combinelog, expandlog, log_combine_rules, log_expand_rules, log_rules};
+pub use rules::{matches, substitute, Bindings, Condition, Rule, RuleSet, Transform};
+pub use trig::{trig_expand_rules, trig_reduce_rules, trig_rules, trigexpand, trigreduce};
+
+lazy_static::lazy_static! {
+    static ref STANDARD_RULES: RuleSet = standard_rules();
+    static ref ALGEBRAIC_RULES: RuleSet = algebraic_rules();
+}
 
-impl Optimize for Expr {
-    fn optimize_expression(self) -> Expr {
-        let mut old = self.clone();
-        let mut latest = self.optimize_node();
+fn is_monomial(expr: &Expr) -> bool {
+    matches!(expr, Expr::Monomial { .. })
+}
+
+fn both_monomials(bindings: &Bindings) -> bool {
+    is_monomial(&bindings["a"]) && is_monomial(&bindings["b"])
+}
+
+/// Combines numbers and monomials of a single variable as [`Polynomial`]s,
+/// declining when the result would need more than one term.
+fn combine_monomials(
+    bindings: &Bindings,
+    op: fn(Polynomial, Polynomial) -> Polynomial,
+) -> Option<Expr> {
+    let a = Polynomial::from_expr(&bindings["a"]).ok()?;
+    let b = Polynomial::from_expr(&bindings["b"]).ok()?;
+    let result = op(a, b);
+    (result.len() <= 1 && result.variables().len() <= 1).then(|| result.to_expr())
+}
+
+/// The algebraic identities shared by [`standard_rules`] and
+/// [`algebraic_rules`].
+fn basic_rules() -> RuleSet {
+    let rules = [
+        // -(-a) = a
+        rule!("-(-?a)" => "?a"),
+        // -0 = 0
+        rule!("-0" => "0"),
+        // 0 + a = a
+        rule!("0 + ?a" => "?a"),
+        // a + 0 = a
+        rule!("?a + 0" => "?a"),
+        // a - a = 0
+        rule!("?a - ?a" => "0"),
+        // 0 - a = -a
+        rule!("0 - ?a" => "-(?a)"),
+        // a - 0 = a
+        rule!("?a - 0" => "?a"),
+        // 1 * a = a
+        rule!("1 * ?a" => "?a"),
+        // a * 1 = a
+        rule!("?a * 1" => "?a"),
+        // 0 * a = 0
+        rule!("0 * ?a" => "0"),
+        // a * 0 = 0
+        rule!("?a * 0" => "0"),
+        // a * a = a^2
+        rule!("?a * ?a" => "?a^2", if |b| !is_monomial(&b["a"])),
+        // a^b * a^c = a^(b+c)
+        rule!("?a^?b * ?a^?c" => "?a^(?b+?c)"),
+        // a^1 = a
+        rule!("?a^1" => "?a"),
+        // a^-n = 1/(a^n)
+        rule!("?a^(-?n)" => "1/(?a^?n)", if |b| matches!(b["n"], Expr::Number(_))),
+        // a / 1 = a
+        rule!("?a / 1" => "?a"),
+        // a / a = 1
+        rule!("?a / ?a" => "1"),
+        // aX^b + cX^b = (a+c)X^b
+        Rule::computed("?a + ?b", |b| combine_monomials(b, |a, b| a + b))
+            .unwrap()
+            .when(both_monomials),
+        // aX^b * cX^d = (a*c)X^(b+d)
+        Rule::computed("?a * ?b", |b| combine_monomials(b, |a, b| a * b))
+            .unwrap()
+            .when(both_monomials),
+    ];
+
+    let mut set = RuleSet::new();
+    for rule in rules {
+        set.add(rule).expect("standard rules must terminate");
+    }
+    set
+}
+
+/// The identities applied by [`Optimize`], in priority order.
+pub fn standard_rules() -> RuleSet {
+    let mut set = basic_rules();
+    set.extend(&trig_rules());
+    set.extend(&log_rules());
+    set
+}
+
+fn fold_numbers(bindings: &Bindings, op: fn(f64, f64) -> f64) -> Option<Expr> {
+    match (&bindings["a"], &bindings["b"]) {
+        (Expr::Number(a), Expr::Number(b)) => {
+            let value = op(*a, *b);
+            // Negative results would not round-trip through the parser
+            (value.is_finite() && value >= 0.0).then_some(Expr::Number(value))
+        }
+        _ => None,
+    }
+}
+
+/// Rewrites that hold in both directions, such as commutativity and
+/// distributivity. They would loop forever under [`Optimize`], so they are
+/// only meant for equality saturation where the e-graph keeps every form.
+/// The trig and log identities are left out since [`simplify`] already seeds the
+/// graph with their greedy result.
+pub fn algebraic_rules() -> RuleSet {
+    let mut set = basic_rules();
+    let rules = [
+        rule!("?a + ?b" => "?b + ?a"),
+        rule!("?a * ?b" => "?b * ?a"),
+        rule!("(?a + ?b) + ?c" => "?a + (?b + ?c)"),
+        rule!("?a + (?b + ?c)" => "(?a + ?b) + ?c"),
+        rule!("(?a * ?b) * ?c" => "?a * (?b * ?c)"),
+        rule!("?a * (?b * ?c)" => "(?a * ?b) * ?c"),
+        rule!("?a - ?b" => "?a + -(?b)"),
+        rule!("?a + -(?b)" => "?a - ?b"),
+        rule!("?a + -(?a)" => "0"),
+        rule!("-(?a) * ?b" => "-(?a * ?b)"),
+        rule!("?a * (?b + ?c)" => "?a * ?b + ?a * ?c"),
+        rule!("?a * ?b + ?a * ?c" => "?a * (?b + ?c)"),
+        rule!("?a + ?a" => "2 * ?a"),
+        rule!("?a / ?b" => "?a * ?b^(-1)"),
+        rule!("?a * ?b^(-1)" => "?a / ?b"),
+        Rule::computed("?a + ?b", |b| fold_numbers(b, |a, b| a + b)).unwrap(),
+        Rule::computed("?a - ?b", |b| fold_numbers(b, |a, b| a - b)).unwrap(),
+        Rule::computed("?a * ?b", |b| fold_numbers(b, |a, b| a * b)).unwrap(),
+        // c * aX^b = (c*a)X^b
+        Rule::computed("?a * ?b", |b| combine_monomials(b, |a, b| a * b))
+            .unwrap()
+            .when(|b| matches!(b["a"], Expr::Number(_)) && is_monomial(&b["b"])),
+    ];
+
+    for rule in rules {
+        set.add_unchecked(rule);
+    }
+    set
+}
+
+/// How [`simplify`] searches for a simpler form.
+pub enum Strategy {
+    /// Apply [`standard_rules`] greedily until a fixpoint, like
+    /// [`Optimize::optimize_expression`].
+    Greedy,
+    /// Saturate an e-graph with [`algebraic_rules`] and extract the cheapest
+    /// equivalent term.
+    Saturation(SaturationConfig),
+}
 
-        while old != latest {
-            old = latest.clone();
-            latest = latest.optimize_node();
+pub fn simplify(expr: Expr, strategy: &Strategy) -> Expr {
+    match strategy {
+        Strategy::Greedy => expr.optimize_expression(),
+        Strategy::Saturation(config) => {
+            // Seeding the graph with the greedy result means saturation can
+            // only improve on it, even when the budget runs out early.
+            let mut graph = EGraph::new();
+            let greedy = expr.clone().optimize_expression();
+            let (Ok(root), Ok(greedy_root)) = (graph.add_expr(&expr), graph.add_expr(&greedy))
+            else {
+                // Only patterns have wildcards, which have no place in a graph
+                return greedy;
+            };
+            graph.union(root, greedy_root);
+            graph.saturate(&ALGEBRAIC_RULES, config);
+            graph.extract(root, config.cost.as_ref())
         }
+    }
+}
 
-        latest
+impl Optimize for Expr {
+    fn optimize_expression(self) -> Expr {
+        STANDARD_RULES.rewrite(self)
     }
 
     fn optimize_node(&self) -> Expr {
-        match self {
-            Expr::UnaryMinus(inner) => {
-                let inner = *inner.to_owned();
-                // -(-a) => a
-                if let Expr::UnaryMinus(inner_inner) = inner {
-                    return *inner_inner;
-                }
-
-                // -0 = 0
-                if let Expr::Number(inner_n) = inner {
-                    if inner_n == 0.0 {
-                        return Expr::Number(0.0);
-                    }
-                }
-
-                Expr::UnaryMinus(Box::new(inner.optimize_node()))
-            }
-            Expr::BinOp { lhs, op, rhs } => {
-                let optimized_lhs = lhs.optimize_node();
-                let optimized_rhs = rhs.optimize_node();
-
-                // 0 + a = a
-                if let (Expr::Number(num), Op::Add) = (&optimized_lhs, &op) {
-                    if num == &0.0 {
-                        return optimized_rhs;
-                    }
-                }
-
-                // a + 0 = a
-                if let (Expr::Number(num), Op::Add) = (&optimized_rhs, &op) {
-                    if num == &0.0 {
-                        return optimized_lhs;
-                    }
-                }
-
-                // a - a = 0
-                if let Op::Subtract = op {
-                    if optimized_lhs == optimized_rhs {
-                        return Expr::Number(0.0);
-                    }
-                }
-
-                // 0 - a = a
-                if let (Expr::Number(num), Op::Subtract) = (&optimized_lhs, &op) {
-                    if num == &0.0 {
-                        return optimized_rhs;
-                    }
-                }
-
-                // a - 0 = a
-                if let (Expr::Number(num), Op::Subtract) = (&optimized_rhs, &op) {
-                    if num == &0.0 {
-                        return optimized_lhs;
-                    }
-                }
-
-                // 1 * a = a
-                if let (Expr::Number(num), Op::Multiply) = (&optimized_lhs, &op) {
-                    if num == &1.0 {
-                        return optimized_rhs;
-                    }
-                }
-
-                // a * 1 = a
-                if let (Expr::Number(num), Op::Multiply) = (&optimized_rhs, &op) {
-                    if num == &1.0 {
-                        return optimized_lhs;
-                    }
-                }
-
-                // 0 * a = 0
-                if let (Expr::Number(num), Op::Multiply) = (&optimized_lhs, &op) {
-                    if num == &0.0 {
-                        return Expr::Number(0.0);
-                    }
-                }
-
-                // a * 0 = 0
-                if let (Expr::Number(num), Op::Multiply) = (&optimized_rhs, &op) {
-                    if num == &0.0 {
-                        return Expr::Number(0.0);
-                    }
-                }
-
-                // a * a = a^2
-                if let Op::Multiply = op {
-                    if optimized_lhs == optimized_rhs {
-                        if let (Expr::Monomial { .. }, Expr::Monomial { .. }) =
-                            (&optimized_lhs, &optimized_rhs)
-                        {
-                        } else {
-                            return Expr::BinOp {
-                                lhs: Box::new(optimized_lhs),
-                                op: Op::Power,
-                                rhs: Box::new(Expr::Number(2.0)),
-                            };
-                        }
-                    }
-                }
-
-                // a^b * a^c = a^(b+c)
-                if let Op::Multiply = op {
-                    if let (
-                        Expr::BinOp {
-                            lhs: left_lhs,
-                            op: left_operator,
-                            rhs: left_rhs,
-                        },
-                        Expr::BinOp {
-                            lhs: right_lhs,
-                            op: right_operator,
-                            rhs: right_rhs,
-                        },
-                    ) = (&optimized_lhs, &optimized_rhs)
-                    {
-                        if let (Op::Power, Op::Power) = (left_operator, right_operator) {
-                            if left_lhs == right_lhs {
-                                return Expr::BinOp {
-                                    lhs: Box::new(*left_lhs.to_owned()),
-                                    op: Op::Power,
-                                    rhs: Box::new(Expr::BinOp {
-                                        lhs: left_rhs.to_owned(),
-                                        op: Op::Add,
-                                        rhs: right_rhs.to_owned(),
-                                    }),
-                                };
-                            }
-                        }
-                    }
-                }
-
-                // a^1 = a
-                if let (Expr::Number(n), Op::Power) = (&optimized_rhs, op) {
-                    if n == &1.0 {
-                        return optimized_lhs;
-                    }
-                }
-
-                // a^-n = 1/(a^n)
-                if let (Expr::UnaryMinus(inner), Op::Power) = (&optimized_rhs, op) {
-                    let inner = *inner.to_owned();
-
-                    if let Expr::Number(n) = inner {
-                        return Expr::BinOp {
-                            lhs: Box::new(Expr::Number(1.0)),
-                            op: Op::Divide,
-                            rhs: Box::new(Expr::BinOp {
-                                lhs: Box::new(optimized_lhs),
-                                op: Op::Power,
-                                rhs: Box::new(Expr::Number(n)),
-                            }),
-                        };
-                    }
-                }
-
-                // a / 1 = a
-                if let (Expr::Number(num), Op::Divide) = (&optimized_rhs, &op) {
-                    if num == &1.0 {
-                        return optimized_lhs;
-                    }
-                }
-
-                // a / a = 1
-                if let Op::Divide = op {
-                    if optimized_lhs == optimized_rhs {
-                        return Expr::Number(1.0);
-                    }
-                }
-
-                // aX^b + cX^d = (a+b)X^(b+d)
-                if let (
-                    Expr::Monomial {
-                        coefficient: left_coefficient,
-                        variable: left_variable,
-                        exponent: left_exponent,
-                    },
-                    Expr::Monomial {
-                        coefficient: right_coefficient,
-                        variable: right_variable,
-                        exponent: right_exponent,
-                    },
-                    Op::Add,
-                ) = (&optimized_lhs, &optimized_rhs, op)
-                {
-                    if left_variable == right_variable && left_exponent == right_exponent {
-                        return Expr::Monomial {
-                            coefficient: left_coefficient + right_coefficient,
-                            variable: left_variable.to_owned(),
-                            exponent: left_exponent.to_owned(),
-                        };
-                    }
-                }
-
-                // aX^b * cX^d = (a*b)X^(b*d)
-                if let (
-                    Expr::Monomial {
-                        coefficient: left_coefficient,
-                        variable: left_variable,
-                        exponent: left_exponent,
-                    },
-                    Expr::Monomial {
-                        coefficient: right_coefficient,
-                        variable: right_variable,
-                        exponent: right_exponent,
-                    },
-                    Op::Multiply,
-                ) = (&optimized_lhs, &optimized_rhs, op)
-                {
-                    if left_variable == right_variable {
-                        return Expr::Monomial {
-                            coefficient: left_coefficient * right_coefficient,
-                            variable: left_variable.to_owned(),
-                            exponent: left_exponent + right_exponent,
-                        };
-                    }
-                }
-
-                Expr::BinOp {
-                    lhs: Box::new(optimized_lhs),
-                    op: *op,
-                    rhs: Box::new(optimized_rhs),
-                }
-            }
-            Expr::Number(n) => Expr::Number(*n),
-            Expr::Monomial { .. } => return self.clone(),
-            token => todo!("Optimizing for '{token:?}' not implemented yet!"),
-        }
+        STANDARD_RULES.rewrite_node(self)
     }
 
-    fn optimize_equation(self) -> Expr {
-        todo!()
+    fn optimize_equation(self, variable: &str) -> Result<Expr> {
+        match solve(&self, variable)? {
+            Solution::Finite(mut roots) if roots.len() == 1 => Ok(Expr::BinOp {
+                lhs: Box::new(var(variable)),
+                op: Op::Equals,
+                rhs: Box::new(roots.remove(0)),
+            }),
+            Solution::Finite(roots) => bail!(SolverError::MultipleSolutions(roots.len())),
+            Solution::Empty => bail!(SolverError::NoSolution),
+            Solution::All => bail!(SolverError::InfiniteSolutions),
+            Solution::Intervals(_) => bail!(SolverError::NotAnEquation),
+        }
     }
 }
@@ -0,0 +1,534 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+
+use crate::dag::{ExprId, ExprPool, Node};
+use crate::error::RuleError;
+use crate::parser::{parse_pattern, Expr};
+
+/// Expressions captured by the wildcards of a pattern, keyed by wildcard name
+/// without the leading `?`.
+pub type Bindings = HashMap<String, Expr>;
+
+/// Node ids captured by the wildcards of a pattern matched in an
+/// [`ExprPool`].
+type IdBindings = HashMap<String, ExprId>;
+
+/// Side condition that has to hold for a rule to fire.
+pub type Condition = fn(&Bindings) -> bool;
+
+/// Builds the replacement from the captured bindings, or declines by
+/// returning `None`.
+pub type Transform = fn(&Bindings) -> Option<Expr>;
+
+#[derive(Debug, Clone)]
+enum Rewrite {
+    Pattern(Expr),
+    Computed(Transform),
+}
+
+/// A single rewrite rule such as `?a * 1 => ?a`.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pattern: Expr,
+    rewrite: Rewrite,
+    condition: Option<Condition>,
+}
+
+/// Creates a [`Rule`] from two pattern strings, optionally followed by a side
+/// condition, e.g. `rule!("?a * 1" => "?a")` or
+/// `rule!("?a^(-?n)" => "1/(?a^?n)", if |b| is_number(&b["n"]))`.
+/// Panics if either pattern fails to parse.
+#[macro_export]
+macro_rules! rule {
+    ($pattern:expr => $rewrite:expr) => {
+        $crate::optimizer::Rule::new($pattern, $rewrite).expect("invalid rule pattern")
+    };
+    ($pattern:expr => $rewrite:expr, if $condition:expr) => {
+        $crate::optimizer::Rule::new($pattern, $rewrite)
+            .expect("invalid rule pattern")
+            .when($condition)
+    };
+}
+
+impl Rule {
+    pub fn new(pattern: &str, rewrite: &str) -> Result<Rule> {
+        Ok(Rule {
+            pattern: parse_pattern(pattern)?,
+            rewrite: Rewrite::Pattern(parse_pattern(rewrite)?),
+            condition: None,
+        })
+    }
+
+    /// Creates a rule whose replacement is computed from the bindings, for
+    /// identities that need arithmetic on the matched values.
+    pub fn computed(pattern: &str, transform: Transform) -> Result<Rule> {
+        Ok(Rule {
+            pattern: parse_pattern(pattern)?,
+            rewrite: Rewrite::Computed(transform),
+            condition: None,
+        })
+    }
+
+    pub fn when(mut self, condition: Condition) -> Rule {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub fn pattern(&self) -> &Expr {
+        &self.pattern
+    }
+
+    /// The replacement pattern, or `None` for computed rules.
+    pub(super) fn rewrite_pattern(&self) -> Option<&Expr> {
+        match &self.rewrite {
+            Rewrite::Pattern(rewrite) => Some(rewrite),
+            Rewrite::Computed(_) => None,
+        }
+    }
+
+    /// Whether firing the rule needs concrete expressions for its bindings.
+    pub(super) fn inspects_bindings(&self) -> bool {
+        self.condition.is_some() || matches!(self.rewrite, Rewrite::Computed(_))
+    }
+
+    pub(super) fn check(&self, bindings: &Bindings) -> bool {
+        self.condition.is_none_or(|condition| condition(bindings))
+    }
+
+    pub(super) fn compute(&self, bindings: &Bindings) -> Option<Expr> {
+        match &self.rewrite {
+            Rewrite::Pattern(rewrite) => Some(substitute(rewrite, bindings)),
+            Rewrite::Computed(transform) => transform(bindings),
+        }
+    }
+
+    /// Matches the rule against the root of `expr` and returns the rewritten
+    /// expression if it fires.
+    pub fn apply(&self, expr: &Expr) -> Option<Expr> {
+        let mut bindings = Bindings::new();
+        if !matches(&self.pattern, expr, &mut bindings) {
+            return None;
+        }
+
+        if !self.check(&bindings) {
+            return None;
+        }
+
+        self.compute(&bindings)
+    }
+
+    /// Like [`Rule::apply`] for the node `id` of `pool`, adding the rewritten
+    /// expression to `pool`. Bindings only become expressions for side
+    /// conditions and computed rewrites.
+    pub fn apply_id(&self, pool: &mut ExprPool, id: ExprId) -> Option<ExprId> {
+        let mut ids = IdBindings::new();
+        let mut repeated = Vec::new();
+        if !matches_id(&self.pattern, pool, id, &mut ids, &mut repeated) {
+            return None;
+        }
+        if repeated
+            .into_iter()
+            .any(|(a, b)| pool.canonical(a) != pool.canonical(b))
+        {
+            return None;
+        }
+
+        let bindings: Bindings = if self.inspects_bindings() {
+            ids.iter()
+                .map(|(name, id)| (name.to_owned(), pool.to_expr(*id)))
+                .collect()
+        } else {
+            Bindings::new()
+        };
+        if !self.check(&bindings) {
+            return None;
+        }
+
+        match &self.rewrite {
+            Rewrite::Pattern(rewrite) => Some(substitute_id(rewrite, pool, &ids)),
+            Rewrite::Computed(transform) => Some(pool.intern(&transform(&bindings)?)),
+        }
+    }
+
+    /// Rejects rules that can obviously never terminate: patterns that match
+    /// everything, rewrites that use unbound wildcards and rewrites that
+    /// reproduce their own pattern.
+    fn validate(&self) -> Result<()> {
+        if let Expr::Wildcard(_) = self.pattern {
+            bail!(RuleError::MatchesEverything);
+        }
+
+        if let Rewrite::Pattern(rewrite) = &self.rewrite {
+            let mut bound = HashSet::new();
+            wildcards(&self.pattern, &mut bound);
+            let mut used = HashSet::new();
+            wildcards(rewrite, &mut used);
+
+            if let Some(name) = used.difference(&bound).next() {
+                bail!(RuleError::UnboundWildcard(name.to_owned()));
+            }
+
+            if contains_match(&self.pattern, rewrite) {
+                bail!(RuleError::NonTerminating);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Ordered collection of rules. Earlier rules take priority when several
+/// match the same node.
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    max_iterations: usize,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            rules: Vec::new(),
+            max_iterations: 1000,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Appends a rule after validating that it can terminate.
+    pub fn add(&mut self, rule: Rule) -> Result<()> {
+        rule.validate()?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    /// Appends a rule without the termination checks. Only useful for
+    /// strategies that bound the work themselves, such as equality
+    /// saturation where commutativity is a perfectly fine rule.
+    pub fn add_unchecked(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    /// Appends every rule of `other`, keeping their order.
+    pub fn extend(&mut self, other: &RuleSet) {
+        self.rules.extend(other.rules.iter().cloned());
+    }
+
+    /// Caps the number of passes made by [`RuleSet::rewrite`].
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Makes a single bottom-up pass: children are rewritten first, then the
+    /// first matching rule is applied to the node itself.
+    pub fn rewrite_node(&self, expr: &Expr) -> Expr {
+        let mut pool = ExprPool::new();
+        let root = pool.intern(expr);
+        let root = self.pass(&mut pool, root, &mut HashMap::new());
+        pool.to_expr(root)
+    }
+
+    /// Rewrites until a fixpoint is reached, a previous form reappears or the
+    /// iteration limit is hit.
+    pub fn rewrite(&self, expr: Expr) -> Expr {
+        let mut pool = ExprPool::new();
+        let root = pool.intern(&expr);
+        let root = self.rewrite_id(&mut pool, root);
+        pool.to_expr(root)
+    }
+
+    /// Like [`RuleSet::rewrite`], but works on the hash-consed nodes of
+    /// `pool`. A subexpression shared by several parents, or seen again in a
+    /// later pass, is only rewritten once.
+    pub fn rewrite_id(&self, pool: &mut ExprPool, root: ExprId) -> ExprId {
+        // Rewriting a node is a pure function of its id, so one pass's
+        // results stay valid for every following pass.
+        let mut passes = HashMap::new();
+        let mut seen = HashSet::from([root]);
+
+        let mut latest = root;
+        for _ in 0..self.max_iterations {
+            latest = self.pass(pool, latest, &mut passes);
+            if !seen.insert(latest) {
+                break;
+            }
+        }
+
+        latest
+    }
+
+    fn pass(&self, pool: &mut ExprPool, id: ExprId, done: &mut HashMap<ExprId, ExprId>) -> ExprId {
+        if let Some(result) = done.get(&id) {
+            return *result;
+        }
+
+        let node = match pool.get(id).clone() {
+            Node::UnaryMinus(inner) => Node::UnaryMinus(self.pass(pool, inner, done)),
+            Node::BinOp { lhs, op, rhs } => Node::BinOp {
+                lhs: self.pass(pool, lhs, done),
+                op,
+                rhs: self.pass(pool, rhs, done),
+            },
+            Node::Function { name, args } => Node::Function {
+                name,
+                args: args
+                    .into_iter()
+                    .map(|arg| self.pass(pool, arg, done))
+                    .collect(),
+            },
+            Node::List(items) => Node::List(
+                items
+                    .into_iter()
+                    .map(|item| self.pass(pool, item, done))
+                    .collect(),
+            ),
+            node => node,
+        };
+        let rebuilt = pool.add(node);
+
+        let result = self
+            .rules
+            .iter()
+            .find_map(|rule| rule.apply_id(pool, rebuilt))
+            .unwrap_or(rebuilt);
+        done.insert(id, result);
+        result
+    }
+}
+
+/// [`matches`] against a node of an [`ExprPool`]. Wildcards bind node ids.
+/// When a wildcard that occurs more than once binds different nodes, the
+/// pair is pushed to `repeated`, to be compared in canonical form.
+fn matches_id(
+    pattern: &Expr,
+    pool: &ExprPool,
+    id: ExprId,
+    bindings: &mut IdBindings,
+    repeated: &mut Vec<(ExprId, ExprId)>,
+) -> bool {
+    match (pattern, pool.get(id)) {
+        (Expr::Wildcard(name), _) => {
+            match bindings.get(name) {
+                Some(bound) if *bound != id => repeated.push((*bound, id)),
+                Some(_) => {}
+                None => {
+                    bindings.insert(name.to_owned(), id);
+                }
+            }
+            true
+        }
+        (Expr::Number(a), Node::Number(b)) => a == b,
+        (Expr::Constant { name: a, .. }, Node::Constant { name: b, .. }) => a == b,
+        (
+            Expr::Monomial {
+                coefficient: a_coefficient,
+                variable: a_variable,
+                exponent: a_exponent,
+            },
+            Node::Monomial {
+                coefficient: b_coefficient,
+                variable: b_variable,
+                exponent: b_exponent,
+            },
+        ) => a_coefficient == b_coefficient && a_variable == b_variable && a_exponent == b_exponent,
+        (Expr::UnaryMinus(pattern), Node::UnaryMinus(inner)) => {
+            matches_id(pattern, pool, *inner, bindings, repeated)
+        }
+        (
+            Expr::BinOp {
+                lhs: pattern_lhs,
+                op: pattern_op,
+                rhs: pattern_rhs,
+            },
+            Node::BinOp { lhs, op, rhs },
+        ) => {
+            pattern_op == op
+                && matches_id(pattern_lhs, pool, *lhs, bindings, repeated)
+                && matches_id(pattern_rhs, pool, *rhs, bindings, repeated)
+        }
+        (
+            Expr::Function {
+                name: pattern_name,
+                args: pattern_args,
+            },
+            Node::Function { name, args },
+        ) => {
+            pattern_name == name
+                && pattern_args.len() == args.len()
+                && pattern_args
+                    .iter()
+                    .zip(args.iter())
+                    .all(|(pattern, arg)| matches_id(pattern, pool, *arg, bindings, repeated))
+        }
+        (Expr::List(pattern_items), Node::List(items)) => {
+            pattern_items.len() == items.len()
+                && pattern_items
+                    .iter()
+                    .zip(items.iter())
+                    .all(|(pattern, item)| matches_id(pattern, pool, *item, bindings, repeated))
+        }
+        _ => false,
+    }
+}
+
+/// Structural match of `pattern` against `expr`. A wildcard that occurs more
+/// than once has to bind equal (in the canonical sense) expressions.
+pub fn matches(pattern: &Expr, expr: &Expr, bindings: &mut Bindings) -> bool {
+    match (pattern, expr) {
+        (Expr::Wildcard(name), expr) => match bindings.get(name) {
+            Some(bound) => bound.canonical_eq(expr),
+            None => {
+                bindings.insert(name.to_owned(), expr.clone());
+                true
+            }
+        },
+        (Expr::Number(a), Expr::Number(b)) => a == b,
+        (Expr::Constant { name: a, .. }, Expr::Constant { name: b, .. }) => a == b,
+        (
+            Expr::Monomial {
+                coefficient: a_coefficient,
+                variable: a_variable,
+                exponent: a_exponent,
+            },
+            Expr::Monomial {
+                coefficient: b_coefficient,
+                variable: b_variable,
+                exponent: b_exponent,
+            },
+        ) => a_coefficient == b_coefficient && a_variable == b_variable && a_exponent == b_exponent,
+        (Expr::UnaryMinus(pattern), Expr::UnaryMinus(expr)) => matches(pattern, expr, bindings),
+        (
+            Expr::BinOp {
+                lhs: pattern_lhs,
+                op: pattern_op,
+                rhs: pattern_rhs,
+            },
+            Expr::BinOp { lhs, op, rhs },
+        ) => {
+            pattern_op == op
+                && matches(pattern_lhs, lhs, bindings)
+                && matches(pattern_rhs, rhs, bindings)
+        }
+        (
+            Expr::Function {
+                name: pattern_name,
+                args: pattern_args,
+            },
+            Expr::Function { name, args },
+        ) => {
+            pattern_name == name
+                && pattern_args.len() == args.len()
+                && pattern_args
+                    .iter()
+                    .zip(args.iter())
+                    .all(|(pattern, arg)| matches(pattern, arg, bindings))
+        }
+        (Expr::List(pattern_items), Expr::List(items)) => {
+            pattern_items.len() == items.len()
+                && pattern_items
+                    .iter()
+                    .zip(items.iter())
+                    .all(|(pattern, item)| matches(pattern, item, bindings))
+        }
+        _ => false,
+    }
+}
+
+/// [`substitute`] into an [`ExprPool`], with bindings given as node ids.
+fn substitute_id(pattern: &Expr, pool: &mut ExprPool, bindings: &IdBindings) -> ExprId {
+    let node = match pattern {
+        Expr::Wildcard(name) => return bindings[name],
+        Expr::UnaryMinus(inner) => Node::UnaryMinus(substitute_id(inner, pool, bindings)),
+        Expr::BinOp { lhs, op, rhs } => Node::BinOp {
+            lhs: substitute_id(lhs, pool, bindings),
+            op: *op,
+            rhs: substitute_id(rhs, pool, bindings),
+        },
+        Expr::Function { name, args } => Node::Function {
+            name: name.to_owned(),
+            args: args
+                .iter()
+                .map(|arg| substitute_id(arg, pool, bindings))
+                .collect(),
+        },
+        Expr::List(items) => Node::List(
+            items
+                .iter()
+                .map(|item| substitute_id(item, pool, bindings))
+                .collect(),
+        ),
+        expr => return pool.intern(expr),
+    };
+    pool.add(node)
+}
+
+/// Replaces every wildcard in `pattern` with its binding.
+pub fn substitute(pattern: &Expr, bindings: &Bindings) -> Expr {
+    match pattern {
+        Expr::Wildcard(name) => bindings[name].clone(),
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(Box::new(substitute(inner, bindings))),
+        Expr::BinOp { lhs, op, rhs } => Expr::BinOp {
+            lhs: Box::new(substitute(lhs, bindings)),
+            op: *op,
+            rhs: Box::new(substitute(rhs, bindings)),
+        },
+        Expr::Function { name, args } => Expr::Function {
+            name: name.to_owned(),
+            args: args
+                .iter()
+                .map(|arg| Box::new(substitute(arg, bindings)))
+                .collect(),
+        },
+        Expr::List(items) => Expr::List(
+            items
+                .iter()
+                .map(|item| Box::new(substitute(item, bindings)))
+                .collect(),
+        ),
+        expr => expr.clone(),
+    }
+}
+
+fn wildcards(expr: &Expr, names: &mut HashSet<String>) {
+    match expr {
+        Expr::Wildcard(name) => {
+            names.insert(name.to_owned());
+        }
+        Expr::UnaryMinus(inner) => wildcards(inner, names),
+        Expr::BinOp { lhs, rhs, .. } => {
+            wildcards(lhs, names);
+            wildcards(rhs, names);
+        }
+        Expr::Function { args, .. } | Expr::List(args) => {
+            args.iter().for_each(|arg| wildcards(arg, names))
+        }
+        _ => (),
+    }
+}
+
+fn contains_match(pattern: &Expr, expr: &Expr) -> bool {
+    if matches(pattern, expr, &mut Bindings::new()) {
+        return true;
+    }
+
+    match expr {
+        Expr::UnaryMinus(inner) => contains_match(pattern, inner),
+        Expr::BinOp { lhs, rhs, .. } => {
+            contains_match(pattern, lhs) || contains_match(pattern, rhs)
+        }
+        Expr::Function { args, .. } | Expr::List(args) => {
+            args.iter().any(|arg| contains_match(pattern, arg))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,355 @@
+use crate::math::Rational;
+use crate::parser::{func, num, Expr, Op, Optimize};
+use crate::polynomial::Polynomial;
+use crate::rule;
+
+use super::{standard_rules, Bindings, Rule, RuleSet};
+
+lazy_static::lazy_static! {
+    static ref EXPAND_RULES: RuleSet = trig_expand_rules();
+    static ref COMBINE_RULES: RuleSet = trig_combine_rules();
+    static ref REDUCE_RULES: RuleSet = trig_reduce_rules();
+}
+
+/// Highest power of a sine or cosine that [`trigreduce`] turns into
+/// multiple angles.
+const MAX_REDUCED_POWER: usize = 32;
+
+/// Multiplies an angle by `factor`, exactly when it is a polynomial so that
+/// `2 * (X/2)` comes out as `X`.
+fn scale(angle: &Expr, factor: Rational) -> Expr {
+    match Polynomial::from_expr(angle) {
+        Ok(angle) => (angle * Polynomial::constant(factor)).to_expr(),
+        Err(_) => Expr::from(factor) * angle.clone(),
+    }
+}
+
+/// Half the sum or difference of two angles, as needed by the
+/// sum-to-product identities.
+fn half(a: &Expr, b: &Expr, op: Op) -> Expr {
+    let factor = Rational::new(1, 2).unwrap();
+    match (Polynomial::from_expr(a), Polynomial::from_expr(b)) {
+        (Ok(a), Ok(b)) => {
+            let sum = if op == Op::Add { a + b } else { a - b };
+            (sum * Polynomial::constant(factor)).to_expr()
+        }
+        _ => {
+            let sum = if op == Op::Add {
+                a.clone() + b.clone()
+            } else {
+                a.clone() - b.clone()
+            };
+            sum / 2
+        }
+    }
+}
+
+/// Splits an angle like `3X` or `3*(X+Y)` into a whole multiple of at least
+/// 2 and the angle that is multiplied.
+fn multiple(angle: &Expr) -> Option<(f64, Expr)> {
+    let is_multiple = |n: f64| n >= 2.0 && n.fract() == 0.0;
+    match angle {
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } if is_multiple(*coefficient) => Some((
+            *coefficient,
+            Expr::Monomial {
+                coefficient: 1.0,
+                variable: variable.to_owned(),
+                exponent: *exponent,
+            },
+        )),
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => match **lhs {
+            Expr::Number(n) if is_multiple(n) => Some((n, (**rhs).clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The exact sine of a whole number of degrees when its reference angle is
+/// 0, 30, 45, 60 or 90 degrees.
+fn exact_sine(degrees: f64) -> Option<Expr> {
+    let angle = degrees.rem_euclid(360.0);
+    let reference = match angle {
+        a if a <= 90.0 => a,
+        a if a <= 180.0 => 180.0 - a,
+        a if a <= 270.0 => a - 180.0,
+        a => 360.0 - a,
+    };
+    let value = match reference {
+        0.0 => return Some(num(0.0)),
+        30.0 => num(1.0) / 2,
+        45.0 => func("sqrt", [num(2.0)]) / 2,
+        60.0 => func("sqrt", [num(3.0)]) / 2,
+        90.0 => num(1.0),
+        _ => return None,
+    };
+    Some(if angle > 180.0 { -value } else { value })
+}
+
+/// The exact tangent of a whole number of degrees, `None` where it is
+/// undefined.
+fn exact_tangent(degrees: f64) -> Option<Expr> {
+    let angle = degrees.rem_euclid(180.0);
+    let reference = if angle <= 90.0 { angle } else { 180.0 - angle };
+    let value = match reference {
+        0.0 => return Some(num(0.0)),
+        30.0 => func("sqrt", [num(3.0)]) / 3,
+        45.0 => num(1.0),
+        60.0 => func("sqrt", [num(3.0)]),
+        _ => return None,
+    };
+    Some(if angle > 90.0 { -value } else { value })
+}
+
+/// Evaluates `sin`, `cos` and `tan` of special angles in degrees, e.g.
+/// `sin(30) = 1/2`.
+fn exact_value(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let Expr::Number(degrees) = bindings["a"] else {
+        return None;
+    };
+    match name {
+        "sin" => exact_sine(degrees),
+        "cos" => exact_sine(degrees + 90.0),
+        _ => exact_tangent(degrees),
+    }
+}
+
+/// Pulls the sign out of a negated angle such as `-(X)` or `-2X`.
+fn parity(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let angle = match &bindings["a"] {
+        Expr::UnaryMinus(inner) => (**inner).clone(),
+        Expr::Monomial {
+            coefficient,
+            variable,
+            exponent,
+        } if *coefficient < 0.0 => Expr::Monomial {
+            coefficient: -coefficient,
+            variable: variable.to_owned(),
+            exponent: *exponent,
+        },
+        _ => return None,
+    };
+    let value = func(name, [angle]);
+    Some(if name == "cos" { value } else { -value })
+}
+
+/// Identities that always make an expression simpler: parity, `sin^2 +
+/// cos^2 = 1` and the exact values at special angles. They are part of
+/// [`standard_rules`].
+pub fn trig_rules() -> RuleSet {
+    let rules = [
+        // sin(-a) = -sin(a), cos(-a) = cos(a), tan(-a) = -tan(a)
+        Rule::computed("sin(?a)", |b| parity("sin", b)).unwrap(),
+        Rule::computed("cos(?a)", |b| parity("cos", b)).unwrap(),
+        Rule::computed("tan(?a)", |b| parity("tan", b)).unwrap(),
+        // sin(a)^2 + cos(a)^2 = 1
+        rule!("sin(?a)^2 + cos(?a)^2" => "1"),
+        rule!("cos(?a)^2 + sin(?a)^2" => "1"),
+        rule!("(?b + sin(?a)^2) + cos(?a)^2" => "?b + 1"),
+        rule!("(?b + cos(?a)^2) + sin(?a)^2" => "?b + 1"),
+        // 1 - sin(a)^2 = cos(a)^2
+        rule!("1 - sin(?a)^2" => "cos(?a)^2"),
+        rule!("1 - cos(?a)^2" => "sin(?a)^2"),
+        // sin(30) = 1/2 and friends
+        Rule::computed("sin(?a)", |b| exact_value("sin", b)).unwrap(),
+        Rule::computed("cos(?a)", |b| exact_value("cos", b)).unwrap(),
+        Rule::computed("tan(?a)", |b| exact_value("tan", b)).unwrap(),
+    ];
+
+    let mut set = RuleSet::new();
+    for rule in rules {
+        set.add(rule).expect("trig rules must terminate");
+    }
+    set
+}
+
+/// `sin(nX)` in terms of `sin(X)` and `cos((n-1)X)`, by the double angle
+/// identity for `n = 2` and the angle sum identity above that.
+fn expand_multiple(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let (n, angle) = multiple(&bindings["a"])?;
+    let (sin, cos) = (func("sin", [angle.clone()]), func("cos", [angle.clone()]));
+    if n == 2.0 {
+        return Some(match name {
+            "sin" => 2 * sin * cos,
+            _ => cos.pow(2) - sin.pow(2),
+        });
+    }
+
+    let rest = scale(&angle, Rational::from_f64(n - 1.0)?);
+    let (sin_rest, cos_rest) = (func("sin", [rest.clone()]), func("cos", [rest]));
+    Some(match name {
+        "sin" => sin * cos_rest + cos * sin_rest,
+        _ => cos * cos_rest - sin * sin_rest,
+    })
+}
+
+/// The rules of [`trigexpand`]: angle sums and multiples are split up and
+/// `tan` is written as `sin/cos`.
+pub fn trig_expand_rules() -> RuleSet {
+    let rules = [
+        // tan(a) = sin(a)/cos(a)
+        rule!("tan(?a)" => "sin(?a) / cos(?a)"),
+        // sin(a+b) = sin(a)cos(b) + cos(a)sin(b)
+        rule!("sin(?a + ?b)" => "sin(?a)*cos(?b) + cos(?a)*sin(?b)"),
+        rule!("sin(?a - ?b)" => "sin(?a)*cos(?b) - cos(?a)*sin(?b)"),
+        // cos(a+b) = cos(a)cos(b) - sin(a)sin(b)
+        rule!("cos(?a + ?b)" => "cos(?a)*cos(?b) - sin(?a)*sin(?b)"),
+        rule!("cos(?a - ?b)" => "cos(?a)*cos(?b) + sin(?a)*sin(?b)"),
+        // sin(2a) = 2sin(a)cos(a), cos(2a) = cos(a)^2 - sin(a)^2
+        Rule::computed("sin(?a)", |b| expand_multiple("sin", b)).unwrap(),
+        Rule::computed("cos(?a)", |b| expand_multiple("cos", b)).unwrap(),
+    ];
+
+    let mut set = standard_rules();
+    for rule in rules {
+        set.add(rule).expect("trig rules must terminate");
+    }
+    set
+}
+
+/// `n sin(a)cos(a) = (n/2) sin(2a)`.
+fn double_angle(bindings: &Bindings) -> Option<Expr> {
+    let n = match bindings.get("n") {
+        Some(Expr::Number(n)) => *n,
+        Some(_) => return None,
+        None => 1.0,
+    };
+    let sine = func("sin", [scale(&bindings["a"], Rational::integer(2))]);
+    Some(match n {
+        2.0 => sine,
+        n => num(n) / 2 * sine,
+    })
+}
+
+/// The first pass of [`trigreduce`], which folds products and quotients
+/// into double angles and `tan` before any power is rewritten.
+fn trig_combine_rules() -> RuleSet {
+    let rules = [
+        // sin(a)/cos(a) = tan(a)
+        rule!("sin(?a) / cos(?a)" => "tan(?a)"),
+        rule!("cos(?a) / sin(?a)" => "1 / tan(?a)"),
+        // cos(a)^2 - sin(a)^2 = cos(2a)
+        Rule::computed("cos(?a)^2 - sin(?a)^2", |b| {
+            Some(func("cos", [scale(&b["a"], Rational::integer(2))]))
+        })
+        .unwrap(),
+        // 2sin(a)cos(a) = sin(2a)
+        Rule::computed("sin(?a) * cos(?a)", double_angle).unwrap(),
+        Rule::computed("cos(?a) * sin(?a)", double_angle).unwrap(),
+        Rule::computed("(?n * sin(?a)) * cos(?a)", double_angle).unwrap(),
+        Rule::computed("(?n * cos(?a)) * sin(?a)", double_angle).unwrap(),
+    ];
+
+    let mut set = standard_rules();
+    for rule in rules {
+        set.add(rule).expect("trig rules must terminate");
+    }
+    set
+}
+
+/// `sin(a) ± sin(b)` or `cos(a) ± cos(b)` as a product.
+fn sum_to_product(first: &str, op: Op, bindings: &Bindings) -> Option<Expr> {
+    let (a, b) = (&bindings["a"], &bindings["b"]);
+    let (sum, difference) = (half(a, b, Op::Add), half(a, b, Op::Subtract));
+    let (sin, cos) = (|x: Expr| func("sin", [x]), |x: Expr| func("cos", [x]));
+    Some(match (first, op) {
+        // sin(a) + sin(b) = 2sin((a+b)/2)cos((a-b)/2)
+        ("sin", Op::Add) => 2 * sin(sum) * cos(difference),
+        // sin(a) - sin(b) = 2cos((a+b)/2)sin((a-b)/2)
+        ("sin", _) => 2 * cos(sum) * sin(difference),
+        // cos(a) + cos(b) = 2cos((a+b)/2)cos((a-b)/2)
+        (_, Op::Add) => 2 * cos(sum) * cos(difference),
+        // cos(a) - cos(b) = -2sin((a+b)/2)sin((a-b)/2)
+        _ => -(2 * sin(sum) * sin(difference)),
+    })
+}
+
+/// Writes `sin(a)^n` or `cos(a)^n` as a sum of multiple angles divided by
+/// `2^(n-1)`, pairing up the terms of `((e^ia ± e^-ia)/2)^n`, e.g. `cos(a)^3
+/// = (3cos(a) + cos(3a))/4` or `sin(a)^2 = (1 - cos(2a))/2`.
+fn reduce_power(name: &str, bindings: &Bindings) -> Option<Expr> {
+    let Expr::Number(n) = bindings["n"] else {
+        return None;
+    };
+    if n < 2.0 || n > MAX_REDUCED_POWER as f64 || n.fract() != 0.0 {
+        return None;
+    }
+    let n = n as i128;
+    let angle = |k: i128| scale(&bindings["a"], Rational::integer(n - 2 * k));
+
+    // C(n, k) for k = 0..=n/2
+    let mut binomials = vec![1i128];
+    for k in 0..n / 2 {
+        binomials.push(binomials[k as usize] * (n - k) / (k + 1));
+    }
+
+    // The middle term of an even power is constant
+    let mut sum = (n % 2 == 0).then(|| num((binomials[n as usize / 2] / 2) as f64));
+    for k in (0..(n + 1) / 2).rev() {
+        // sin(a)^n alternates in sign, counted from the highest angle
+        let negative = name == "sin" && (n / 2 - k) % 2 == 1;
+        let term = match (name, n % 2) {
+            ("sin", 1) => func("sin", [angle(k)]),
+            _ => func("cos", [angle(k)]),
+        };
+        let term = match binomials[k as usize] {
+            1 => term,
+            c => num(c as f64) * term,
+        };
+        sum = Some(match (sum, negative) {
+            (Some(sum), true) => sum - term,
+            (Some(sum), false) => sum + term,
+            (None, true) => -term,
+            (None, false) => term,
+        });
+    }
+    Some(sum? / 2f64.powi(n as i32 - 1))
+}
+
+/// The rules of [`trigreduce`] after the first pass: powers become
+/// multiple angles and sums of sines or cosines become products.
+pub fn trig_reduce_rules() -> RuleSet {
+    let rules = [
+        // sin(a)^2 = (1 - cos(2a))/2, cos(a)^3 = (3cos(a) + cos(3a))/4
+        Rule::computed("sin(?a)^?n", |b| reduce_power("sin", b)).unwrap(),
+        Rule::computed("cos(?a)^?n", |b| reduce_power("cos", b)).unwrap(),
+        Rule::computed("sin(?a) + sin(?b)", |b| sum_to_product("sin", Op::Add, b)).unwrap(),
+        Rule::computed("sin(?a) - sin(?b)", |b| {
+            sum_to_product("sin", Op::Subtract, b)
+        })
+        .unwrap(),
+        Rule::computed("cos(?a) + cos(?b)", |b| sum_to_product("cos", Op::Add, b)).unwrap(),
+        Rule::computed("cos(?a) - cos(?b)", |b| {
+            sum_to_product("cos", Op::Subtract, b)
+        })
+        .unwrap(),
+    ];
+
+    let mut set = standard_rules();
+    for rule in rules {
+        set.add(rule).expect("trig rules must terminate");
+    }
+    set
+}
+
+/// Expands sines and cosines of sums and multiples of angles, so that
+/// `sin(2X)` becomes `2sin(X)cos(X)`, and writes `tan` as `sin/cos`.
+pub fn trigexpand(expr: &Expr) -> Expr {
+    EXPAND_RULES.rewrite(expr.clone()).optimize_expression()
+}
+
+/// Rewrites products and powers of sines and cosines as multiple angles
+/// and sums of them as products, so that `sin(X)cos(X)` becomes
+/// `sin(2X)/2`. It undoes [`trigexpand`].
+pub fn trigreduce(expr: &Expr) -> Expr {
+    let combined = COMBINE_RULES.rewrite(expr.clone());
+    REDUCE_RULES.rewrite(combined).optimize_expression()
+}
@@ -0,0 +1,129 @@
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use super::{Expr, Op};
+
+/// Creates a variable, the same node the parser produces for a bare `X`.
+pub fn var(name: &str) -> Expr {
+    Expr::Monomial {
+        coefficient: 1.0,
+        variable: name.to_string(),
+        exponent: 1.0,
+    }
+}
+
+/// Creates a number literal. The grammar has no negative literals, so a
+/// negative value is wrapped in a unary minus just like `-2` would be.
+pub fn num(value: f64) -> Expr {
+    if value.is_sign_negative() && value != 0.0 {
+        Expr::UnaryMinus(Box::new(Expr::Number(-value)))
+    } else {
+        Expr::Number(value)
+    }
+}
+
+/// Creates a function call such as `func("max", [num(1.0), var("X")])`.
+pub fn func<I>(name: &str, args: I) -> Expr
+where
+    I: IntoIterator<Item = Expr>,
+{
+    Expr::Function {
+        name: name.to_string(),
+        args: args.into_iter().map(Box::new).collect(),
+    }
+}
+
+/// Raises `base` to `exponent`, see [`Expr::pow`].
+pub fn pow(base: impl Into<Expr>, exponent: impl Into<Expr>) -> Expr {
+    base.into().pow(exponent)
+}
+
+impl Expr {
+    /// Raises the expression to `exponent`. A plain variable raised to a
+    /// non-negative number collapses into a monomial, matching how `X^2` is
+    /// parsed.
+    pub fn pow(self, exponent: impl Into<Expr>) -> Expr {
+        let exponent = exponent.into();
+
+        if let (
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent: old_exponent,
+            },
+            Expr::Number(n),
+        ) = (&self, &exponent)
+        {
+            if *coefficient == 1.0 && *old_exponent == 1.0 {
+                return Expr::Monomial {
+                    coefficient: 1.0,
+                    variable: variable.to_owned(),
+                    exponent: *n,
+                };
+            }
+        }
+
+        Expr::BinOp {
+            lhs: Box::new(self),
+            op: Op::Power,
+            rhs: Box::new(exponent),
+        }
+    }
+}
+
+impl From<f64> for Expr {
+    fn from(value: f64) -> Self {
+        num(value)
+    }
+}
+
+impl From<i32> for Expr {
+    fn from(value: i32) -> Self {
+        num(value as f64)
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        Expr::UnaryMinus(Box::new(self))
+    }
+}
+
+macro_rules! impl_bin_op {
+    ($trait:ident, $method:ident, $op:expr) => {
+        impl<T: Into<Expr>> $trait<T> for Expr {
+            type Output = Expr;
+
+            fn $method(self, rhs: T) -> Expr {
+                Expr::BinOp {
+                    lhs: Box::new(self),
+                    op: $op,
+                    rhs: Box::new(rhs.into()),
+                }
+            }
+        }
+
+        impl $trait<Expr> for f64 {
+            type Output = Expr;
+
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::from(self).$method(rhs)
+            }
+        }
+
+        impl $trait<Expr> for i32 {
+            type Output = Expr;
+
+            fn $method(self, rhs: Expr) -> Expr {
+                Expr::from(self).$method(rhs)
+            }
+        }
+    };
+}
+
+impl_bin_op!(Add, add, Op::Add);
+impl_bin_op!(Sub, sub, Op::Subtract);
+impl_bin_op!(Mul, mul, Op::Multiply);
+impl_bin_op!(Div, div, Op::Divide);
+impl_bin_op!(Rem, rem, Op::Modulo);
@@ -1,5 +1,7 @@
+mod builder;
 mod parser;
 mod token;
 
-pub use parser::{parse, parse_equation};
-pub use token::{Expr, Op, Optimize};
\ No newline at end of file
+pub use builder::{func, num, pow, var};
+pub use parser::{parse, parse_equation, parse_pattern};
+pub use token::{Expr, Op, Optimize};
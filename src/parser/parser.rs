@@ -6,7 +6,7 @@ use pest::{iterators::Pairs, pratt_parser::Assoc};
 use crate::error::ParserError;
 use crate::math::CONSTANTS_DATABASE;
 
-use super::{Expr, Op};
+use super::{func, var, Expr, Op};
 
 #[derive(pest_derive::Parser)]
 #[grammar = "grammar/sedenion.pest"]
@@ -19,11 +19,16 @@ lazy_static::lazy_static! {
 
         // Precedence is defined lowest to highest
         PrattParser::new()
+            .op(Op::infix(equals, Left)
+                | Op::infix(less, Left)
+                | Op::infix(greater, Left)
+                | Op::infix(less_equal, Left)
+                | Op::infix(greater_equal, Left)
+                | Op::infix(not_equal, Left))
             .op(Op::infix(add, Left) | Op::infix(subtract, Left))
             .op(Op::infix(multiply, Left) | Op::infix(divide, Left) | Op::infix(modulo, Left))
             .op(Op::infix(power, Right))
             .op(Op::prefix(unary_minus))
-            .op(Op::infix(equals, Left))
         };
 }
 
@@ -54,6 +59,20 @@ fn parse_function(pairs: Pairs<Rule>) -> Result<Expr> {
     }
 }
 
+fn parse_derivative(pairs: Pairs<Rule>) -> Result<Expr> {
+    let mut variable: Option<String> = None;
+    let mut expr: Option<Expr> = None;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::variable => variable = Some(pair.as_str().to_string()),
+            Rule::expr => expr = Some(parse_expr(pair.into_inner())?),
+            rule => bail!(ParserError::InvalidToken(format!("{:?}", rule))),
+        }
+    }
+
+    Ok(func("diff", [expr.unwrap(), var(&variable.unwrap())]))
+}
+
 fn parse_monomial(pairs: Pairs<Rule>) -> Result<Expr> {
     let mut coefficient: Option<f64> = None;
     let mut exponent: Option<f64> = None;
@@ -87,6 +106,14 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<Expr> {
             Rule::expr => parse_expr(primary.into_inner()),
             Rule::function => parse_function(primary.into_inner()),
             Rule::monomial => parse_monomial(primary.into_inner()),
+            Rule::derivative => parse_derivative(primary.into_inner()),
+            Rule::list => Ok(Expr::List(
+                primary
+                    .into_inner()
+                    .map(|item| parse_expr(item.into_inner()).map(Box::new))
+                    .collect::<Result<Vec<Box<Expr>>>>()?,
+            )),
+            Rule::wildcard => Ok(Expr::Wildcard(primary.as_str()[1..].to_string())),
             Rule::constant => {
                 let name = primary.as_str().to_string();
                 let value = *match CONSTANTS_DATABASE.get(&name) {
@@ -106,6 +133,12 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<Expr> {
                 Rule::divide => Ok(Op::Divide),
                 Rule::modulo => Ok(Op::Modulo),
                 Rule::power => Ok(Op::Power),
+                Rule::equals => Ok(Op::Equals),
+                Rule::less => Ok(Op::Less),
+                Rule::greater => Ok(Op::Greater),
+                Rule::less_equal => Ok(Op::LessEqual),
+                Rule::greater_equal => Ok(Op::GreaterEqual),
+                Rule::not_equal => Ok(Op::NotEqual),
                 rule => bail!(ParserError::InvalidOperator(format!("{:?}", rule))),
             };
             let op = op?;
@@ -122,7 +155,29 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<Expr> {
         .parse(pairs)
 }
 
+fn contains_wildcard(expr: &Expr) -> bool {
+    match expr {
+        Expr::Wildcard(_) => true,
+        Expr::UnaryMinus(inner) => contains_wildcard(inner),
+        Expr::BinOp { lhs, rhs, .. } => contains_wildcard(lhs) || contains_wildcard(rhs),
+        Expr::Function { args, .. } | Expr::List(args) => {
+            args.iter().any(|arg| contains_wildcard(arg))
+        }
+        _ => false,
+    }
+}
+
 pub fn parse(expression: &str) -> Result<Expr> {
+    let expr = parse_pattern(expression)?;
+    if contains_wildcard(&expr) {
+        bail!(ParserError::UnexpectedWildcard);
+    }
+
+    Ok(expr)
+}
+
+/// Parses an expression that may contain wildcards such as `?a`.
+pub fn parse_pattern(expression: &str) -> Result<Expr> {
     let mut pairs = CalculatorParser::parse(Rule::equation, expression)?;
     parse_expr(pairs.next().unwrap().into_inner())
 }
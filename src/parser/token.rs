@@ -1,3 +1,5 @@
+use anyhow::Result;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     Number(f64),
@@ -20,9 +22,12 @@ pub enum Expr {
         name: String,
         value: f64,
     },
+    Wildcard(String),
+    /// `[a, b, ...]`, such as the equations and variables of a system.
+    List(Vec<Box<Expr>>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Op {
     Add,
     Subtract,
@@ -31,12 +36,30 @@ pub enum Op {
     Modulo,
     Power,
     Equals,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    NotEqual,
+}
+
+impl Op {
+    /// Whether the operator compares its sides, like `=` or `<`, instead of
+    /// computing a value.
+    pub fn is_relation(&self) -> bool {
+        matches!(
+            self,
+            Op::Equals | Op::Less | Op::Greater | Op::LessEqual | Op::GreaterEqual | Op::NotEqual
+        )
+    }
 }
 
 pub trait Optimize {
     fn optimize_expression(self) -> Expr;
     fn optimize_node(&self) -> Expr;
-    fn optimize_equation(self) -> Expr;
+    /// Isolates `variable` in an `lhs = rhs` equation, returning
+    /// `variable = value`.
+    fn optimize_equation(self, variable: &str) -> Result<Expr>;
 }
 
 impl ToString for Expr {
@@ -49,13 +72,18 @@ impl ToString for Expr {
                 let lhs = lhs.to_string();
                 let rhs = rhs.to_string();
                 let op = match op {
-                    Op::Add => '+',
-                    Op::Subtract => '-',
-                    Op::Multiply => '*',
-                    Op::Divide => '/',
-                    Op::Modulo => '%',
-                    Op::Power => '^',
-                    Op::Equals => '=',
+                    Op::Add => "+",
+                    Op::Subtract => "-",
+                    Op::Multiply => "*",
+                    Op::Divide => "/",
+                    Op::Modulo => "%",
+                    Op::Power => "^",
+                    Op::Equals => "=",
+                    Op::Less => "<",
+                    Op::Greater => ">",
+                    Op::LessEqual => "<=",
+                    Op::GreaterEqual => ">=",
+                    Op::NotEqual => "!=",
                 };
 
                 out.push_str(&format!("({lhs}{op}{rhs})"));
@@ -74,6 +102,15 @@ impl ToString for Expr {
                 exponent,
             } => out.push_str(&format!("{coefficient}{variable}^({exponent})")),
             Expr::Constant { name, .. } => out.push_str(&name),
+            Expr::Wildcard(name) => out.push_str(&format!("?{name}")),
+            Expr::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                out.push_str(&format!("[{items}]"));
+            }
         }
         return out;
     }
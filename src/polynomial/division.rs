@@ -0,0 +1,173 @@
+use anyhow::{bail, Result};
+
+use crate::error::PolynomialError;
+use crate::math::{gcd, Rational};
+
+use super::univariate;
+use super::{Monomial, MonomialOrder, Polynomial};
+
+impl Polynomial {
+    /// The coefficient of `variable^power`, a polynomial in the other
+    /// variables.
+    pub fn coefficient_in(&self, variable: &str, power: u32) -> Polynomial {
+        let mut coefficient = Polynomial::zero();
+        for (monomial, value) in &self.terms {
+            if monomial.power(variable) == power {
+                let rest = monomial.powers().filter(|(name, _)| *name != variable);
+                coefficient.insert(Monomial::new(rest), *value);
+            }
+        }
+        coefficient
+    }
+
+    /// Long division in `variable`, with the other variables as part of the
+    /// coefficients, so that `self = quotient * divisor + remainder`. The
+    /// remainder has a lower degree in `variable` unless a leading
+    /// coefficient doesn't divide exactly, where division stops early.
+    pub fn div_rem(
+        &self,
+        divisor: &Polynomial,
+        variable: &str,
+    ) -> Result<(Polynomial, Polynomial)> {
+        let Some(divisor_degree) = divisor.degree_in(variable) else {
+            bail!(PolynomialError::DivisionByZero);
+        };
+        let divisor_leading = divisor.coefficient_in(variable, divisor_degree);
+
+        let mut quotient = Polynomial::zero();
+        let mut remainder = self.clone();
+        while let Some(degree) = remainder.degree_in(variable) {
+            if degree < divisor_degree {
+                break;
+            }
+
+            let leading = remainder.coefficient_in(variable, degree);
+            let Some(factor) = leading.exact_div(&divisor_leading) else {
+                break;
+            };
+            let term = factor
+                * Polynomial::term(
+                    Rational::ONE,
+                    Monomial::variable(variable, degree - divisor_degree),
+                );
+            remainder = remainder - term.clone() * divisor.clone();
+            quotient = quotient + term;
+        }
+        Ok((quotient, remainder))
+    }
+
+    /// The quotient if `divisor` divides exactly, `None` otherwise.
+    pub fn exact_div(&self, divisor: &Polynomial) -> Option<Polynomial> {
+        let (divisor_monomial, divisor_coefficient) = divisor.leading_term(MonomialOrder::GrLex)?;
+
+        let mut quotient = Polynomial::zero();
+        let mut remainder = self.clone();
+        while let Some((monomial, coefficient)) = remainder.leading_term(MonomialOrder::GrLex) {
+            let term = Polynomial::term(
+                coefficient / divisor_coefficient,
+                monomial.checked_div(divisor_monomial)?,
+            );
+            remainder = remainder - term.clone() * divisor.clone();
+            quotient = quotient + term;
+        }
+        Some(quotient)
+    }
+
+    /// Greatest common divisor. The rational contents contribute their own
+    /// gcd, so `gcd(2X + 2, 4X + 4)` is `2X + 2`, and the leading
+    /// coefficient is positive.
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() {
+            return if other.content().is_negative() {
+                -other.clone()
+            } else {
+                other.clone()
+            };
+        }
+        if other.is_zero() {
+            return other.gcd(self);
+        }
+
+        let (a, b) = (self.content(), other.content());
+        let content = Rational::new(
+            gcd(a.numerator(), b.numerator()),
+            a.denominator() / gcd(a.denominator(), b.denominator()) * b.denominator(),
+        )
+        .expect("nonzero denominator");
+
+        let mut variables = self.variables();
+        variables.extend(other.variables());
+        variables.sort();
+        variables.dedup();
+
+        let a = self.primitive_part();
+        let b = other.primitive_part();
+        let gcd = match variables.as_slice() {
+            [] => Polynomial::constant(Rational::ONE),
+            [variable] => {
+                let dense = univariate::gcd(
+                    &a.to_dense(variable).expect("univariate"),
+                    &b.to_dense(variable).expect("univariate"),
+                );
+                Polynomial::from_dense(&univariate::primitive(&dense), variable)
+            }
+            [variable, ..] => primitive_gcd(a, b, variable).primitive_part(),
+        };
+        Polynomial::constant(content) * gcd
+    }
+
+    /// Divides by the content, leaving coprime integer coefficients and a
+    /// positive leading coefficient.
+    pub fn primitive_part(&self) -> Polynomial {
+        match self.content() {
+            content if content.is_zero() => Polynomial::zero(),
+            content => self.clone() * Polynomial::constant(Rational::ONE / content),
+        }
+    }
+
+    /// Divides by the gcd of the coefficients in `variable`.
+    fn primitive_in(&self, variable: &str) -> Polynomial {
+        if self.is_zero() {
+            return Polynomial::zero();
+        }
+        let content = self.content_in(variable);
+        self.exact_div(&content).expect("content divides")
+    }
+
+    fn content_in(&self, variable: &str) -> Polynomial {
+        (0..=self.degree_in(variable).unwrap_or(0))
+            .map(|power| self.coefficient_in(variable, power))
+            .fold(Polynomial::zero(), |content, coefficient| {
+                content.gcd(&coefficient)
+            })
+    }
+}
+
+/// Euclid's algorithm in `variable` on pseudo-remainders made primitive,
+/// with the contents handled by recursing into the other variables.
+fn primitive_gcd(a: Polynomial, b: Polynomial, variable: &str) -> Polynomial {
+    let content = a.content_in(variable).gcd(&b.content_in(variable));
+    let (mut a, mut b) = (a.primitive_in(variable), b.primitive_in(variable));
+    if a.degree_in(variable) < b.degree_in(variable) {
+        (a, b) = (b, a);
+    }
+
+    while let Some(divisor_degree) = b.degree_in(variable) {
+        // Scale by the divisor's leading coefficient instead of dividing
+        let divisor_leading = b.coefficient_in(variable, divisor_degree);
+        let mut remainder = a;
+        while let Some(degree) = remainder.degree_in(variable) {
+            if degree < divisor_degree {
+                break;
+            }
+            let leading = remainder.coefficient_in(variable, degree);
+            let shift = Polynomial::term(
+                Rational::ONE,
+                Monomial::variable(variable, degree - divisor_degree),
+            );
+            remainder = remainder * divisor_leading.clone() - leading * shift * b.clone();
+        }
+        (a, b) = (b, remainder.primitive_in(variable));
+    }
+    content * a.primitive_in(variable)
+}
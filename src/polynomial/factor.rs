@@ -0,0 +1,448 @@
+use std::fmt;
+
+use crate::math::{gcd, Rational};
+use crate::parser::{Expr, Op};
+
+use super::modular::{
+    exact_div, inverse, is_prime, leading, primitive, symmetric, zp_add, zp_div_rem, zp_ext_gcd,
+    zp_gcd, zp_is_square_free, zp_monic, zp_mul, zp_pow_mod, zp_reduce, zp_scale, zp_sub, zp_trim,
+    Zx,
+};
+use super::univariate::{self, Dense};
+use super::{Monomial, MonomialOrder, Polynomial};
+
+/// Largest modulus Hensel lifting goes up to, so that the product of two
+/// reduced coefficients still fits in an `i128`.
+const MODULUS_LIMIT: i128 = 1_000_000_000_000_000_000;
+
+/// How many primes are tried before giving up on finding one that keeps
+/// the polynomial square-free.
+const PRIME_ATTEMPTS: usize = 50;
+
+/// A polynomial written as `content * f1^m1 * f2^m2 * ...`, where every
+/// factor has integer coefficients and a positive leading coefficient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factorization {
+    pub content: Rational,
+    /// Single variables first, then the other factors by degree.
+    pub factors: Vec<(Polynomial, u32)>,
+}
+
+impl Factorization {
+    /// Multiplies the factors back together.
+    pub fn expand(&self) -> Polynomial {
+        self.factors.iter().fold(
+            Polynomial::constant(self.content),
+            |product, (factor, multiplicity)| product * factor.pow(*multiplicity),
+        )
+    }
+
+    /// Builds the product expression, content first.
+    pub fn to_expr(&self) -> Expr {
+        let product = self
+            .factors
+            .iter()
+            .map(|(factor, multiplicity)| match single_variable(factor) {
+                Some(variable) => Expr::Monomial {
+                    coefficient: 1.0,
+                    variable,
+                    exponent: *multiplicity as f64,
+                },
+                None if *multiplicity == 1 => factor.to_expr(),
+                None => Expr::BinOp {
+                    lhs: Box::new(factor.to_expr()),
+                    op: Op::Power,
+                    rhs: Box::new(Expr::Number(*multiplicity as f64)),
+                },
+            })
+            .reduce(|product, factor| Expr::BinOp {
+                lhs: Box::new(product),
+                op: Op::Multiply,
+                rhs: Box::new(factor),
+            });
+
+        match product {
+            None => Expr::from(self.content),
+            Some(product) if self.content == Rational::ONE => product,
+            Some(product) if self.content == -Rational::ONE => Expr::UnaryMinus(Box::new(product)),
+            Some(product) => Expr::BinOp {
+                lhs: Box::new(Expr::from(self.content)),
+                op: Op::Multiply,
+                rhs: Box::new(product),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Factorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.content != Rational::ONE || self.factors.is_empty() {
+            write!(f, "{}", self.content)?;
+        }
+        for (factor, multiplicity) in &self.factors {
+            write!(f, "({factor})")?;
+            if *multiplicity > 1 {
+                write!(f, "^{multiplicity}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The variable of a polynomial that is exactly `X`, if it is one.
+fn single_variable(polynomial: &Polynomial) -> Option<String> {
+    let (monomial, coefficient) = polynomial.terms.iter().next()?;
+    (polynomial.len() == 1 && *coefficient == Rational::ONE && monomial.degree() == 1)
+        .then(|| monomial.variables().next().unwrap_or_default().to_owned())
+}
+
+impl Polynomial {
+    /// Greatest rational dividing every coefficient, signed so that the
+    /// primitive part has a positive leading coefficient. Zero for the zero
+    /// polynomial.
+    pub fn content(&self) -> Rational {
+        let Some((_, leading)) = self.leading_term(MonomialOrder::GrLex) else {
+            return Rational::ZERO;
+        };
+
+        let (numerator, denominator) =
+            self.terms
+                .values()
+                .fold((0, 1), |(numerator, denominator), coefficient| {
+                    let lcm = denominator / gcd(denominator, coefficient.denominator())
+                        * coefficient.denominator();
+                    (gcd(numerator, coefficient.numerator()), lcm)
+                });
+        let content = Rational::new(numerator, denominator).expect("nonzero denominator");
+        if leading.is_negative() {
+            -content
+        } else {
+            content
+        }
+    }
+
+    /// Factors over the integers: the rational content and common monomial
+    /// are pulled out, univariate polynomials are split into irreducible
+    /// factors, and so are homogeneous polynomials in two variables. Other
+    /// multivariate parts are kept as a single factor.
+    pub fn factor(&self) -> Factorization {
+        let content = self.content();
+        let mut factorization = Factorization {
+            content,
+            factors: Vec::new(),
+        };
+        if content.is_zero() {
+            return factorization;
+        }
+
+        let primitive = self.primitive_part();
+        let common = Monomial::new(primitive.variables().iter().map(|variable| {
+            let power = primitive.terms.keys().map(|m| m.power(variable)).min();
+            (variable.as_str(), power.unwrap_or(0))
+        }));
+        for (variable, power) in common.powers() {
+            if power > 0 {
+                factorization
+                    .factors
+                    .push((Polynomial::variable(variable), power));
+            }
+        }
+
+        let rest = Polynomial {
+            terms: primitive
+                .terms
+                .into_iter()
+                .map(|(monomial, coefficient)| {
+                    let monomial = monomial.checked_div(&common).expect("common divisor");
+                    (monomial, coefficient)
+                })
+                .collect(),
+        };
+        if rest.is_constant() {
+            return factorization;
+        }
+
+        let variables = rest.variables();
+        match variables.as_slice() {
+            [variable] => {
+                let dense = rest.to_dense(variable).expect("univariate");
+                for (factor, multiplicity) in factor_dense(&dense) {
+                    let factor = Polynomial::from_dense(&factor, variable);
+                    factorization.factors.push((factor, multiplicity));
+                }
+            }
+            [x, y] if rest.is_homogeneous() => {
+                // Factors of f(X, Y) are those of f(X, 1) made homogeneous
+                // again, which is exact because Y doesn't divide f.
+                let degree = rest.degree().unwrap_or(0);
+                let mut dense = vec![Rational::ZERO; degree as usize + 1];
+                for (monomial, coefficient) in &rest.terms {
+                    dense[monomial.power(x) as usize] = *coefficient;
+                }
+                for (factor, multiplicity) in factor_dense(&univariate::trim(dense)) {
+                    let degree = factor.len() as u32 - 1;
+                    let factor = factor
+                        .iter()
+                        .enumerate()
+                        .map(|(power, coefficient)| {
+                            let power = power as u32;
+                            let monomial =
+                                Monomial::new([(x.as_str(), power), (y.as_str(), degree - power)]);
+                            Polynomial::term(*coefficient, monomial)
+                        })
+                        .fold(Polynomial::zero(), |sum, term| sum + term);
+                    factorization.factors.push((factor, multiplicity));
+                }
+            }
+            _ => factorization.factors.push((rest, 1)),
+        }
+
+        // Single variables first, then by degree
+        factorization.factors.sort_by_cached_key(|(factor, _)| {
+            let variable = single_variable(factor).is_none();
+            (variable, factor.degree(), factor.to_string())
+        });
+        factorization
+    }
+
+    fn is_homogeneous(&self) -> bool {
+        let mut degrees = self.terms.keys().map(Monomial::degree);
+        let first = degrees.next();
+        degrees.all(|degree| Some(degree) == first)
+    }
+}
+
+/// Splits a primitive univariate polynomial with positive leading
+/// coefficient into irreducible integer factors with multiplicities.
+fn factor_dense(dense: &[Rational]) -> Vec<(Dense, u32)> {
+    let mut factors = Vec::new();
+    for (part, multiplicity) in univariate::square_free(dense) {
+        for factor in zassenhaus(&integer_coefficients(&univariate::primitive(&part))) {
+            let factor = factor.into_iter().map(Rational::integer).collect();
+            factors.push((factor, multiplicity));
+        }
+    }
+    factors
+}
+
+fn integer_coefficients(dense: &[Rational]) -> Zx {
+    dense.iter().map(Rational::numerator).collect()
+}
+
+/// Zassenhaus' algorithm for a primitive square-free integer polynomial:
+/// factor modulo a small prime, Hensel lift the factors past the
+/// coefficient bound and recombine them into true factors. Polynomials
+/// whose bound doesn't fit are returned whole.
+fn zassenhaus(f: &[i128]) -> Vec<Zx> {
+    let degree = f.len().saturating_sub(1);
+    if degree <= 1 {
+        return vec![f.to_vec()];
+    }
+
+    let lead = leading(f);
+    let prime = (3..)
+        .filter(|p| is_prime(*p))
+        .take(PRIME_ATTEMPTS)
+        .find(|p| lead % p != 0 && zp_is_square_free(&zp_reduce(f, *p), *p));
+    let Some(p) = prime else {
+        return vec![f.to_vec()];
+    };
+
+    let modular = zp_factor(&zp_monic(&zp_reduce(f, p), p), p);
+    if modular.len() == 1 {
+        return vec![f.to_vec()];
+    }
+
+    // Mignotte's bound on the coefficients of lc(f) times any factor
+    let norm = f.iter().map(|c| (*c as f64).powi(2)).sum::<f64>().sqrt();
+    let bound = 2.0 * 2f64.powi(degree as i32) * norm * (lead as f64).abs();
+    let (mut modulus, mut exponent) = (p, 1);
+    while modulus as f64 <= bound {
+        if modulus > MODULUS_LIMIT / p {
+            return vec![f.to_vec()];
+        }
+        modulus *= p;
+        exponent += 1;
+    }
+
+    let lifted = hensel_lift(f, &modular, p, exponent);
+    recombine(f, lifted, modulus)
+}
+
+/// Tries products of lifted factors, smallest subsets first, and keeps
+/// those that divide `f` over the integers.
+fn recombine(f: &[i128], mut modular: Vec<Zx>, modulus: i128) -> Vec<Zx> {
+    let mut f = f.to_vec();
+    let mut factors = Vec::new();
+    let mut size = 1;
+    while 2 * size <= modular.len() {
+        let found = combinations(modular.len(), size)
+            .into_iter()
+            .find_map(|subset| {
+                let lead = leading(&f).rem_euclid(modulus);
+                let candidate = subset.iter().fold(vec![lead], |product, i| {
+                    zp_mul(&product, &modular[*i], modulus)
+                });
+                let candidate: Zx = candidate.iter().map(|c| symmetric(*c, modulus)).collect();
+                let candidate = primitive(&candidate);
+                exact_div(&f, &candidate).map(|quotient| (subset, candidate, quotient))
+            });
+
+        match found {
+            Some((subset, candidate, quotient)) => {
+                factors.push(candidate);
+                f = quotient;
+                for i in subset.into_iter().rev() {
+                    modular.remove(i);
+                }
+            }
+            None => size += 1,
+        }
+    }
+    factors.push(f);
+    factors
+}
+
+/// Every increasing `size`-element selection of `0..n`.
+fn combinations(n: usize, size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    (size - 1..n)
+        .flat_map(|last| {
+            combinations(last, size - 1)
+                .into_iter()
+                .map(move |mut subset| {
+                    subset.push(last);
+                    subset
+                })
+        })
+        .collect()
+}
+
+/// Multifactor Hensel lifting of monic factors of `f` modulo `p` to
+/// factors modulo `p^exponent`, the first one carrying the leading
+/// coefficient.
+fn hensel_lift(f: &[i128], factors: &[Zx], p: i128, exponent: u32) -> Vec<Zx> {
+    let modulus = p.pow(exponent);
+    let lead = leading(f);
+    let (last, rest) = factors.split_last().expect("at least one factor");
+    if rest.is_empty() {
+        let inverse = inverse(lead, modulus).expect("leading coefficient is a unit");
+        return vec![zp_scale(f, inverse, modulus)];
+    }
+
+    // Lift f = g * h one factor at a time, where h is the last factor and g
+    // keeps the exact leading coefficient so the error drops a degree.
+    let g0 = rest
+        .iter()
+        .fold(vec![lead.rem_euclid(p)], |product, factor| {
+            zp_mul(&product, factor, p)
+        });
+    let (_, s, t) = zp_ext_gcd(&g0, last, p);
+    let mut g = g0;
+    *g.last_mut().expect("nonzero") = lead.rem_euclid(modulus);
+    let mut h = last.clone();
+
+    let mut power = p;
+    for _ in 1..exponent {
+        let next = power * p;
+        let error = zp_sub(&zp_reduce(f, next), &zp_mul(&g, &h, next), next);
+        let error: Zx = error.iter().map(|c| c / power).collect();
+        let error = zp_reduce(&error, p);
+
+        let (quotient, sigma) = zp_div_rem(&zp_mul(&s, &error, p), &zp_reduce(&h, p), p);
+        let tau = zp_add(
+            &zp_mul(&t, &error, p),
+            &zp_mul(&quotient, &zp_reduce(&g, p), p),
+            p,
+        );
+        g = zp_add(&g, &zp_scale(&tau, power, next), next);
+        h = zp_add(&h, &zp_scale(&sigma, power, next), next);
+        power = next;
+    }
+
+    let mut lifted = hensel_lift(&g, rest, p, exponent);
+    lifted.push(h);
+    lifted
+}
+
+/// Cantor–Zassenhaus factorization of a monic square-free polynomial
+/// modulo an odd prime.
+fn zp_factor(f: &[i128], p: i128) -> Vec<Zx> {
+    let mut random = Lcg(0x2545_f491_4f6c_dd1d);
+    let mut factors = Vec::new();
+    for (part, degree) in distinct_degree(f, p) {
+        equal_degree(&part, degree, p, &mut random, &mut factors);
+    }
+    factors
+}
+
+/// Groups the irreducible factors of `f` by degree.
+fn distinct_degree(f: &[i128], p: i128) -> Vec<(Zx, usize)> {
+    let x = vec![0, 1];
+    let mut f = f.to_vec();
+    let mut power = x.clone();
+    let mut parts = Vec::new();
+    let mut degree = 0;
+    while f.len() > 2 * (degree + 1) {
+        degree += 1;
+        power = zp_pow_mod(&power, p as u128, &f, p);
+        let part = zp_gcd(&zp_sub(&power, &x, p), &f, p);
+        if part.len() > 1 {
+            f = zp_div_rem(&f, &part, p).0;
+            power = zp_div_rem(&power, &f, p).1;
+            parts.push((part, degree));
+        }
+    }
+    if f.len() > 1 {
+        let degree = f.len() - 1;
+        parts.push((f, degree));
+    }
+    parts
+}
+
+/// Splits a product of irreducible factors of the same degree with random
+/// gcds.
+fn equal_degree(f: &[i128], degree: usize, p: i128, random: &mut Lcg, factors: &mut Vec<Zx>) {
+    if f.len() - 1 <= degree {
+        factors.push(f.to_vec());
+        return;
+    }
+
+    loop {
+        let a: Zx = zp_trim((0..f.len() - 1).map(|_| random.next(p)).collect());
+        if a.len() < 2 {
+            continue;
+        }
+
+        // a^((p^d - 1) / 2) as (a * a^p * ... * a^(p^(d-1)))^((p - 1) / 2)
+        let mut power = a.clone();
+        let mut norm = a;
+        for _ in 1..degree {
+            power = zp_pow_mod(&power, p as u128, f, p);
+            norm = zp_div_rem(&zp_mul(&norm, &power, p), f, p).1;
+        }
+        let b = zp_pow_mod(&norm, (p as u128 - 1) / 2, f, p);
+        let split = zp_gcd(&zp_sub(&b, &[1], p), f, p);
+        if split.len() > 1 && split.len() < f.len() {
+            let rest = zp_div_rem(f, &split, p).0;
+            equal_degree(&split, degree, p, random, factors);
+            equal_degree(&rest, degree, p, random, factors);
+            return;
+        }
+    }
+}
+
+/// Deterministic generator so factorizations are reproducible.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: i128) -> i128 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) as i128 % bound
+    }
+}
@@ -0,0 +1,137 @@
+use crate::math::Rational;
+
+use super::{Monomial, MonomialOrder, Polynomial};
+
+impl Polynomial {
+    /// The remainder of dividing by every polynomial in `divisors` under
+    /// `order`. No term of the result is divisible by the leading monomial
+    /// of a divisor.
+    pub fn reduce(&self, divisors: &[Polynomial], order: MonomialOrder) -> Polynomial {
+        let divisors: Vec<(&Polynomial, Monomial, Rational)> = divisors
+            .iter()
+            .filter_map(|divisor| {
+                let (monomial, coefficient) = divisor.leading_term(order)?;
+                Some((divisor, monomial.clone(), coefficient))
+            })
+            .collect();
+
+        let mut remainder = Polynomial::zero();
+        let mut rest = self.clone();
+        while let Some((monomial, coefficient)) = rest.leading_term(order) {
+            let monomial = monomial.clone();
+            let step =
+                divisors
+                    .iter()
+                    .find_map(|(divisor, divisor_monomial, divisor_coefficient)| {
+                        let quotient = monomial.checked_div(divisor_monomial)?;
+                        let factor = Polynomial::term(coefficient / *divisor_coefficient, quotient);
+                        Some(factor * (*divisor).clone())
+                    });
+            match step {
+                Some(step) => rest = rest - step,
+                None => {
+                    let term = Polynomial::term(coefficient, monomial);
+                    rest = rest - term.clone();
+                    remainder = remainder + term;
+                }
+            }
+        }
+        remainder
+    }
+
+    /// Scales the leading coefficient under `order` to 1.
+    fn monic(&self, order: MonomialOrder) -> Polynomial {
+        match self.leading_term(order) {
+            Some((_, coefficient)) => {
+                Polynomial::constant(Rational::ONE / coefficient) * self.clone()
+            }
+            None => Polynomial::zero(),
+        }
+    }
+}
+
+/// The combination of `f` and `g` that cancels their leading terms.
+fn s_polynomial(f: &Polynomial, g: &Polynomial, order: MonomialOrder) -> Polynomial {
+    let (f_monomial, f_coefficient) = f.leading_term(order).expect("nonzero polynomial");
+    let (g_monomial, g_coefficient) = g.leading_term(order).expect("nonzero polynomial");
+    let lcm = f_monomial.lcm(g_monomial);
+
+    let scale = |monomial: &Monomial, coefficient: Rational| {
+        Polynomial::term(
+            Rational::ONE / coefficient,
+            lcm.checked_div(monomial).expect("the lcm is a multiple"),
+        )
+    };
+    scale(f_monomial, f_coefficient) * f.clone() - scale(g_monomial, g_coefficient) * g.clone()
+}
+
+/// Computes the reduced Gröbner basis of the ideal generated by
+/// `polynomials` with Buchberger's algorithm. Pairs are taken smallest
+/// `lcm` first and skipped when their leading monomials are coprime. The
+/// result is monic and sorted by descending leading monomial, so under
+/// [`MonomialOrder::Lex`] the polynomials in fewer variables come last.
+pub fn groebner_basis(polynomials: &[Polynomial], order: MonomialOrder) -> Vec<Polynomial> {
+    let mut basis: Vec<Polynomial> = polynomials
+        .iter()
+        .filter(|polynomial| !polynomial.is_zero())
+        .map(|polynomial| polynomial.monic(order))
+        .collect();
+    let mut pairs: Vec<(usize, usize)> = (0..basis.len())
+        .flat_map(|j| (0..j).map(move |i| (i, j)))
+        .collect();
+
+    let leading = |polynomial: &Polynomial| {
+        polynomial
+            .leading_term(order)
+            .expect("nonzero polynomial")
+            .0
+            .clone()
+    };
+
+    while !pairs.is_empty() {
+        let lcm = |(i, j): (usize, usize)| leading(&basis[i]).lcm(&leading(&basis[j]));
+        let next = (0..pairs.len())
+            .min_by(|a, b| order.cmp(&lcm(pairs[*a]), &lcm(pairs[*b])))
+            .expect("at least one pair");
+        let (i, j) = pairs.swap_remove(next);
+
+        // Coprime leading monomials always reduce to zero
+        let (a, b) = (leading(&basis[i]), leading(&basis[j]));
+        if a.lcm(&b) == &a * &b {
+            continue;
+        }
+
+        let remainder = s_polynomial(&basis[i], &basis[j], order).reduce(&basis, order);
+        if !remainder.is_zero() {
+            pairs.extend((0..basis.len()).map(|k| (k, basis.len())));
+            basis.push(remainder.monic(order));
+        }
+    }
+
+    // Drop redundant generators, then reduce the rest by each other
+    let mut minimal: Vec<Polynomial> = Vec::new();
+    for (i, polynomial) in basis.iter().enumerate() {
+        let monomial = leading(polynomial);
+        let redundant = basis.iter().enumerate().any(|(j, other)| {
+            let other = leading(other);
+            monomial.is_divisible_by(&other) && (monomial != other || j < i)
+        });
+        if !redundant {
+            minimal.push(polynomial.clone());
+        }
+    }
+
+    let mut reduced: Vec<Polynomial> = (0..minimal.len())
+        .map(|i| {
+            let others: Vec<Polynomial> = minimal
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other)| other.clone())
+                .collect();
+            minimal[i].reduce(&others, order)
+        })
+        .collect();
+    reduced.sort_by(|a, b| order.cmp(&leading(b), &leading(a)));
+    reduced
+}
@@ -0,0 +1,389 @@
+mod division;
+mod factor;
+mod groebner;
+mod modular;
+mod monomial;
+mod partial_fractions;
+mod rational_function;
+mod univariate;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use anyhow::{bail, Result};
+
+use crate::error::PolynomialError;
+use crate::math::Rational;
+use crate::parser::{Expr, Op};
+
+pub use factor::Factorization;
+pub use groebner::groebner_basis;
+pub use monomial::{Monomial, MonomialOrder};
+pub use partial_fractions::{PartialFraction, PartialFractions};
+pub use rational_function::RationalFunction;
+
+pub(crate) use univariate::square_free;
+
+/// Sparse multivariate polynomial with exact rational coefficients. Zero
+/// coefficients are never stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    terms: BTreeMap<Monomial, Rational>,
+}
+
+impl Polynomial {
+    pub fn zero() -> Polynomial {
+        Polynomial::default()
+    }
+
+    pub fn constant(value: Rational) -> Polynomial {
+        Polynomial::term(value, Monomial::one())
+    }
+
+    pub fn variable(name: &str) -> Polynomial {
+        Polynomial::term(Rational::ONE, Monomial::variable(name, 1))
+    }
+
+    pub fn term(coefficient: Rational, monomial: Monomial) -> Polynomial {
+        let mut terms = BTreeMap::new();
+        if !coefficient.is_zero() {
+            terms.insert(monomial, coefficient);
+        }
+        Polynomial { terms }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether the polynomial has no variables, zero included.
+    pub fn is_constant(&self) -> bool {
+        self.terms.keys().all(|monomial| monomial.degree() == 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Total degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<u32> {
+        self.terms.keys().map(|monomial| monomial.degree()).max()
+    }
+
+    /// Highest power of `variable`, or `None` for the zero polynomial.
+    pub fn degree_in(&self, variable: &str) -> Option<u32> {
+        self.terms
+            .keys()
+            .map(|monomial| monomial.power(variable))
+            .max()
+    }
+
+    /// Every variable that occurs, in alphabetical order.
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = self
+            .terms
+            .keys()
+            .flat_map(|monomial| monomial.variables().map(str::to_owned))
+            .collect();
+        variables.sort();
+        variables.dedup();
+        variables
+    }
+
+    pub fn coefficient(&self, monomial: &Monomial) -> Rational {
+        self.terms.get(monomial).copied().unwrap_or(Rational::ZERO)
+    }
+
+    /// Terms from the largest to the smallest monomial under `order`.
+    pub fn terms(&self, order: MonomialOrder) -> Vec<(&Monomial, Rational)> {
+        let mut terms: Vec<(&Monomial, Rational)> = self
+            .terms
+            .iter()
+            .map(|(monomial, coefficient)| (monomial, *coefficient))
+            .collect();
+        terms.sort_by(|(a, _), (b, _)| order.cmp(b, a));
+        terms
+    }
+
+    /// Largest term under `order`, or `None` for the zero polynomial.
+    pub fn leading_term(&self, order: MonomialOrder) -> Option<(&Monomial, Rational)> {
+        self.terms
+            .iter()
+            .max_by(|(a, _), (b, _)| order.cmp(a, b))
+            .map(|(monomial, coefficient)| (monomial, *coefficient))
+    }
+
+    /// Raises to a power, using the binomial theorem for two-term bases.
+    /// Panics on coefficient overflow, see [`Polynomial::checked_pow`].
+    pub fn pow(&self, exponent: u32) -> Polynomial {
+        self.checked_pow(exponent).expect("polynomial overflow")
+    }
+
+    /// Raises to a power, `None` if a coefficient overflows.
+    pub fn checked_pow(&self, exponent: u32) -> Option<Polynomial> {
+        let terms: Vec<(&Monomial, &Rational)> = self.terms.iter().collect();
+        if let [(a, a_coefficient), (b, b_coefficient)] = terms.as_slice() {
+            let a = Polynomial::term(**a_coefficient, (*a).clone());
+            let b = Polynomial::term(**b_coefficient, (*b).clone());
+
+            let mut sum = Polynomial::zero();
+            let mut binomial = Rational::ONE;
+            for k in 0..=exponent {
+                let term = a
+                    .pow_repeated(exponent - k)?
+                    .checked_mul(&b.pow_repeated(k)?)?;
+                sum = sum.checked_add(&Polynomial::constant(binomial).checked_mul(&term)?)?;
+                if k < exponent {
+                    // C(n, k+1) = C(n, k) * (n-k) / (k+1)
+                    binomial = binomial
+                        .checked_mul(&Rational::integer((exponent - k) as i128))?
+                        .checked_div(&Rational::integer(k as i128 + 1))?;
+                }
+            }
+            return Some(sum);
+        }
+
+        self.pow_repeated(exponent)
+    }
+
+    fn pow_repeated(&self, exponent: u32) -> Option<Polynomial> {
+        (0..exponent).try_fold(Polynomial::constant(Rational::ONE), |power, _| {
+            power.checked_mul(self)
+        })
+    }
+
+    /// Sum, `None` if a coefficient overflows.
+    pub fn checked_add(&self, rhs: &Polynomial) -> Option<Polynomial> {
+        let mut sum = self.clone();
+        for (monomial, coefficient) in &rhs.terms {
+            sum.insert(monomial.clone(), *coefficient)?;
+        }
+        Some(sum)
+    }
+
+    /// Difference, `None` if a coefficient overflows.
+    pub fn checked_sub(&self, rhs: &Polynomial) -> Option<Polynomial> {
+        self.checked_add(&-rhs.clone())
+    }
+
+    /// Product, `None` if a coefficient overflows.
+    pub fn checked_mul(&self, rhs: &Polynomial) -> Option<Polynomial> {
+        let mut product = Polynomial::zero();
+        for (a, a_coefficient) in &self.terms {
+            for (b, b_coefficient) in &rhs.terms {
+                product.insert(a * b, a_coefficient.checked_mul(b_coefficient)?)?;
+            }
+        }
+        Some(product)
+    }
+
+    fn insert(&mut self, monomial: Monomial, coefficient: Rational) -> Option<()> {
+        let sum = self.coefficient(&monomial).checked_add(&coefficient)?;
+        if sum.is_zero() {
+            self.terms.remove(&monomial);
+        } else {
+            self.terms.insert(monomial, sum);
+        }
+        Some(())
+    }
+
+    /// Converts an expression built from numbers, monomials, `+`, `-`, `*`,
+    /// division by constants and non-negative integer powers.
+    pub fn from_expr(expr: &Expr) -> Result<Polynomial> {
+        let rational = |value: f64| match Rational::from_f64(value) {
+            Some(value) => Ok(value),
+            None => bail!(PolynomialError::InexactCoefficient(value)),
+        };
+
+        Ok(match expr {
+            Expr::Number(value) => Polynomial::constant(rational(*value)?),
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } => {
+                if exponent.fract() != 0.0 || *exponent < 0.0 || *exponent > u32::MAX as f64 {
+                    bail!(PolynomialError::InvalidExponent(expr.to_string()));
+                }
+                Polynomial::term(
+                    rational(*coefficient)?,
+                    Monomial::variable(variable, *exponent as u32),
+                )
+            }
+            Expr::UnaryMinus(inner) => -Polynomial::from_expr(inner)?,
+            Expr::BinOp { lhs, op, rhs } => {
+                let lhs = Polynomial::from_expr(lhs)?;
+                let rhs = Polynomial::from_expr(rhs)?;
+                let result = match op {
+                    Op::Add => lhs.checked_add(&rhs),
+                    Op::Subtract => lhs.checked_sub(&rhs),
+                    Op::Multiply => lhs.checked_mul(&rhs),
+                    Op::Divide => match rhs.constant_value().and_then(|value| value.recip()) {
+                        Some(inverse) => lhs.checked_mul(&Polynomial::constant(inverse)),
+                        _ => bail!(PolynomialError::NotPolynomial(expr.to_string())),
+                    },
+                    Op::Power => match rhs.constant_value() {
+                        Some(exponent) if exponent.is_integer() && !exponent.is_negative() => {
+                            match u32::try_from(exponent.numerator()) {
+                                Ok(exponent) => lhs.checked_pow(exponent),
+                                Err(_) => bail!(PolynomialError::InvalidExponent(expr.to_string())),
+                            }
+                        }
+                        _ => bail!(PolynomialError::InvalidExponent(expr.to_string())),
+                    },
+                    _ => {
+                        bail!(PolynomialError::NotPolynomial(expr.to_string()))
+                    }
+                };
+                match result {
+                    Some(poly) => poly,
+                    None => bail!(PolynomialError::Overflow(expr.to_string())),
+                }
+            }
+            _ => bail!(PolynomialError::NotPolynomial(expr.to_string())),
+        })
+    }
+
+    /// The value of a constant polynomial, `None` if it has variables.
+    pub fn constant_value(&self) -> Option<Rational> {
+        self.is_constant()
+            .then(|| self.coefficient(&Monomial::one()))
+    }
+
+    /// Builds the expression of the polynomial, terms in graded
+    /// lexicographic order. Single-variable terms become `Expr::Monomial`.
+    pub fn to_expr(&self) -> Expr {
+        let mut terms = self.terms(MonomialOrder::GrLex).into_iter();
+        let Some((monomial, coefficient)) = terms.next() else {
+            return Expr::Number(0.0);
+        };
+
+        terms.fold(
+            term_expr(coefficient, monomial),
+            |sum, (monomial, coefficient)| {
+                let (op, coefficient) = if coefficient.is_negative() {
+                    (Op::Subtract, -coefficient)
+                } else {
+                    (Op::Add, coefficient)
+                };
+                Expr::BinOp {
+                    lhs: Box::new(sum),
+                    op,
+                    rhs: Box::new(term_expr(coefficient, monomial)),
+                }
+            },
+        )
+    }
+}
+
+fn term_expr(coefficient: Rational, monomial: &Monomial) -> Expr {
+    let mut powers = monomial.powers();
+    let Some((variable, exponent)) = powers.next() else {
+        return Expr::from(coefficient);
+    };
+
+    // Coefficients without a finite decimal expansion stay exact as a
+    // separate factor instead of being rounded into the f64 coefficient.
+    let (coefficient, factor) = if coefficient.is_decimal() {
+        (coefficient.to_f64(), None)
+    } else {
+        (1.0, Some(Expr::from(coefficient)))
+    };
+
+    let first = Expr::Monomial {
+        coefficient,
+        variable: variable.to_owned(),
+        exponent: exponent as f64,
+    };
+    let product = powers.fold(first, |product, (variable, exponent)| Expr::BinOp {
+        lhs: Box::new(product),
+        op: Op::Multiply,
+        rhs: Box::new(Expr::Monomial {
+            coefficient: 1.0,
+            variable: variable.to_owned(),
+            exponent: exponent as f64,
+        }),
+    });
+
+    match factor {
+        Some(factor) => Expr::BinOp {
+            lhs: Box::new(factor),
+            op: Op::Multiply,
+            rhs: Box::new(product),
+        },
+        None => product,
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    fn neg(mut self) -> Polynomial {
+        for coefficient in self.terms.values_mut() {
+            *coefficient = -*coefficient;
+        }
+        self
+    }
+}
+
+/// Panics on coefficient overflow, use [`Polynomial::checked_add`] where
+/// that can happen.
+impl Add for Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Polynomial) -> Polynomial {
+        self.checked_add(&rhs).expect("polynomial overflow")
+    }
+}
+
+impl Sub for Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Polynomial) -> Polynomial {
+        self + -rhs
+    }
+}
+
+/// Panics on coefficient overflow, use [`Polynomial::checked_mul`] where
+/// that can happen.
+impl Mul for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Polynomial) -> Polynomial {
+        self.checked_mul(&rhs).expect("polynomial overflow")
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self.terms(MonomialOrder::GrLex);
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (monomial, coefficient)) in terms.into_iter().enumerate() {
+            let magnitude = if i == 0 {
+                if coefficient.is_negative() {
+                    write!(f, "-")?;
+                }
+                coefficient.abs()
+            } else {
+                let sign = if coefficient.is_negative() { '-' } else { '+' };
+                write!(f, " {sign} ")?;
+                coefficient.abs()
+            };
+
+            match (magnitude == Rational::ONE, monomial.degree() == 0) {
+                (_, true) => write!(f, "{magnitude}")?,
+                (true, false) => write!(f, "{monomial}")?,
+                (false, false) => write!(f, "{magnitude}*{monomial}")?,
+            }
+        }
+        Ok(())
+    }
+}
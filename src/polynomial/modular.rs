@@ -0,0 +1,260 @@
+use crate::math::gcd;
+
+/// Integer polynomial, lowest degree first and without trailing zeros.
+/// Modular helpers keep every coefficient in `0..modulus`.
+pub(super) type Zx = Vec<i128>;
+
+/// Primes used by the modular gcd, below 2^31 so that products of two
+/// residues stay far from overflowing.
+const GCD_PRIMES_FROM: i128 = 2_147_483_647;
+
+/// How many primes the modular gcd tries before giving up.
+const GCD_ATTEMPTS: usize = 16;
+
+pub(super) fn primitive(a: &[i128]) -> Zx {
+    let content = a.iter().fold(0, |content, c| gcd(content, *c));
+    let sign = if leading(a) < 0 { -1 } else { 1 };
+    a.iter().map(|c| c / content * sign).collect()
+}
+
+pub(super) fn leading(a: &[i128]) -> i128 {
+    a.last().copied().unwrap_or(0)
+}
+
+pub(super) fn is_prime(n: i128) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+/// Quotient of exact division over the integers, or `None` if `b` doesn't
+/// divide `a` or an intermediate value overflows.
+pub(super) fn exact_div(a: &[i128], b: &[i128]) -> Option<Zx> {
+    let divisor_degree = b.len().checked_sub(1)?;
+    let lead = leading(b);
+    let mut remainder = a.to_vec();
+    let mut quotient = vec![0; a.len().checked_sub(divisor_degree)?];
+    for i in (0..quotient.len()).rev() {
+        let c = remainder[i + divisor_degree];
+        if c % lead != 0 {
+            return None;
+        }
+        quotient[i] = c / lead;
+        for (j, b) in b.iter().enumerate() {
+            remainder[i + j] = remainder[i + j].checked_sub(quotient[i].checked_mul(*b)?)?;
+        }
+    }
+    remainder.iter().all(|c| *c == 0).then(|| zp_trim(quotient))
+}
+
+pub(super) fn symmetric(a: i128, modulus: i128) -> i128 {
+    let a = a.rem_euclid(modulus);
+    if a > modulus / 2 {
+        a - modulus
+    } else {
+        a
+    }
+}
+
+pub(super) fn zp_trim(mut a: Zx) -> Zx {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+    a
+}
+
+pub(super) fn zp_reduce(a: &[i128], m: i128) -> Zx {
+    zp_trim(a.iter().map(|c| c.rem_euclid(m)).collect())
+}
+
+pub(super) fn zp_add(a: &[i128], b: &[i128], m: i128) -> Zx {
+    let sum = (0..a.len().max(b.len()))
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(0);
+            let b = b.get(i).copied().unwrap_or(0);
+            (a + b).rem_euclid(m)
+        })
+        .collect();
+    zp_trim(sum)
+}
+
+pub(super) fn zp_sub(a: &[i128], b: &[i128], m: i128) -> Zx {
+    let negated: Zx = b.iter().map(|c| (m - c).rem_euclid(m)).collect();
+    zp_add(a, &negated, m)
+}
+
+pub(super) fn zp_scale(a: &[i128], c: i128, m: i128) -> Zx {
+    let c = c.rem_euclid(m);
+    zp_trim(a.iter().map(|a| (a * c).rem_euclid(m)).collect())
+}
+
+pub(super) fn zp_mul(a: &[i128], b: &[i128], m: i128) -> Zx {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] = (product[i + j] + a * b % m) % m;
+        }
+    }
+    zp_trim(product)
+}
+
+/// Modular inverse by the extended Euclidean algorithm.
+pub(super) fn inverse(a: i128, m: i128) -> Option<i128> {
+    let (mut r0, mut r1) = (a.rem_euclid(m), m);
+    let (mut s0, mut s1) = (1i128, 0i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    (r0 == 1).then(|| s0.rem_euclid(m))
+}
+
+/// Division by a polynomial whose leading coefficient is a unit mod `m`.
+pub(super) fn zp_div_rem(a: &[i128], b: &[i128], m: i128) -> (Zx, Zx) {
+    let divisor_degree = b.len() - 1;
+    let inverse = inverse(leading(b), m).expect("leading coefficient is a unit");
+    let mut remainder = a.to_vec();
+    let mut quotient = vec![0; a.len().saturating_sub(divisor_degree)];
+    while remainder.len() > divisor_degree {
+        let shift = remainder.len() - 1 - divisor_degree;
+        let factor = leading(&remainder) * inverse % m;
+        quotient[shift] = factor;
+        for (i, c) in b.iter().enumerate() {
+            remainder[shift + i] = (remainder[shift + i] - factor * c % m).rem_euclid(m);
+        }
+        remainder = zp_trim(remainder);
+    }
+    (zp_trim(quotient), remainder)
+}
+
+pub(super) fn zp_monic(a: &[i128], p: i128) -> Zx {
+    match inverse(leading(a), p) {
+        Some(inverse) => zp_scale(a, inverse, p),
+        None => a.to_vec(),
+    }
+}
+
+pub(super) fn zp_gcd(a: &[i128], b: &[i128], p: i128) -> Zx {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    while !b.is_empty() {
+        let (_, remainder) = zp_div_rem(&a, &b, p);
+        (a, b) = (b, remainder);
+    }
+    zp_monic(&a, p)
+}
+
+/// Monic gcd `g` with Bézout coefficients, `s * a + t * b = g` mod `p`.
+pub(super) fn zp_ext_gcd(a: &[i128], b: &[i128], p: i128) -> (Zx, Zx, Zx) {
+    let (mut r0, mut r1) = (a.to_vec(), b.to_vec());
+    let (mut s0, mut s1) = (vec![1], Vec::new());
+    let (mut t0, mut t1) = (Vec::new(), vec![1]);
+    while !r1.is_empty() {
+        let (quotient, remainder) = zp_div_rem(&r0, &r1, p);
+        let s2 = zp_sub(&s0, &zp_mul(&quotient, &s1, p), p);
+        let t2 = zp_sub(&t0, &zp_mul(&quotient, &t1, p), p);
+        (r0, r1) = (r1, remainder);
+        (s0, s1) = (s1, s2);
+        (t0, t1) = (t1, t2);
+    }
+
+    let inverse = inverse(leading(&r0), p).expect("nonzero gcd");
+    (
+        zp_scale(&r0, inverse, p),
+        zp_scale(&s0, inverse, p),
+        zp_scale(&t0, inverse, p),
+    )
+}
+
+pub(super) fn zp_pow_mod(base: &[i128], mut exponent: u128, modulus: &[i128], p: i128) -> Zx {
+    let mut result = vec![1];
+    let mut base = zp_div_rem(base, modulus, p).1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = zp_div_rem(&zp_mul(&result, &base, p), modulus, p).1;
+        }
+        base = zp_div_rem(&zp_mul(&base, &base, p), modulus, p).1;
+        exponent >>= 1;
+    }
+    result
+}
+
+pub(super) fn zp_is_square_free(a: &[i128], p: i128) -> bool {
+    let derivative: Zx = a
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| (i as i128 * c).rem_euclid(p))
+        .collect();
+    let derivative = zp_trim(derivative);
+    !derivative.is_empty() && zp_gcd(a, &derivative, p).len() == 1
+}
+
+/// Primitive greatest common divisor of two integer polynomials, computed
+/// modulo large primes and put together by Chinese remaindering, as plain
+/// Euclid overflows on the intermediate coefficients. `None` if no
+/// candidate was found within the attempts.
+pub(super) fn integer_gcd(a: &[i128], b: &[i128]) -> Option<Zx> {
+    if a.is_empty() {
+        return Some(primitive(b));
+    }
+    if b.is_empty() {
+        return Some(primitive(a));
+    }
+
+    let (a, b) = (primitive(a), primitive(b));
+    let scale = gcd(leading(&a), leading(&b));
+    let primes = (0..)
+        .map(|i| GCD_PRIMES_FROM - 2 * i)
+        .filter(|p| is_prime(*p) && scale % p != 0)
+        .take(GCD_ATTEMPTS);
+
+    let mut image: Option<(Zx, i128)> = None;
+    for p in primes {
+        let residue = zp_scale(&zp_gcd(&zp_reduce(&a, p), &zp_reduce(&b, p), p), scale, p);
+        if residue.len() == 1 {
+            return Some(vec![1]);
+        }
+
+        // Primes where the gcd comes out too large are unlucky, a smaller
+        // degree means every earlier prime was
+        image = match image {
+            Some((combined, modulus)) if combined.len() == residue.len() => {
+                match chinese_remainder(&combined, modulus, &residue, p) {
+                    Some(image) => Some(image),
+                    None => return None,
+                }
+            }
+            Some((combined, modulus)) if combined.len() < residue.len() => {
+                Some((combined, modulus))
+            }
+            _ => Some((residue, p)),
+        };
+
+        let (combined, modulus) = image.as_ref().expect("set above");
+        let candidate: Zx = combined.iter().map(|c| symmetric(*c, *modulus)).collect();
+        let candidate = primitive(&candidate);
+        if exact_div(&a, &candidate).is_some() && exact_div(&b, &candidate).is_some() {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// Combines residues modulo `m` and the prime `p` into residues modulo
+/// `m * p`, or `None` once that would overflow.
+fn chinese_remainder(a: &[i128], m: i128, b: &[i128], p: i128) -> Option<(Zx, i128)> {
+    let modulus = m.checked_mul(p)?;
+    let inverse = inverse(m, p)?;
+    let combined = (0..a.len().max(b.len()))
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(0);
+            let b = b.get(i).copied().unwrap_or(0);
+            // x = a + m * ((b - a) / m mod p)
+            let k = ((b - a).rem_euclid(p) * inverse).rem_euclid(p);
+            (a + m * k).rem_euclid(modulus)
+        })
+        .collect();
+    Some((zp_trim(combined), modulus))
+}
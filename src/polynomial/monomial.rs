@@ -0,0 +1,167 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Mul;
+
+/// Product of variables raised to positive powers, such as `X^2*Y`. The
+/// derived order only keeps storage deterministic, use [`MonomialOrder`] to
+/// compare monomials algebraically.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Monomial {
+    powers: BTreeMap<String, u32>,
+}
+
+impl Monomial {
+    /// The empty product, i.e. the monomial of constant terms.
+    pub fn one() -> Monomial {
+        Monomial::default()
+    }
+
+    pub fn variable(name: &str, exponent: u32) -> Monomial {
+        let mut powers = BTreeMap::new();
+        if exponent > 0 {
+            powers.insert(name.to_owned(), exponent);
+        }
+        Monomial { powers }
+    }
+
+    /// Builds a monomial from `(variable, exponent)` pairs, multiplying
+    /// repeated variables together.
+    pub fn new<'a>(powers: impl IntoIterator<Item = (&'a str, u32)>) -> Monomial {
+        powers
+            .into_iter()
+            .fold(Monomial::one(), |product, (name, exponent)| {
+                &product * &Monomial::variable(name, exponent)
+            })
+    }
+
+    /// Total degree.
+    pub fn degree(&self) -> u32 {
+        self.powers.values().sum()
+    }
+
+    pub fn power(&self, variable: &str) -> u32 {
+        self.powers.get(variable).copied().unwrap_or(0)
+    }
+
+    /// Variables with a positive exponent, in alphabetical order.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.powers.keys().map(String::as_str)
+    }
+
+    /// `(variable, exponent)` pairs in alphabetical order.
+    pub fn powers(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.powers
+            .iter()
+            .map(|(variable, exponent)| (variable.as_str(), *exponent))
+    }
+
+    /// Whether `other` divides `self`.
+    pub fn is_divisible_by(&self, other: &Monomial) -> bool {
+        other
+            .powers
+            .iter()
+            .all(|(variable, exponent)| self.power(variable) >= *exponent)
+    }
+
+    /// `self / other`, or `None` if `other` does not divide `self`.
+    pub fn checked_div(&self, other: &Monomial) -> Option<Monomial> {
+        if !self.is_divisible_by(other) {
+            return None;
+        }
+
+        let powers = self
+            .powers
+            .iter()
+            .map(|(variable, exponent)| (variable.to_owned(), exponent - other.power(variable)))
+            .filter(|(_, exponent)| *exponent > 0)
+            .collect();
+        Some(Monomial { powers })
+    }
+
+    /// Least common multiple, taking the highest power of every variable.
+    pub fn lcm(&self, other: &Monomial) -> Monomial {
+        let mut powers = self.powers.clone();
+        for (variable, exponent) in &other.powers {
+            let power = powers.entry(variable.to_owned()).or_insert(0);
+            *power = (*power).max(*exponent);
+        }
+        Monomial { powers }
+    }
+}
+
+impl Mul for &Monomial {
+    type Output = Monomial;
+
+    // Multiplying monomials adds their exponents
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: &Monomial) -> Monomial {
+        let mut powers = self.powers.clone();
+        for (variable, exponent) in &rhs.powers {
+            *powers.entry(variable.to_owned()).or_insert(0) += exponent;
+        }
+        Monomial { powers }
+    }
+}
+
+impl fmt::Display for Monomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.powers.is_empty() {
+            return write!(f, "1");
+        }
+
+        let factors: Vec<String> = self
+            .powers()
+            .map(|(variable, exponent)| match exponent {
+                1 => variable.to_owned(),
+                exponent => format!("{variable}^{exponent}"),
+            })
+            .collect();
+        write!(f, "{}", factors.join("*"))
+    }
+}
+
+/// Total orders on monomials used to pick leading terms. Variables rank in
+/// alphabetical order, so `X > Y > Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MonomialOrder {
+    /// Lexicographic: compare the exponent of the first variable, then the
+    /// next one, ...
+    Lex,
+    /// Graded lexicographic: total degree first, ties broken by `Lex`.
+    #[default]
+    GrLex,
+    /// Graded reverse lexicographic: total degree first, then the monomial
+    /// with the smaller exponent in the last variable wins.
+    GrevLex,
+}
+
+impl MonomialOrder {
+    pub fn cmp(&self, a: &Monomial, b: &Monomial) -> Ordering {
+        let mut variables: Vec<&str> = a.variables().chain(b.variables()).collect();
+        variables.sort();
+        variables.dedup();
+
+        let lex = || {
+            variables
+                .iter()
+                .map(|variable| a.power(variable).cmp(&b.power(variable)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+        let reverse_lex = || {
+            variables
+                .iter()
+                .rev()
+                .map(|variable| b.power(variable).cmp(&a.power(variable)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+
+        match self {
+            MonomialOrder::Lex => lex(),
+            MonomialOrder::GrLex => a.degree().cmp(&b.degree()).then_with(lex),
+            MonomialOrder::GrevLex => a.degree().cmp(&b.degree()).then_with(reverse_lex),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::{bail, Result};
+
+use crate::error::PolynomialError;
+use crate::math::Rational;
+
+use super::univariate;
+use super::{Polynomial, RationalFunction};
+
+/// One term `numerator / factor^power` of a partial fraction
+/// decomposition. The numerator has a lower degree than the factor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialFraction {
+    pub numerator: Polynomial,
+    pub factor: Polynomial,
+    pub power: u32,
+}
+
+/// A rational function split into a polynomial part and proper fractions
+/// over the irreducible factors of its denominator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialFractions {
+    pub polynomial: Polynomial,
+    pub fractions: Vec<PartialFraction>,
+}
+
+impl RationalFunction {
+    /// Partial fraction decomposition in `variable`. The denominator may
+    /// only depend on `variable`, the numerator can have other variables
+    /// too.
+    pub fn apart(&self, variable: &str) -> Result<PartialFractions> {
+        let (reduced, _) = self.cancel();
+        let denominator = &reduced.denominator;
+        if denominator.variables().iter().any(|name| name != variable) {
+            bail!(PolynomialError::NotUnivariate(
+                denominator.to_string(),
+                variable.to_string()
+            ));
+        }
+
+        let (polynomial, remainder) = reduced.numerator.div_rem(denominator, variable)?;
+        let factorization = denominator.factor();
+        let numerator = remainder * Polynomial::constant(Rational::ONE / factorization.content);
+
+        // Each factor's share of the numerator is found modulo its power,
+        // using the inverse of the cofactor there
+        let powers: Vec<Polynomial> = factorization
+            .factors
+            .iter()
+            .map(|(factor, power)| factor.pow(*power))
+            .collect();
+        let mut fractions = Vec::new();
+        for (i, (factor, power)) in factorization.factors.iter().enumerate() {
+            let cofactor = powers
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Polynomial::constant(Rational::ONE), |product, (_, q)| {
+                    product * q.clone()
+                });
+            let dense = |p: &Polynomial| p.to_dense(variable).expect("univariate");
+            let inverse = univariate::inverse_mod(&dense(&cofactor), &dense(&powers[i]))
+                .expect("factors are coprime");
+            let inverse = Polynomial::from_dense(&inverse, variable);
+            let (_, mut share) = (numerator.clone() * inverse).div_rem(&powers[i], variable)?;
+
+            // Expand the share in powers of the factor
+            let mut terms = Vec::new();
+            for power in (1..=*power).rev() {
+                let (quotient, remainder) = share.div_rem(factor, variable)?;
+                if !remainder.is_zero() {
+                    terms.push(PartialFraction {
+                        numerator: remainder,
+                        factor: factor.clone(),
+                        power,
+                    });
+                }
+                share = quotient;
+            }
+            fractions.extend(terms.into_iter().rev());
+        }
+
+        Ok(PartialFractions {
+            polynomial,
+            fractions,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+use std::ops::Neg;
+
+use anyhow::{bail, Result};
+
+use crate::error::PolynomialError;
+use crate::math::Rational;
+use crate::parser::{Expr, Op};
+
+use super::Polynomial;
+
+/// Quotient of two polynomials. Common factors are only removed by
+/// [`RationalFunction::cancel`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RationalFunction {
+    pub numerator: Polynomial,
+    pub denominator: Polynomial,
+}
+
+impl RationalFunction {
+    pub fn new(numerator: Polynomial, denominator: Polynomial) -> Result<RationalFunction> {
+        if denominator.is_zero() {
+            bail!(PolynomialError::DivisionByZero);
+        }
+        Ok(RationalFunction {
+            numerator,
+            denominator,
+        })
+    }
+
+    pub fn polynomial(numerator: Polynomial) -> RationalFunction {
+        RationalFunction {
+            numerator,
+            denominator: Polynomial::constant(Rational::ONE),
+        }
+    }
+
+    /// Converts an expression built from numbers, monomials, `+`, `-`, `*`,
+    /// `/` and integer powers.
+    pub fn from_expr(expr: &Expr) -> Result<RationalFunction> {
+        Ok(match expr {
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } if *exponent < 0.0 => {
+                let reciprocal = Expr::Monomial {
+                    coefficient: 1.0,
+                    variable: variable.to_owned(),
+                    exponent: -exponent,
+                };
+                RationalFunction::new(
+                    Polynomial::from_expr(&Expr::Number(*coefficient))?,
+                    Polynomial::from_expr(&reciprocal)?,
+                )?
+            }
+            Expr::Number(_) | Expr::Monomial { .. } => {
+                RationalFunction::polynomial(Polynomial::from_expr(expr)?)
+            }
+            Expr::UnaryMinus(inner) => {
+                let inner = RationalFunction::from_expr(inner)?;
+                RationalFunction {
+                    numerator: -inner.numerator,
+                    denominator: inner.denominator,
+                }
+            }
+            Expr::BinOp { lhs, op, rhs } => {
+                let lhs = RationalFunction::from_expr(lhs)?;
+                match op {
+                    Op::Add => lhs.add(RationalFunction::from_expr(rhs)?),
+                    Op::Subtract => lhs.add(-RationalFunction::from_expr(rhs)?),
+                    Op::Multiply => lhs.mul(RationalFunction::from_expr(rhs)?),
+                    Op::Divide => lhs.mul(RationalFunction::from_expr(rhs)?.recip()?),
+                    Op::Power => {
+                        let exponent = Polynomial::from_expr(rhs)?.constant_value();
+                        match exponent.map(|e| (e.is_integer(), i32::try_from(e.numerator()))) {
+                            Some((true, Ok(exponent))) => lhs.pow(exponent)?,
+                            _ => bail!(PolynomialError::InvalidExponent(expr.to_string())),
+                        }
+                    }
+                    _ => {
+                        bail!(PolynomialError::NotPolynomial(expr.to_string()))
+                    }
+                }
+            }
+            _ => bail!(PolynomialError::NotPolynomial(expr.to_string())),
+        })
+    }
+
+    pub fn recip(self) -> Result<RationalFunction> {
+        RationalFunction::new(self.denominator, self.numerator)
+    }
+
+    fn add(self, other: RationalFunction) -> RationalFunction {
+        if self.denominator == other.denominator {
+            return RationalFunction {
+                numerator: self.numerator + other.numerator,
+                denominator: self.denominator,
+            };
+        }
+        RationalFunction {
+            numerator: self.numerator * other.denominator.clone()
+                + other.numerator * self.denominator.clone(),
+            denominator: self.denominator * other.denominator,
+        }
+    }
+
+    fn mul(self, other: RationalFunction) -> RationalFunction {
+        RationalFunction {
+            numerator: self.numerator * other.numerator,
+            denominator: self.denominator * other.denominator,
+        }
+    }
+
+    fn pow(self, exponent: i32) -> Result<RationalFunction> {
+        let power = RationalFunction {
+            numerator: self.numerator.pow(exponent.unsigned_abs()),
+            denominator: self.denominator.pow(exponent.unsigned_abs()),
+        };
+        if exponent < 0 {
+            power.recip()
+        } else {
+            Ok(power)
+        }
+    }
+
+    /// Removes the greatest common divisor of numerator and denominator,
+    /// returning the reduced function and the factor that was removed. The
+    /// denominator is left primitive with a positive leading coefficient.
+    pub fn cancel(&self) -> (RationalFunction, Polynomial) {
+        let common = self.numerator.gcd(&self.denominator);
+        let numerator = self.numerator.exact_div(&common).expect("gcd divides");
+        let denominator = self.denominator.exact_div(&common).expect("gcd divides");
+
+        let content = Polynomial::constant(Rational::ONE / denominator.content());
+        let reduced = RationalFunction {
+            numerator: numerator * content.clone(),
+            denominator: denominator * content,
+        };
+        (reduced, common.primitive_part())
+    }
+
+    /// The expression `numerator / denominator`, or just the numerator when
+    /// the denominator is 1.
+    pub fn to_expr(&self) -> Expr {
+        match self.denominator.constant_value() {
+            Some(value) if value == Rational::ONE => self.numerator.to_expr(),
+            _ => Expr::BinOp {
+                lhs: Box::new(self.numerator.to_expr()),
+                op: Op::Divide,
+                rhs: Box::new(self.denominator.to_expr()),
+            },
+        }
+    }
+}
+
+impl Neg for RationalFunction {
+    type Output = RationalFunction;
+
+    fn neg(self) -> RationalFunction {
+        RationalFunction {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl fmt::Display for RationalFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denominator.constant_value() {
+            Some(value) if value == Rational::ONE => write!(f, "{}", self.numerator),
+            _ => write!(f, "({})/({})", self.numerator, self.denominator),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::math::{self, Rational};
+
+use super::modular::{self, Zx};
+use super::{Monomial, Polynomial};
+
+/// Coefficients of a univariate polynomial over the rationals, lowest degree
+/// first and without trailing zeros, so the zero polynomial is empty.
+pub(crate) type Dense = Vec<Rational>;
+
+pub(crate) fn trim(mut a: Dense) -> Dense {
+    while a.last().is_some_and(|c| c.is_zero()) {
+        a.pop();
+    }
+    a
+}
+
+pub(crate) fn degree(a: &[Rational]) -> Option<usize> {
+    a.len().checked_sub(1)
+}
+
+pub(crate) fn sub(a: &[Rational], b: &[Rational]) -> Dense {
+    let difference = (0..a.len().max(b.len()))
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(Rational::ZERO);
+            let b = b.get(i).copied().unwrap_or(Rational::ZERO);
+            a - b
+        })
+        .collect();
+    trim(difference)
+}
+
+pub(crate) fn mul(a: &[Rational], b: &[Rational]) -> Dense {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut product = vec![Rational::ZERO; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] = product[i + j] + *a * *b;
+        }
+    }
+    trim(product)
+}
+
+/// Long division, returning quotient and remainder. Panics if `b` is zero.
+pub(crate) fn div_rem(a: &[Rational], b: &[Rational]) -> (Dense, Dense) {
+    let divisor_degree = degree(b).expect("division by the zero polynomial");
+    let leading = b[divisor_degree];
+
+    let mut remainder = a.to_vec();
+    let mut quotient = vec![Rational::ZERO; a.len().saturating_sub(divisor_degree)];
+    while let Some(remainder_degree) = degree(&remainder) {
+        if remainder_degree < divisor_degree {
+            break;
+        }
+
+        let shift = remainder_degree - divisor_degree;
+        let factor = remainder[remainder_degree] / leading;
+        quotient[shift] = factor;
+        for (i, c) in b.iter().enumerate() {
+            remainder[shift + i] = remainder[shift + i] - factor * *c;
+        }
+        remainder = trim(remainder);
+    }
+
+    (trim(quotient), remainder)
+}
+
+pub(crate) fn monic(a: &[Rational]) -> Dense {
+    match a.last() {
+        Some(leading) => a.iter().map(|c| *c / *leading).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Scales to coprime integer coefficients with a positive leading one.
+pub(crate) fn primitive(a: &[Rational]) -> Dense {
+    let denominator = a.iter().fold(1, |lcm, c| {
+        lcm / math::gcd(lcm, c.denominator()) * c.denominator()
+    });
+    let numerators: Vec<i128> = a
+        .iter()
+        .map(|c| c.numerator() * (denominator / c.denominator()))
+        .collect();
+    let content = numerators
+        .iter()
+        .fold(0, |content, c| math::gcd(content, *c));
+    let sign = if numerators.last().is_some_and(|c| *c < 0) {
+        -1
+    } else {
+        1
+    };
+    numerators
+        .into_iter()
+        .map(|c| Rational::integer(c / content * sign))
+        .collect()
+}
+
+/// Monic greatest common divisor, or 1 if the coefficients grow out of
+/// reach of the modular algorithm.
+pub(crate) fn gcd(a: &[Rational], b: &[Rational]) -> Dense {
+    let integer = |a: &[Rational]| -> Zx { primitive(a).iter().map(Rational::numerator).collect() };
+    match modular::integer_gcd(&integer(a), &integer(b)) {
+        Some(gcd) => monic(&gcd.into_iter().map(Rational::integer).collect::<Dense>()),
+        None => vec![Rational::ONE],
+    }
+}
+
+/// The inverse of `a` modulo `modulus` by the extended Euclidean
+/// algorithm, or `None` if they share a factor.
+pub(crate) fn inverse_mod(a: &[Rational], modulus: &[Rational]) -> Option<Dense> {
+    let (mut r0, mut r1) = (modulus.to_vec(), div_rem(a, modulus).1);
+    let (mut t0, mut t1) = (Vec::new(), vec![Rational::ONE]);
+    while let Some(&leading) = r1.last() {
+        // Monic remainders keep the coefficients from blowing up
+        (r1, t1) = (monic(&r1), t1.iter().map(|c| *c / leading).collect());
+        let (quotient, remainder) = div_rem(&r0, &r1);
+        let t2 = sub(&t0, &mul(&quotient, &t1));
+        (r0, r1) = (r1, remainder);
+        (t0, t1) = (t1, t2);
+    }
+    (degree(&r0) == Some(0)).then(|| div_rem(&t0, modulus).1)
+}
+
+pub(crate) fn derivative(a: &[Rational]) -> Dense {
+    let derivative = a
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| *c * Rational::integer(i as i128))
+        .collect();
+    trim(derivative)
+}
+
+/// Yun's algorithm: splits a polynomial into monic square-free parts, each
+/// with the multiplicity it appears with.
+pub(crate) fn square_free(a: &[Rational]) -> Vec<(Dense, u32)> {
+    let mut parts = Vec::new();
+    if degree(a).unwrap_or(0) == 0 {
+        return parts;
+    }
+
+    let derivative_a = derivative(a);
+    let common = gcd(a, &derivative_a);
+    let mut b = div_rem(a, &common).0;
+    let c = div_rem(&derivative_a, &common).0;
+    let mut d = sub(&c, &derivative(&b));
+
+    let mut multiplicity = 1;
+    while degree(&b).unwrap_or(0) > 0 {
+        let part = gcd(&b, &d);
+        b = div_rem(&b, &part).0;
+        let c = div_rem(&d, &part).0;
+        d = sub(&c, &derivative(&b));
+        if degree(&part).unwrap_or(0) > 0 {
+            parts.push((part, multiplicity));
+        }
+        multiplicity += 1;
+    }
+    parts
+}
+
+impl Polynomial {
+    /// Dense coefficients in `variable`, or `None` if any other variable
+    /// occurs.
+    pub(crate) fn to_dense(&self, variable: &str) -> Option<Dense> {
+        let mut dense =
+            vec![Rational::ZERO; self.degree_in(variable).map_or(0, |d| d as usize + 1)];
+        for (monomial, coefficient) in &self.terms {
+            if monomial.variables().any(|name| name != variable) {
+                return None;
+            }
+            dense[monomial.power(variable) as usize] = *coefficient;
+        }
+        Some(dense)
+    }
+
+    pub(crate) fn from_dense(dense: &[Rational], variable: &str) -> Polynomial {
+        dense
+            .iter()
+            .enumerate()
+            .map(|(power, coefficient)| {
+                Polynomial::term(*coefficient, Monomial::variable(variable, power as u32))
+            })
+            .fold(Polynomial::zero(), |sum, term| sum + term)
+    }
+}
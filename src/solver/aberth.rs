@@ -0,0 +1,193 @@
+use std::f64::consts::TAU;
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::math::{round, Complex, Rational};
+use crate::parser::{Expr, Op};
+use crate::polynomial::{square_free, Polynomial};
+
+use super::polynomial::{coefficients, evaluate_complex};
+use super::radical::complex_expr;
+
+/// Most Aberth sweeps before settling for the current approximations.
+const MAX_ITERATIONS: usize = 500;
+
+/// Most decimal places an `f64` root can be given to.
+pub const MAX_DIGITS: u32 = 15;
+
+/// Roots of polynomials with inexact coefficients closer than this,
+/// relative to their size, are taken as one multiple root.
+const CLUSTER: f64 = 1e-4;
+
+/// A root of a polynomial and how often it occurs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Root {
+    pub value: Complex,
+    pub multiplicity: u32,
+}
+
+impl fmt::Display for Root {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", complex_expr(self.value).to_string())?;
+        if self.multiplicity > 1 {
+            write!(f, " (×{})", self.multiplicity)?;
+        }
+        Ok(())
+    }
+}
+
+/// Approximates all roots of `poly`, given lowest power first, at once
+/// with the Aberth–Ehrlich method. Multiple roots converge slowly and only
+/// to about `1/multiplicity` of the digits.
+pub(super) fn aberth(poly: &[f64]) -> Vec<Complex> {
+    let degree = poly.len() - 1;
+    let derivative: Vec<f64> = (1..=degree).map(|k| poly[k] * k as f64).collect();
+
+    // Start on a circle whose radius is the geometric mean of the roots,
+    // rotated off the real axis so conjugate pairs can separate
+    let radius = match (poly[0] / poly[degree]).abs().powf(1.0 / degree as f64) {
+        radius if radius > 0.0 && radius.is_finite() => radius,
+        _ => 1.0,
+    };
+    let mut roots: Vec<Complex> = (0..degree)
+        .map(|k| Complex::from_polar(radius, TAU * k as f64 / degree as f64 + 0.4))
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut converged = true;
+        for i in 0..degree {
+            let value = evaluate_complex(poly, roots[i]);
+            if value == Complex::ZERO {
+                continue;
+            }
+            let ratio = value / evaluate_complex(&derivative, roots[i]);
+            let repulsion = (0..degree)
+                .filter(|j| *j != i)
+                .fold(Complex::ZERO, |sum, j| {
+                    sum + Complex::ONE / (roots[i] - roots[j])
+                });
+            let step = ratio / (Complex::ONE - ratio * repulsion);
+            if !(step.re.is_finite() && step.im.is_finite()) {
+                continue;
+            }
+
+            roots[i] = roots[i] - step;
+            if step.abs() > f64::EPSILON * roots[i].abs().max(1.0) {
+                converged = false;
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+    roots
+}
+
+/// Groups approximations of the same multiple root, averaging each group.
+fn cluster(values: Vec<Complex>) -> Vec<Root> {
+    let mut roots: Vec<(Complex, u32)> = Vec::new();
+    for value in values {
+        let close = roots.iter_mut().find(|(root, multiplicity)| {
+            let center = *root / Complex::real(*multiplicity as f64);
+            (center - value).abs() <= CLUSTER * center.abs().max(1.0)
+        });
+        match close {
+            Some((sum, multiplicity)) => {
+                *sum = *sum + value;
+                *multiplicity += 1;
+            }
+            None => roots.push((value, 1)),
+        }
+    }
+    roots
+        .into_iter()
+        .map(|(sum, multiplicity)| Root {
+            value: sum / Complex::real(multiplicity as f64),
+            multiplicity,
+        })
+        .collect()
+}
+
+/// `lhs - rhs` for an equation, or the expression itself.
+fn difference(expr: &Expr) -> Expr {
+    match expr {
+        Expr::BinOp {
+            lhs,
+            op: Op::Equals,
+            rhs,
+        } => Expr::BinOp {
+            lhs: lhs.clone(),
+            op: Op::Subtract,
+            rhs: rhs.clone(),
+        },
+        expr => expr.clone(),
+    }
+}
+
+/// Finds every complex root of a polynomial in `variable`, with its
+/// multiplicity, rounded to `digits` decimal places. Rational coefficients
+/// are split into square-free parts first, so multiplicities are exact;
+/// otherwise approximations that coincide are counted together.
+pub fn all_roots(expr: &Expr, variable: &str, digits: u32) -> Result<Vec<Root>> {
+    if digits > MAX_DIGITS {
+        bail!(SolverError::Precision(digits));
+    }
+
+    let difference = difference(expr);
+    let exact = Polynomial::from_expr(&difference)
+        .ok()
+        .and_then(|poly| poly.to_dense(variable));
+    let mut roots = match exact {
+        Some(poly) => {
+            if poly.len() <= 1 {
+                return constant_roots(poly.first().is_some_and(|c| !c.is_zero()));
+            }
+            square_free(&poly)
+                .into_iter()
+                .flat_map(|(part, multiplicity)| {
+                    let part: Vec<f64> = part.iter().map(Rational::to_f64).collect();
+                    aberth(&part).into_iter().map(move |value| Root {
+                        value,
+                        multiplicity,
+                    })
+                })
+                .collect()
+        }
+        None => {
+            let Some(mut poly) = coefficients::<f64>(&difference, variable) else {
+                bail!(SolverError::NonPolynomial(variable.to_string()));
+            };
+            while poly.len() > 1 && poly.last() == Some(&0.0) {
+                poly.pop();
+            }
+            if poly.len() <= 1 {
+                return constant_roots(poly.first().is_some_and(|c| *c != 0.0));
+            }
+            cluster(aberth(&poly))
+        }
+    };
+
+    for root in &mut roots {
+        let re = round(root.value.re, digits) + 0.0;
+        let im = round(root.value.im, digits) + 0.0;
+        root.value = Complex::new(re, im);
+    }
+    roots.sort_by(|a, b| {
+        let key = |root: &Root| (root.value.im != 0.0, root.value.re, root.value.im);
+        let (a, b) = (key(a), key(b));
+        a.0.cmp(&b.0)
+            .then(a.1.total_cmp(&b.1))
+            .then(a.2.total_cmp(&b.2))
+    });
+    Ok(roots)
+}
+
+fn constant_roots(nonzero: bool) -> Result<Vec<Root>> {
+    if nonzero {
+        Ok(Vec::new())
+    } else {
+        bail!(SolverError::InfiniteSolutions)
+    }
+}
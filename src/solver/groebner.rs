@@ -0,0 +1,163 @@
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::math::{Complex, Rational};
+use crate::parser::Expr;
+use crate::polynomial::{groebner_basis, MonomialOrder, Polynomial};
+
+use super::aberth::aberth;
+use super::polynomial::{exact_roots, is_root, quadratic};
+use super::radical::Radical;
+
+/// Values found so far for some of the variables.
+type Branch<'a> = Vec<(&'a str, Radical)>;
+
+/// One value per variable.
+type Solution = Vec<(String, Expr)>;
+
+/// Plugs the values of `branch` into `polynomial`, leaving the coefficients
+/// of `variable`, lowest power first and without negligible leading ones.
+fn substitute(polynomial: &Polynomial, variable: &str, branch: &Branch) -> Vec<Radical> {
+    let degree = polynomial.degree_in(variable).unwrap_or(0) as usize;
+    let mut coefficients = vec![Radical::from(Rational::ZERO); degree + 1];
+    for (monomial, coefficient) in polynomial.terms(MonomialOrder::Lex) {
+        let mut term = Radical::from(coefficient);
+        for (name, power) in monomial.powers().filter(|(name, _)| *name != variable) {
+            let (_, value) = branch
+                .iter()
+                .find(|(known, _)| *known == name)
+                .expect("only polynomials in known variables are substituted");
+            for _ in 0..power {
+                term = term.mul(value);
+            }
+        }
+        let power = monomial.power(variable) as usize;
+        coefficients[power] = coefficients[power].add(&term);
+    }
+
+    // Approximate values rarely cancel exactly
+    let scale = coefficients
+        .iter()
+        .map(|coefficient| coefficient.value().abs())
+        .fold(1.0, f64::max);
+    while coefficients
+        .last()
+        .is_some_and(|coefficient| match coefficient.rational() {
+            Some(value) => value.is_zero(),
+            None => coefficient.value().abs() <= 1e-10 * scale,
+        })
+    {
+        coefficients.pop();
+    }
+    coefficients
+}
+
+/// Real roots of a univariate polynomial: exact for rational coefficients,
+/// in radicals up to degree two otherwise and numeric beyond that.
+fn real_roots(coefficients: &[Radical]) -> Vec<Radical> {
+    let exact: Option<Vec<Rational>> = coefficients.iter().map(Radical::rational).collect();
+    let roots = match (exact, coefficients) {
+        (Some(exact), _) => exact_roots(exact),
+        (None, [c, b]) => vec![c.neg().div(b)],
+        (None, [c, b, a]) => quadratic(&b.div(a), &c.div(a)),
+        (None, _) => {
+            let approximate: Vec<f64> = coefficients.iter().map(|c| c.value().re).collect();
+            aberth(&approximate)
+                .into_iter()
+                .filter(|root| root.is_real(1e-9))
+                .map(|root| Radical::approximate(Complex::real(root.re)))
+                .collect()
+        }
+    };
+    roots
+        .into_iter()
+        .filter(|root| root.value().is_real(1e-9))
+        .collect()
+}
+
+/// Solves a polynomial system exactly through its lexicographic Gröbner
+/// basis. The basis is triangular: its last polynomials only depend on the
+/// last variable, so the variables are solved one at a time, each root
+/// being substituted into the polynomials of the next variable. Only real
+/// solutions are returned, each as values in the order of `variables`.
+pub(super) fn solve_groebner(
+    polynomials: &[Polynomial],
+    variables: &[String],
+) -> Result<Vec<Solution>> {
+    let basis = groebner_basis(polynomials, MonomialOrder::Lex);
+    if basis.iter().any(Polynomial::is_constant) {
+        // The ideal contains 1
+        return Ok(Vec::new());
+    }
+
+    // Lex orders variables alphabetically, so the last one is eliminated
+    // the most
+    let mut order: Vec<&str> = variables.iter().map(String::as_str).collect();
+    order.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut branches: Vec<Branch> = vec![Vec::new()];
+    for variable in order {
+        let mut extended = Vec::new();
+        for branch in branches {
+            let known = |name: &String| name == variable || branch.iter().any(|(k, _)| k == name);
+            let candidates: Vec<Vec<Radical>> = basis
+                .iter()
+                .filter(|polynomial| polynomial.variables().iter().all(known))
+                .map(|polynomial| substitute(polynomial, variable, &branch))
+                .filter(|coefficients| !coefficients.is_empty())
+                .collect();
+            let Some(lowest) = candidates
+                .iter()
+                .min_by_key(|coefficients| coefficients.len())
+            else {
+                // Nothing pins the variable down
+                bail!(SolverError::InfiniteSolutions);
+            };
+
+            for root in real_roots(lowest) {
+                let vanishes = candidates.iter().all(|coefficients| {
+                    let approximate: Vec<f64> = coefficients.iter().map(|c| c.value().re).collect();
+                    is_root(&approximate, root.value())
+                });
+                if vanishes {
+                    let mut branch = branch.clone();
+                    branch.push((variable, root));
+                    extended.push(branch);
+                }
+            }
+        }
+        branches = extended;
+    }
+
+    let mut solutions: Vec<(Vec<f64>, Solution)> = branches
+        .into_iter()
+        .map(|branch| {
+            variables
+                .iter()
+                .map(|variable| {
+                    let (_, value) = branch.iter().find(|(name, _)| name == variable).unwrap();
+                    (
+                        value.value().re,
+                        (variable.to_owned(), value.expr().clone()),
+                    )
+                })
+                .unzip()
+        })
+        .collect();
+    solutions.sort_by(|(a, _), (b, _)| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| a.total_cmp(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    solutions.dedup_by(|(a, _), (b, _)| {
+        a.iter()
+            .zip(b.iter())
+            .all(|(a, b)| (a - b).abs() <= 1e-9 * b.abs().max(1.0))
+    });
+    Ok(solutions
+        .into_iter()
+        .map(|(_, solution)| solution)
+        .collect())
+}
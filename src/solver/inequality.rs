@@ -0,0 +1,154 @@
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::numeric_evaluator::{evaluate_expr, function_of};
+use crate::parser::{Expr, Op};
+use crate::polynomial::{Polynomial, RationalFunction};
+
+use super::interval::{Endpoint, Interval, IntervalSet};
+use super::polynomial::solve_polynomial;
+use super::Solution;
+
+/// A point where the sign of a rational function may change: a root of its
+/// numerator or, if `pole`, of its denominator.
+struct Critical {
+    value: Expr,
+    approximate: f64,
+    pole: bool,
+}
+
+/// Real roots of `polynomial`, which only depends on `variable`.
+fn real_roots(polynomial: &Polynomial, variable: &str, pole: bool) -> Result<Vec<Critical>> {
+    if polynomial.is_constant() {
+        return Ok(Vec::new());
+    }
+    let roots = match solve_polynomial(&polynomial.to_expr(), &Expr::Number(0.0), variable)? {
+        Solution::Finite(roots) => roots,
+        _ => Vec::new(),
+    };
+    Ok(roots
+        .into_iter()
+        .filter_map(|value| {
+            // Complex roots don't evaluate to a real number
+            let approximate = evaluate_expr(&value).ok()?;
+            approximate.is_finite().then_some(Critical {
+                value,
+                approximate,
+                pole,
+            })
+        })
+        .collect())
+}
+
+/// Whether a value of sign `sign` satisfies `value op 0`.
+fn holds(op: Op, sign: f64) -> bool {
+    match op {
+        Op::Less => sign < 0.0,
+        Op::Greater => sign > 0.0,
+        Op::LessEqual => sign <= 0.0,
+        Op::GreaterEqual => sign >= 0.0,
+        Op::NotEqual => sign != 0.0,
+        _ => sign == 0.0,
+    }
+}
+
+/// Solves an inequality such as `X^2 - 4 < 0` whose sides are rational
+/// functions of `variable`. The roots of the numerator and denominator of
+/// `lhs - rhs` split the real line into pieces of constant sign, so a sign
+/// chart only has to test one point per piece. Roots are included for `<=`
+/// and `>=`, poles never are.
+pub fn solve_inequality(inequality: &Expr, variable: &str) -> Result<IntervalSet> {
+    let (lhs, rhs, op) = match inequality {
+        Expr::BinOp { lhs, op, rhs } if op.is_relation() && *op != Op::Equals => (lhs, rhs, *op),
+        _ => bail!(SolverError::NotAnInequality),
+    };
+    let difference = RationalFunction::from_expr(&Expr::BinOp {
+        lhs: lhs.clone(),
+        op: Op::Subtract,
+        rhs: rhs.clone(),
+    })?;
+    let RationalFunction {
+        numerator,
+        denominator,
+    } = &difference;
+    if let Some(other) = numerator
+        .variables()
+        .into_iter()
+        .chain(denominator.variables())
+        .find(|name| name != variable)
+    {
+        bail!(SolverError::ExtraVariable(variable.to_string(), other));
+    }
+
+    let mut critical = real_roots(numerator, variable, false)?;
+    critical.extend(real_roots(denominator, variable, true)?);
+    critical.sort_by(|a, b| a.approximate.total_cmp(&b.approximate));
+    critical.dedup_by(|a, b| {
+        let shared = (a.approximate - b.approximate).abs() <= 1e-9 * b.approximate.abs().max(1.0);
+        // A root shared with the denominator is still a hole
+        b.pole |= shared && a.pole;
+        shared
+    });
+
+    let (top, bottom) = (
+        function_of(&numerator.to_expr(), variable),
+        function_of(&denominator.to_expr(), variable),
+    );
+    let sign = |x: f64| -> Result<f64> {
+        if numerator.is_zero() {
+            return Ok(0.0);
+        }
+        Ok(top(x)?.signum() * bottom(x)?.signum())
+    };
+
+    // The pieces between the critical points alternate with the points
+    let mut chart: Vec<(bool, Option<&Critical>)> = Vec::new();
+    let test_point = |i: usize| match (i.checked_sub(1).map(|j| &critical[j]), critical.get(i)) {
+        (None, None) => 0.0,
+        (None, Some(next)) => next.approximate - 1.0,
+        (Some(previous), None) => previous.approximate + 1.0,
+        (Some(previous), Some(next)) => (previous.approximate + next.approximate) / 2.0,
+    };
+    for i in 0..=critical.len() {
+        chart.push((holds(op, sign(test_point(i))?), None));
+        if let Some(point) = critical.get(i) {
+            chart.push((!point.pole && holds(op, 0.0), Some(point)));
+        }
+    }
+
+    let open = |point: &Critical| Endpoint::open(point.value.clone(), point.approximate);
+    let closed = |point: &Critical| Endpoint::closed(point.value.clone(), point.approximate);
+    let mut intervals = Vec::new();
+    let mut lower: Option<Option<Endpoint>> = None;
+    let mut previous: Option<&Critical> = None;
+    for (satisfied, point) in chart {
+        match (satisfied, lower.is_some()) {
+            (true, false) => {
+                lower = Some(match point {
+                    Some(point) => Some(closed(point)),
+                    None => previous.map(open),
+                })
+            }
+            (false, true) => {
+                let upper = match point {
+                    Some(point) => open(point),
+                    // The piece right after a satisfied point
+                    None => closed(previous.expect("pieces follow points")),
+                };
+                intervals.push(Interval {
+                    lower: lower.take().unwrap(),
+                    upper: Some(upper),
+                });
+            }
+            _ => {}
+        }
+        if point.is_some() {
+            previous = point;
+        }
+    }
+    if let Some(lower) = lower {
+        intervals.push(Interval { lower, upper: None });
+    }
+
+    Ok(IntervalSet(intervals))
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+
+use crate::parser::Expr;
+
+/// A finite end of an [`Interval`], at the exact `value` whose
+/// approximation orders it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub value: Expr,
+    pub approximate: f64,
+    /// Whether the endpoint itself belongs to the interval.
+    pub closed: bool,
+}
+
+impl Endpoint {
+    pub fn open(value: Expr, approximate: f64) -> Endpoint {
+        Endpoint {
+            value,
+            approximate,
+            closed: false,
+        }
+    }
+
+    pub fn closed(value: Expr, approximate: f64) -> Endpoint {
+        Endpoint {
+            value,
+            approximate,
+            closed: true,
+        }
+    }
+}
+
+/// A connected set of real numbers. A missing end is infinite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+    pub lower: Option<Endpoint>,
+    pub upper: Option<Endpoint>,
+}
+
+impl Interval {
+    /// Every real number.
+    pub fn all() -> Interval {
+        Interval {
+            lower: None,
+            upper: None,
+        }
+    }
+
+    /// The single point `[value, value]`.
+    pub fn point(value: Expr, approximate: f64) -> Interval {
+        Interval {
+            lower: Some(Endpoint::closed(value.clone(), approximate)),
+            upper: Some(Endpoint::closed(value, approximate)),
+        }
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        let above = self
+            .lower
+            .as_ref()
+            .is_none_or(|lower| x > lower.approximate || (lower.closed && x == lower.approximate));
+        let below = self
+            .upper
+            .as_ref()
+            .is_none_or(|upper| x < upper.approximate || (upper.closed && x == upper.approximate));
+        above && below
+    }
+
+    fn is_point(&self) -> bool {
+        matches!(
+            (&self.lower, &self.upper),
+            (Some(lower), Some(upper)) if lower.closed && upper.closed
+                && lower.approximate == upper.approximate
+        )
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_point() {
+            let value = &self.lower.as_ref().unwrap().value;
+            return write!(f, "{{{}}}", value.to_string());
+        }
+
+        let (open, lower) = match &self.lower {
+            Some(lower) => (
+                if lower.closed { '[' } else { '(' },
+                lower.value.to_string(),
+            ),
+            None => ('(', "-inf".to_string()),
+        };
+        let (upper, close) = match &self.upper {
+            Some(upper) => (
+                upper.value.to_string(),
+                if upper.closed { ']' } else { ')' },
+            ),
+            None => ("inf".to_string(), ')'),
+        };
+        write!(f, "{open}{lower}, {upper}{close}")
+    }
+}
+
+/// A union of disjoint intervals in ascending order, such as the solutions
+/// of an inequality.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IntervalSet(pub Vec<Interval>);
+
+impl IntervalSet {
+    pub fn empty() -> IntervalSet {
+        IntervalSet(Vec::new())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.0
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.0.iter().any(|interval| interval.contains(x))
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no solution");
+        }
+        let intervals: Vec<String> = self.0.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", intervals.join(" ∪ "))
+    }
+}
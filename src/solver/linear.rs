@@ -0,0 +1,146 @@
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::parser::{Expr, Op, Optimize};
+
+use super::{contains_variable, Solution};
+
+/// Coefficients of `slope * X + intercept`. Both are free of `X`, but may
+/// still contain other variables, constants or functions.
+struct Linear {
+    slope: Expr,
+    intercept: Expr,
+}
+
+impl Linear {
+    fn constant(expr: Expr) -> Self {
+        Linear {
+            slope: Expr::Number(0.0),
+            intercept: expr,
+        }
+    }
+
+    fn combine(self, op: Op, other: Linear) -> Self {
+        Linear {
+            slope: fold(self.slope, op, other.slope),
+            intercept: fold(self.intercept, op, other.intercept),
+        }
+    }
+
+    fn scale(self, op: Op, factor: &Expr) -> Self {
+        Linear {
+            slope: fold(self.slope, op, factor.clone()),
+            intercept: fold(self.intercept, op, factor.clone()),
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        matches!(self.slope, Expr::Number(n) if n == 0.0)
+    }
+}
+
+/// Builds `lhs op rhs`, computing the result right away when both sides are
+/// plain numbers so that numeric coefficients do not pile up as trees.
+fn fold(lhs: Expr, op: Op, rhs: Expr) -> Expr {
+    match (&lhs, &rhs) {
+        (Expr::Number(a), Expr::Number(b)) => Expr::Number(
+            match op {
+                Op::Add => a + b,
+                Op::Subtract => a - b,
+                Op::Multiply => a * b,
+                Op::Divide => a / b,
+                _ => unreachable!("'{op:?}' is not linear"),
+            } + 0.0,
+        ),
+        _ => Expr::BinOp {
+            lhs: Box::new(lhs),
+            op,
+            rhs: Box::new(rhs),
+        }
+        .optimize_node(),
+    }
+}
+
+fn negate(expr: Expr) -> Expr {
+    match expr {
+        // Adding zero folds -0.0 into 0.0
+        Expr::Number(n) => Expr::Number(-n + 0.0),
+        expr => Expr::UnaryMinus(Box::new(expr)).optimize_node(),
+    }
+}
+
+/// Rewrites `expr` as `slope * variable + intercept`, failing when the
+/// variable appears in a non-linear position.
+fn linear_form(expr: &Expr, variable: &str) -> Result<Linear> {
+    if !contains_variable(expr, variable) {
+        return Ok(Linear::constant(expr.clone()));
+    }
+
+    match expr {
+        Expr::Monomial {
+            coefficient,
+            exponent,
+            ..
+        } if *exponent == 1.0 => Ok(Linear {
+            slope: Expr::Number(*coefficient),
+            intercept: Expr::Number(0.0),
+        }),
+        Expr::UnaryMinus(inner) => {
+            let inner = linear_form(inner, variable)?;
+            Ok(Linear {
+                slope: negate(inner.slope),
+                intercept: negate(inner.intercept),
+            })
+        }
+        Expr::BinOp {
+            lhs,
+            op: op @ (Op::Add | Op::Subtract),
+            rhs,
+        } => Ok(linear_form(lhs, variable)?.combine(*op, linear_form(rhs, variable)?)),
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => {
+            let lhs = linear_form(lhs, variable)?;
+            let rhs = linear_form(rhs, variable)?;
+            if lhs.is_constant() {
+                Ok(rhs.scale(Op::Multiply, &lhs.intercept))
+            } else if rhs.is_constant() {
+                Ok(lhs.scale(Op::Multiply, &rhs.intercept))
+            } else {
+                bail!(SolverError::NonLinear(variable.to_string()))
+            }
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } if !contains_variable(rhs, variable) => {
+            Ok(linear_form(lhs, variable)?.scale(Op::Divide, rhs))
+        }
+        _ => bail!(SolverError::NonLinear(variable.to_string())),
+    }
+}
+
+/// Solves `lhs = rhs` for `variable` by moving every term to the left,
+/// collecting them into `a * X + b = 0` and dividing by `a`.
+pub fn solve_linear(lhs: &Expr, rhs: &Expr, variable: &str) -> Result<Solution> {
+    let Linear { slope, intercept } =
+        linear_form(lhs, variable)?.combine(Op::Subtract, linear_form(rhs, variable)?);
+
+    match (&slope, &intercept) {
+        (Expr::Number(a), Expr::Number(b)) if *a == 0.0 => Ok(if *b == 0.0 {
+            Solution::All
+        } else {
+            Solution::Empty
+        }),
+        (Expr::Number(a), _) if *a == 0.0 => {
+            bail!(SolverError::MissingVariable(variable.to_string()))
+        }
+        _ => {
+            let root = fold(negate(intercept), Op::Divide, slope);
+            Ok(Solution::Finite(vec![root.optimize_expression()]))
+        }
+    }
+}
@@ -0,0 +1,100 @@
+mod aberth;
+mod groebner;
+mod inequality;
+mod interval;
+mod linear;
+mod numeric;
+mod polynomial;
+mod radical;
+mod system;
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::parser::{Expr, Op};
+
+pub use aberth::{all_roots, Root};
+pub use inequality::solve_inequality;
+pub use interval::{Endpoint, Interval, IntervalSet};
+pub use linear::solve_linear;
+pub use numeric::{nsolve, nsolve_system, roots};
+pub use polynomial::solve_polynomial;
+pub use radical::imaginary_unit;
+pub use system::{solve_system, solve_system_with, SystemSolution, SystemStrategy};
+
+/// The set of values of a variable that satisfy an equation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution {
+    Finite(Vec<Expr>),
+    Empty,
+    All,
+    /// The solutions of an inequality.
+    Intervals(IntervalSet),
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Solution::Finite(roots) => {
+                let roots = roots
+                    .iter()
+                    .map(|root| root.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "{{{roots}}}")
+            }
+            Solution::Empty => write!(f, "no solution"),
+            Solution::All => write!(f, "infinitely many solutions"),
+            Solution::Intervals(intervals) => write!(f, "{intervals}"),
+        }
+    }
+}
+
+/// Splits an `lhs = rhs` tree into its two sides.
+fn sides(equation: &Expr) -> Result<(&Expr, &Expr)> {
+    match equation {
+        Expr::BinOp {
+            lhs,
+            op: Op::Equals,
+            rhs,
+        } => Ok((lhs, rhs)),
+        _ => bail!(SolverError::NotAnEquation),
+    }
+}
+
+/// Solves `equation` for `variable`. Inequalities such as `X^2 < 4` are
+/// solved into intervals.
+pub fn solve(equation: &Expr, variable: &str) -> Result<Solution> {
+    if matches!(equation, Expr::BinOp { op, .. } if op.is_relation() && *op != Op::Equals) {
+        return Ok(Solution::Intervals(solve_inequality(equation, variable)?));
+    }
+
+    let (lhs, rhs) = sides(equation)?;
+    match solve_linear(lhs, rhs, variable) {
+        Err(err) if matches!(err.downcast_ref(), Some(SolverError::NonLinear(_))) => {
+            solve_polynomial(lhs, rhs, variable)
+        }
+        solution => solution,
+    }
+}
+
+/// Whether `variable` occurs anywhere in `expr`.
+pub(crate) fn contains_variable(expr: &Expr, variable: &str) -> bool {
+    match expr {
+        Expr::Monomial {
+            variable: name,
+            exponent,
+            ..
+        } => name == variable && *exponent != 0.0,
+        Expr::UnaryMinus(inner) => contains_variable(inner, variable),
+        Expr::BinOp { lhs, rhs, .. } => {
+            contains_variable(lhs, variable) || contains_variable(rhs, variable)
+        }
+        Expr::Function { args, .. } | Expr::List(args) => {
+            args.iter().any(|arg| contains_variable(arg, variable))
+        }
+        _ => false,
+    }
+}
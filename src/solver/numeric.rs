@@ -0,0 +1,405 @@
+use anyhow::{bail, Result};
+
+use crate::algebra::diff;
+use crate::error::{NumericError, SolverError};
+use crate::math::round;
+use crate::numeric_evaluator::{function_of, function_of_many};
+use crate::parser::{Expr, Op};
+
+/// Most Newton or Brent steps before giving up.
+const MAX_ITERATIONS: usize = 200;
+
+/// Pieces `roots` splits its interval into when looking for sign changes.
+const SUBDIVISIONS: usize = 1000;
+
+/// Relative step size below which an iteration has converged.
+const EPSILON: f64 = 1e-15;
+
+type Function<'a> = dyn Fn(f64) -> Result<f64> + 'a;
+
+type MultiFunction = Box<dyn Fn(&[f64]) -> Result<f64>>;
+
+/// `lhs - rhs` for an equation, or the expression itself, whose zeros are
+/// the solutions.
+fn difference(equation: &Expr) -> Expr {
+    match equation {
+        Expr::BinOp {
+            lhs,
+            op: Op::Equals,
+            rhs,
+        } => Expr::BinOp {
+            lhs: lhs.clone(),
+            op: Op::Subtract,
+            rhs: rhs.clone(),
+        },
+        expr => expr.clone(),
+    }
+}
+
+/// The function whose zeros solve `equation` and its derivative, taken
+/// symbolically when possible and by central differences otherwise.
+fn functions(equation: &Expr, variable: &str) -> (Box<Function<'static>>, Box<Function<'static>>) {
+    let difference = difference(equation);
+    let f = Box::new(function_of(&difference, variable));
+    let df: Box<Function> = match diff(&difference, variable) {
+        Ok(derivative) => Box::new(function_of(&derivative, variable)),
+        Err(_) => {
+            let f = function_of(&difference, variable);
+            Box::new(move |x: f64| {
+                let h = 1e-7 * x.abs().max(1.0);
+                Ok((f(x + h)? - f(x - h)?) / (2.0 * h))
+            })
+        }
+    };
+    (f, df)
+}
+
+/// Damped Newton iteration from `guess`, or `None` if it doesn't settle on
+/// a zero.
+fn newton(f: &Function, df: &Function, guess: f64) -> Option<f64> {
+    let mut x = guess;
+    let mut fx = f(x).ok()?;
+    let scale = fx.abs().max(1.0);
+    for _ in 0..MAX_ITERATIONS {
+        if fx == 0.0 {
+            return Some(x);
+        }
+        let slope = df(x).ok()?;
+        let mut step = fx / slope;
+        if !step.is_finite() {
+            return None;
+        }
+
+        // Halve the step until it doesn't make things worse
+        let mut next = x - step;
+        let mut f_next = f(next).unwrap_or(f64::NAN);
+        for _ in 0..30 {
+            if f_next.abs() <= fx.abs() {
+                break;
+            }
+            step /= 2.0;
+            next = x - step;
+            f_next = f(next).unwrap_or(f64::NAN);
+        }
+        if !f_next.is_finite() {
+            return None;
+        }
+
+        (x, fx) = (next, f_next);
+        if step.abs() <= EPSILON * x.abs().max(1.0) {
+            // Steps also shrink when running into a pole
+            return (fx.abs() <= 1e-8 * scale).then_some(x);
+        }
+    }
+    None
+}
+
+/// Brent's method on `[a, b]`, where `f(a)` and `f(b)` have opposite signs.
+fn brent(f: &Function, mut a: f64, mut b: f64, mut fa: f64, mut fb: f64) -> Result<f64> {
+    if fa.abs() < fb.abs() {
+        (a, b, fa, fb) = (b, a, fb, fa);
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut bisected = true;
+
+    for _ in 0..MAX_ITERATIONS {
+        if fb == 0.0 || (b - a).abs() <= 2.0 * EPSILON * b.abs().max(1.0) {
+            return Ok(b);
+        }
+
+        let mut s = if fa != fc && fb != fc {
+            // Inverse quadratic interpolation
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            // Secant
+            b - fb * (b - a) / (fb - fa)
+        };
+
+        let bound = (3.0 * a + b) / 4.0;
+        let outside = !((s > bound.min(b)) && (s < bound.max(b)));
+        let slow = if bisected {
+            (s - b).abs() >= (b - c).abs() / 2.0
+        } else {
+            (s - b).abs() >= (c - d).abs() / 2.0
+        };
+        bisected = outside || slow;
+        if bisected {
+            s = (a + b) / 2.0;
+        }
+
+        let fs = f(s)?;
+        (d, c, fc) = (c, b, fb);
+        if fa * fs < 0.0 {
+            (b, fb) = (s, fs);
+        } else {
+            (a, fa) = (s, fs);
+        }
+        if fa.abs() < fb.abs() {
+            (a, b, fa, fb) = (b, a, fb, fa);
+        }
+    }
+    Ok(b)
+}
+
+/// Brent's method, rejecting a result where `|f|` blew up instead of
+/// vanishing, which means the sign change was a pole.
+fn refine(f: &Function, a: f64, b: f64, fa: f64, fb: f64) -> Result<Option<f64>> {
+    let root = brent(f, a, b, fa, fb)?;
+    let scale = fa.abs().min(fb.abs()).max(1.0);
+    Ok((f(root)?.abs() <= 1e-8 * scale).then_some(root))
+}
+
+/// Looks for a sign change around `guess` in growing steps.
+fn bracket(f: &Function, guess: f64) -> Option<(f64, f64, f64, f64)> {
+    let f_guess = f(guess).ok().filter(|value| value.is_finite())?;
+    let mut width = 0.01 * guess.abs().max(1.0);
+    for _ in 0..60 {
+        for x in [guess - width, guess + width] {
+            if let Ok(fx) = f(x) {
+                if fx.is_finite() && fx * f_guess <= 0.0 {
+                    return Some((guess, x, f_guess, fx));
+                }
+            }
+        }
+        width *= 1.6;
+    }
+    None
+}
+
+/// Finds one solution of `equation`, either `lhs = rhs` or an expression
+/// equal to zero, near `guess`. Newton's method is tried first, with a
+/// bracketing search and Brent's method as fallback.
+pub fn nsolve(equation: &Expr, variable: &str, guess: f64) -> Result<f64> {
+    let (f, df) = functions(equation, variable);
+    if let Some(root) = newton(&*f, &*df, guess) {
+        return Ok(round(root, 15));
+    }
+    if let Some((a, b, fa, fb)) = bracket(&*f, guess) {
+        if let Some(root) = refine(&*f, a, b, fa, fb)? {
+            return Ok(round(root, 15));
+        }
+    }
+    // An equation that can't be evaluated at all, e.g. a call with the
+    // wrong number of arguments, reports why
+    f(guess)?;
+    bail!(SolverError::NoConvergence(guess.to_string()))
+}
+
+/// Finds every real solution of `equation` in `[lower, upper]`, in
+/// ascending order. The interval is sampled for sign changes, which are
+/// refined with Brent's method, while roots that only touch zero are found
+/// with Newton's method from local minima of `|f|`. Poles where the sign
+/// flips are not mistaken for roots.
+pub fn roots(equation: &Expr, variable: &str, lower: f64, upper: f64) -> Result<Vec<f64>> {
+    let (f, df) = functions(equation, variable);
+    let (lower, upper) = (lower.min(upper), lower.max(upper));
+    let xs: Vec<f64> = (0..=SUBDIVISIONS)
+        .map(|i| lower + (upper - lower) * i as f64 / SUBDIVISIONS as f64)
+        .collect();
+    // Samples outside the domain, such as ln of a negative number, are gaps
+    let ys = xs
+        .iter()
+        .map(|x| match f(*x) {
+            Err(err) if matches!(err.downcast_ref(), Some(NumericError::Domain(..))) => {
+                Ok(f64::NAN)
+            }
+            y => y,
+        })
+        .collect::<Result<Vec<f64>>>()?;
+
+    let mut roots = Vec::new();
+    for i in 0..xs.len() {
+        let (x, y) = (xs[i], ys[i]);
+        let next = ys.get(i + 1).copied().unwrap_or(f64::NAN);
+        // Samples that hit a root are rarely exactly zero, e.g. sin(180)
+        let neighbours = [ys.get(i.wrapping_sub(1)).copied(), Some(next)];
+        let scale = neighbours
+            .into_iter()
+            .flatten()
+            .filter(|value| value.is_finite())
+            .map(f64::abs)
+            .reduce(f64::min)
+            .unwrap_or(1.0)
+            .max(1.0);
+        if y.abs() <= 1e-12 * scale {
+            roots.push(x);
+            continue;
+        }
+        if y.is_finite() && next.is_finite() && y * next < 0.0 {
+            roots.extend(refine(&*f, x, xs[i + 1], y, next)?);
+        }
+
+        // A local minimum of |f| without a sign change may touch zero
+        let (Some(before), Some(after)) = (ys.get(i.wrapping_sub(1)), ys.get(i + 1)) else {
+            continue;
+        };
+        if y.abs() < before.abs() && y.abs() < after.abs() && y * before > 0.0 && y * after > 0.0 {
+            if let Some(root) = newton(&*f, &*df, x) {
+                let scale = before.abs().max(after.abs()).max(1.0);
+                if root > xs[i - 1] && root < xs[i + 1] && f(root)?.abs() <= 1e-12 * scale {
+                    roots.push(root);
+                }
+            }
+        }
+    }
+
+    let mut roots: Vec<f64> = roots
+        .into_iter()
+        .map(|root| round(root, 15) + 0.0)
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-9 * b.abs().max(1.0));
+    Ok(roots)
+}
+
+/// The partial derivative of `difference` in the `index`-th variable,
+/// symbolic when possible and by central differences otherwise.
+fn partial(difference: &Expr, variables: &[String], index: usize) -> MultiFunction {
+    match diff(difference, &variables[index]) {
+        Ok(derivative) => Box::new(function_of_many(&derivative, variables)),
+        Err(_) => {
+            let f = function_of_many(difference, variables);
+            Box::new(move |x: &[f64]| {
+                let h = 1e-7 * x[index].abs().max(1.0);
+                let (mut above, mut below) = (x.to_vec(), x.to_vec());
+                above[index] += h;
+                below[index] -= h;
+                Ok((f(&above)? - f(&below)?) / (2.0 * h))
+            })
+        }
+    }
+}
+
+/// Solves `matrix * x = rhs` by Gaussian elimination with partial pivoting,
+/// `None` if the matrix is singular.
+fn linear_solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    for column in 0..n {
+        let pivot = (column..n).max_by(|a, b| {
+            matrix[*a][column]
+                .abs()
+                .total_cmp(&matrix[*b][column].abs())
+        })?;
+        if matrix[pivot][column] == 0.0 || !matrix[pivot][column].is_finite() {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        for row in column + 1..n {
+            let factor = matrix[row][column] / matrix[column][column];
+            let pivot_row = matrix[column].clone();
+            for (value, pivot) in matrix[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|j| matrix[row][j] * x[j]).sum();
+        x[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Some(x)
+}
+
+fn norm(values: &[f64]) -> f64 {
+    values.iter().map(|value| value * value).sum::<f64>().sqrt()
+}
+
+/// Damped Newton iteration for a square system from `guess`, or `None` if
+/// it doesn't settle on a zero.
+fn newton_system(
+    f: &[MultiFunction],
+    jacobian: &[Vec<MultiFunction>],
+    guess: &[f64],
+) -> Option<Vec<f64>> {
+    let evaluate = |x: &[f64]| -> Option<Vec<f64>> {
+        f.iter()
+            .map(|f| f(x).ok().filter(|value| value.is_finite()))
+            .collect()
+    };
+
+    let mut x = guess.to_vec();
+    let mut fx = evaluate(&x)?;
+    let scale = norm(&fx).max(1.0);
+    for _ in 0..MAX_ITERATIONS {
+        if norm(&fx) == 0.0 {
+            return Some(x);
+        }
+        let matrix = jacobian
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|df| df(&x).ok())
+                    .collect::<Option<Vec<f64>>>()
+            })
+            .collect::<Option<Vec<Vec<f64>>>>()?;
+        let mut step = linear_solve(matrix, fx.clone())?;
+
+        // Halve the step until it doesn't make things worse
+        let mut next: Vec<f64> = x.iter().zip(&step).map(|(x, dx)| x - dx).collect();
+        let mut f_next = evaluate(&next);
+        for _ in 0..30 {
+            if f_next
+                .as_ref()
+                .is_some_and(|f_next| norm(f_next) <= norm(&fx))
+            {
+                break;
+            }
+            step.iter_mut().for_each(|dx| *dx /= 2.0);
+            next = x.iter().zip(&step).map(|(x, dx)| x - dx).collect();
+            f_next = evaluate(&next);
+        }
+
+        (x, fx) = (next, f_next?);
+        let settled = x
+            .iter()
+            .zip(&step)
+            .all(|(x, dx)| dx.abs() <= EPSILON * x.abs().max(1.0));
+        if settled {
+            return (norm(&fx) <= 1e-8 * scale).then_some(x);
+        }
+    }
+    None
+}
+
+/// Solves a square system of equations with Newton's method from `guess`,
+/// one starting value per variable. The Jacobian is taken symbolically
+/// wherever the derivative is known.
+pub fn nsolve_system(equations: &[Expr], variables: &[String], guess: &[f64]) -> Result<Vec<f64>> {
+    if equations.len() != variables.len() {
+        bail!(SolverError::NotSquare(equations.len(), variables.len()));
+    }
+    if guess.len() != variables.len() {
+        bail!(SolverError::GuessCount(variables.len(), guess.len()));
+    }
+
+    let differences: Vec<Expr> = equations.iter().map(difference).collect();
+    let f: Vec<MultiFunction> = differences
+        .iter()
+        .map(|difference| Box::new(function_of_many(difference, variables)) as MultiFunction)
+        .collect();
+    let jacobian: Vec<Vec<MultiFunction>> = differences
+        .iter()
+        .map(|difference| {
+            (0..variables.len())
+                .map(|index| partial(difference, variables, index))
+                .collect()
+        })
+        .collect();
+
+    match newton_system(&f, &jacobian, guess) {
+        Some(root) => Ok(root.into_iter().map(|x| round(x, 15) + 0.0).collect()),
+        None => {
+            let guess: Vec<String> = guess.iter().map(f64::to_string).collect();
+            bail!(SolverError::NoConvergence(format!(
+                "[{}]",
+                guess.join(", ")
+            )))
+        }
+    }
+}
@@ -0,0 +1,654 @@
+use anyhow::{bail, Result};
+
+use crate::error::SolverError;
+use crate::math::{gcd, Complex, Rational};
+use crate::numeric_evaluator::evaluate_expr;
+use crate::parser::{Expr, Op};
+
+use super::aberth::aberth;
+use super::radical::Radical;
+use super::{contains_variable, Solution};
+
+/// Highest power accepted when expanding `(...)^n`.
+const MAX_DEGREE: usize = 64;
+
+/// Most Newton steps spent refining a numeric root.
+const POLISH_STEPS: usize = 20;
+
+/// Largest constant term or leading coefficient whose divisors are tried as
+/// rational roots.
+const DIVISOR_LIMIT: i128 = 1_000_000_000_000;
+
+/// Field the coefficients of a polynomial are extracted into.
+pub(super) trait Coefficient: Clone + Sized {
+    fn number(value: f64) -> Option<Self>;
+    /// Any other subexpression free of the variable, such as `pi` or `sin(2)`.
+    fn constant(expr: &Expr) -> Option<Self>;
+    fn zero() -> Self;
+    fn is_zero(&self) -> bool;
+    fn add(&self, other: &Self) -> Option<Self>;
+    fn sub(&self, other: &Self) -> Option<Self>;
+    fn mul(&self, other: &Self) -> Option<Self>;
+    fn div(&self, other: &Self) -> Option<Self>;
+    fn to_integer(&self) -> Option<i32>;
+}
+
+impl Coefficient for Rational {
+    fn number(value: f64) -> Option<Self> {
+        Rational::from_f64(value)
+    }
+
+    fn constant(_expr: &Expr) -> Option<Self> {
+        None
+    }
+
+    fn zero() -> Self {
+        Rational::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        Rational::is_zero(self)
+    }
+
+    fn add(&self, other: &Self) -> Option<Self> {
+        self.checked_add(other)
+    }
+
+    fn sub(&self, other: &Self) -> Option<Self> {
+        self.checked_sub(other)
+    }
+
+    fn mul(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(other)
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        self.checked_div(other)
+    }
+
+    fn to_integer(&self) -> Option<i32> {
+        self.is_integer()
+            .then(|| i32::try_from(self.numerator()).ok())
+            .flatten()
+    }
+}
+
+impl Coefficient for f64 {
+    fn number(value: f64) -> Option<Self> {
+        Some(value)
+    }
+
+    fn constant(expr: &Expr) -> Option<Self> {
+        evaluate_expr(expr).ok()
+    }
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0.0
+    }
+
+    fn add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn sub(&self, other: &Self) -> Option<Self> {
+        Some(self - other)
+    }
+
+    fn mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        let quotient = self / other;
+        quotient.is_finite().then_some(quotient)
+    }
+
+    fn to_integer(&self) -> Option<i32> {
+        (self.fract() == 0.0 && self.abs() <= i32::MAX as f64).then_some(*self as i32)
+    }
+}
+
+fn trim<T: Coefficient>(mut poly: Vec<T>) -> Vec<T> {
+    while poly.len() > 1 && poly.last().is_some_and(|c| c.is_zero()) {
+        poly.pop();
+    }
+    poly
+}
+
+fn combine<T: Coefficient>(lhs: Vec<T>, rhs: Vec<T>, subtract: bool) -> Option<Vec<T>> {
+    let zero = T::zero();
+    (0..lhs.len().max(rhs.len()))
+        .map(|i| {
+            let a = lhs.get(i).unwrap_or(&zero);
+            let b = rhs.get(i).unwrap_or(&zero);
+            if subtract {
+                a.sub(b)
+            } else {
+                a.add(b)
+            }
+        })
+        .collect()
+}
+
+fn multiply<T: Coefficient>(lhs: &[T], rhs: &[T]) -> Option<Vec<T>> {
+    if lhs.len() + rhs.len() - 1 > MAX_DEGREE + 1 {
+        return None;
+    }
+
+    let mut product = vec![T::zero(); lhs.len() + rhs.len() - 1];
+    for (i, a) in lhs.iter().enumerate() {
+        for (j, b) in rhs.iter().enumerate() {
+            product[i + j] = product[i + j].add(&a.mul(b)?)?;
+        }
+    }
+    Some(product)
+}
+
+/// Expands `expr` into dense coefficients of `variable`, lowest degree
+/// first. Fails if the variable appears anywhere but in non-negative
+/// integer powers, or if a coefficient does not fit into `T`.
+pub(super) fn coefficients<T: Coefficient>(expr: &Expr, variable: &str) -> Option<Vec<T>> {
+    let poly = match expr {
+        Expr::Number(value) => vec![T::number(*value)?],
+        Expr::Monomial {
+            coefficient,
+            variable: name,
+            exponent,
+        } if name == variable || *exponent == 0.0 => {
+            let degree = T::number(*exponent)?.to_integer()?;
+            if degree < 0 || degree as usize > MAX_DEGREE {
+                return None;
+            }
+
+            let mut poly = vec![T::zero(); degree as usize + 1];
+            poly[degree as usize] = T::number(*coefficient)?;
+            poly
+        }
+        Expr::UnaryMinus(inner) => combine(Vec::new(), coefficients(inner, variable)?, true)?,
+        Expr::BinOp {
+            lhs,
+            op: op @ (Op::Add | Op::Subtract),
+            rhs,
+        } => combine(
+            coefficients(lhs, variable)?,
+            coefficients(rhs, variable)?,
+            *op == Op::Subtract,
+        )?,
+        Expr::BinOp {
+            lhs,
+            op: Op::Multiply,
+            rhs,
+        } => multiply(&coefficients(lhs, variable)?, &coefficients(rhs, variable)?)?,
+        Expr::BinOp {
+            lhs,
+            op: Op::Divide,
+            rhs,
+        } => {
+            let divisor = trim(coefficients::<T>(rhs, variable)?);
+            if divisor.len() != 1 {
+                return None;
+            }
+
+            coefficients::<T>(lhs, variable)?
+                .iter()
+                .map(|c| c.div(&divisor[0]))
+                .collect::<Option<_>>()?
+        }
+        Expr::BinOp {
+            lhs,
+            op: Op::Power,
+            rhs,
+        } if !contains_variable(rhs, variable) => {
+            let base = trim(coefficients::<T>(lhs, variable)?);
+            let exponent = trim(coefficients::<T>(rhs, variable)?);
+            let exponent = match exponent.as_slice() {
+                [exponent] => exponent.to_integer(),
+                _ => None,
+            };
+
+            match exponent {
+                Some(exponent) if (0..=MAX_DEGREE as i32).contains(&exponent) => {
+                    let mut power = vec![T::number(1.0)?];
+                    for _ in 0..exponent {
+                        power = multiply(&power, &base)?;
+                    }
+                    power
+                }
+                Some(exponent) if base.len() == 1 && exponent < 0 => {
+                    let mut power = T::number(1.0)?;
+                    for _ in 0..exponent.unsigned_abs() {
+                        power = power.div(&base[0])?;
+                    }
+                    vec![power]
+                }
+                _ if !contains_variable(lhs, variable) => vec![T::constant(expr)?],
+                _ => return None,
+            }
+        }
+        Expr::Constant { .. } | Expr::Function { .. } | Expr::BinOp { .. }
+            if !contains_variable(expr, variable) =>
+        {
+            vec![T::constant(expr)?]
+        }
+        _ => return None,
+    };
+
+    Some(poly)
+}
+
+/// The degree `expr` would have as a polynomial in `variable` if it is
+/// beyond [`MAX_DEGREE`], so that such equations are not reported as
+/// non-polynomial. Cancellation is not taken into account.
+fn degree_bound(expr: &Expr, variable: &str) -> Option<usize> {
+    fn bound(expr: &Expr, variable: &str) -> Option<f64> {
+        match expr {
+            Expr::Monomial {
+                variable: name,
+                exponent,
+                ..
+            } if name == variable => Some(*exponent),
+            Expr::UnaryMinus(inner) => bound(inner, variable),
+            Expr::BinOp { lhs, op, rhs } => {
+                let (lhs_degree, rhs_degree) = (bound(lhs, variable)?, bound(rhs, variable)?);
+                match op {
+                    Op::Add | Op::Subtract => Some(lhs_degree.max(rhs_degree)),
+                    Op::Multiply => Some(lhs_degree + rhs_degree),
+                    Op::Divide if rhs_degree == 0.0 => Some(lhs_degree),
+                    Op::Power if rhs_degree == 0.0 => match **rhs {
+                        Expr::Number(exponent) => Some(lhs_degree * exponent),
+                        _ if lhs_degree == 0.0 => Some(0.0),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ if !contains_variable(expr, variable) => Some(0.0),
+            _ => None,
+        }
+    }
+
+    let degree = bound(expr, variable)?;
+    (degree.fract() == 0.0 && degree > MAX_DEGREE as f64).then_some(degree as usize)
+}
+
+/// Evaluates a polynomial at a rational point, `None` on overflow.
+fn evaluate_rational(poly: &[Rational], x: Rational) -> Option<Rational> {
+    poly.iter()
+        .rev()
+        .try_fold(Rational::ZERO, |acc, c| acc.checked_mul(&x)?.checked_add(c))
+}
+
+pub(super) fn evaluate_complex(poly: &[f64], x: Complex) -> Complex {
+    poly.iter()
+        .rev()
+        .fold(Complex::ZERO, |acc, c| acc * x + Complex::real(*c))
+}
+
+/// Divides out the root `root` by synthetic division, `None` on overflow.
+fn deflate(poly: &[Rational], root: Rational) -> Option<Vec<Rational>> {
+    let mut quotient = vec![Rational::ZERO; poly.len() - 1];
+    let mut carry = Rational::ZERO;
+    for i in (1..poly.len()).rev() {
+        carry = carry.checked_mul(&root)?.checked_add(&poly[i])?;
+        quotient[i - 1] = carry;
+    }
+    Some(quotient)
+}
+
+fn divisors(n: i128) -> Option<Vec<i128>> {
+    let n = n.abs();
+    if n > DIVISOR_LIMIT {
+        return None;
+    }
+
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut d = 1;
+    while d * d <= n {
+        if n % d == 0 {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    Some(small)
+}
+
+/// Finds the smallest rational root `p/q` of a polynomial, where `p` divides
+/// the constant term and `q` the leading coefficient once both are scaled to
+/// integers.
+fn rational_root(poly: &[Rational]) -> Option<Rational> {
+    if poly[0].is_zero() {
+        return Some(Rational::ZERO);
+    }
+
+    let scale = poly.iter().try_fold(1i128, |lcm, c| {
+        (lcm / gcd(lcm, c.denominator())).checked_mul(c.denominator())
+    })?;
+    let constant = poly[0].checked_mul(&Rational::integer(scale))?.numerator();
+    let leading = poly
+        .last()?
+        .checked_mul(&Rational::integer(scale))?
+        .numerator();
+
+    let mut candidates = Vec::new();
+    for p in divisors(constant)? {
+        for q in divisors(leading)? {
+            let candidate = Rational::new(p, q)?;
+            candidates.push(-candidate);
+            candidates.push(candidate);
+        }
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .find(|candidate| evaluate_rational(poly, *candidate).is_some_and(|value| value.is_zero()))
+}
+
+/// Roots of the monic `y^2 + b*y + c`, as `-b/2 -+ sqrt(b^2/4 - c)`.
+pub(super) fn quadratic(b: &Radical, c: &Radical) -> Vec<Radical> {
+    let half = Radical::from(Rational::new(1, 2).unwrap());
+    let center = b.mul(&half).neg();
+    let discriminant = center.mul(&center).sub(c).sqrt();
+    vec![center.sub(&discriminant), center.add(&discriminant)]
+}
+
+/// Cardano's formula for the monic `x^3 + a*x^2 + b*x + c`. Three distinct
+/// real roots have no expression in real radicals and are given in
+/// trigonometric form instead.
+fn cubic(a: Rational, b: Rational, c: Rational) -> Option<Vec<Radical>> {
+    let third = Rational::new(1, 3)?;
+    let shift = Radical::from(a.checked_mul(&third)?);
+
+    // Depressed cubic t^3 + p*t + q with x = t - a/3
+    let p = b.checked_sub(&a.checked_mul(&a)?.checked_mul(&third)?)?;
+    let q = Rational::new(2, 27)?
+        .checked_mul(&a.checked_pow(3)?)?
+        .checked_sub(&a.checked_mul(&b)?.checked_mul(&third)?)?
+        .checked_add(&c)?;
+    let discriminant = q
+        .checked_mul(&q)?
+        .checked_mul(&Rational::new(1, 4)?)?
+        .checked_add(&p.checked_pow(3)?.checked_mul(&Rational::new(1, 27)?)?)?;
+    if discriminant.is_negative() {
+        return trigonometric_cubic(p, q, discriminant, &shift);
+    }
+
+    let center = Radical::from(-q.checked_mul(&Rational::new(1, 2)?)?);
+    let root = Radical::from(discriminant).sqrt();
+    let u = center.add(&root).cbrt();
+    let v = center.sub(&root).cbrt();
+
+    let sum = u.add(&v);
+    let real = sum.mul(&Radical::from(Rational::new(-1, 2)?)).sub(&shift);
+    let imaginary = Radical::from(Rational::new(-3, 4)?).sqrt().mul(&u.sub(&v));
+
+    Some(vec![
+        sum.sub(&shift),
+        real.sub(&imaginary),
+        real.add(&imaginary),
+    ])
+}
+
+/// The three real roots of the depressed `t^3 + p*t + q` with a negative
+/// discriminant, as `2 sqrt(-p/3) cos(acos(x)/3 - 120k)` for `x = 3q/(2p)
+/// sqrt(-3/p)`. The evaluator has no `acos`, so it is written as `90 -
+/// atan(x/sqrt(1 - x^2))`, whose argument `-sign(q) sqrt(-q^2/(4D))` only
+/// needs a rational square root.
+fn trigonometric_cubic(
+    p: Rational,
+    q: Rational,
+    discriminant: Rational,
+    shift: &Radical,
+) -> Option<Vec<Radical>> {
+    let ratio = q
+        .checked_mul(&q)?
+        .checked_div(&discriminant.checked_mul(&Rational::integer(-4))?)?;
+    let mut tangent = Radical::from(ratio).sqrt();
+    if q > Rational::ZERO {
+        tangent = tangent.neg();
+    }
+
+    let third = tangent.atan().mul(&Radical::from(Rational::new(1, 3)?));
+    let scale = Radical::from(-p.checked_mul(&Rational::new(1, 3)?)?)
+        .sqrt()
+        .mul(&Radical::from(Rational::integer(2)));
+
+    Some(
+        (0..3)
+            .map(|k| {
+                let angle = Radical::from(Rational::integer(30 - 120 * k)).sub(&third);
+                scale.mul(&angle.cos()).sub(shift)
+            })
+            .collect(),
+    )
+}
+
+/// Ferrari's method for the monic `x^4 + a*x^3 + b*x^2 + c*x + d`.
+fn quartic(a: Rational, b: Rational, c: Rational, d: Rational) -> Option<Vec<Radical>> {
+    let shift = Radical::from(a.checked_mul(&Rational::new(1, 4)?)?);
+    let square = a.checked_mul(&a)?;
+
+    // Depressed quartic y^4 + p*y^2 + q*y + r with x = y - a/4
+    let p = b.checked_sub(&square.checked_mul(&Rational::new(3, 8)?)?)?;
+    let q = c
+        .checked_sub(&a.checked_mul(&b)?.checked_mul(&Rational::new(1, 2)?)?)?
+        .checked_add(&square.checked_mul(&a)?.checked_mul(&Rational::new(1, 8)?)?)?;
+    let r = d
+        .checked_sub(&a.checked_mul(&c)?.checked_mul(&Rational::new(1, 4)?)?)?
+        .checked_add(
+            &square
+                .checked_mul(&b)?
+                .checked_mul(&Rational::new(1, 16)?)?,
+        )?
+        .checked_sub(
+            &square
+                .checked_mul(&square)?
+                .checked_mul(&Rational::new(3, 256)?)?,
+        )?;
+
+    let roots = if q.is_zero() {
+        // Biquadratic: solve for z = y^2
+        quadratic(&Radical::from(p), &Radical::from(r))
+            .into_iter()
+            .flat_map(|z| {
+                let root = z.sqrt();
+                [root.neg(), root]
+            })
+            .collect::<Vec<_>>()
+    } else {
+        // A positive root of the resolvent m^3 + p*m^2 + (p^2/4 - r)*m - q^2/8
+        let resolvent = [
+            -q.checked_mul(&q)?.checked_mul(&Rational::new(1, 8)?)?,
+            p.checked_pow(2)?
+                .checked_mul(&Rational::new(1, 4)?)?
+                .checked_sub(&r)?,
+            p,
+            Rational::ONE,
+        ];
+        let m = positive_resolvent_root(&resolvent)?;
+
+        let s = m.mul(&Radical::from(Rational::integer(2))).sqrt();
+        let base = Radical::from(p.checked_mul(&Rational::new(1, 2)?)?).add(&m);
+        let offset = Radical::from(q).div(&s.mul(&Radical::from(Rational::integer(2))));
+
+        let mut roots = quadratic(&s.neg(), &base.add(&offset));
+        roots.extend(quadratic(&s, &base.sub(&offset)));
+        roots
+    };
+
+    Some(roots.into_iter().map(|y| y.sub(&shift)).collect())
+}
+
+fn positive_resolvent_root(resolvent: &[Rational]) -> Option<Radical> {
+    let mut poly = resolvent.to_vec();
+    while poly.len() > 1 {
+        let Some(root) = rational_root(&poly) else {
+            break;
+        };
+        if root > Rational::ZERO {
+            return Some(Radical::from(root));
+        }
+        poly = deflate(&poly, root)?;
+    }
+
+    match poly.as_slice() {
+        [c, b, a, _] => cubic(*a, *b, *c)?
+            .into_iter()
+            .find(|m| m.value().is_real(1e-12) && m.value().re > 0.0),
+        [c, b, _] => quadratic(&Radical::from(*b), &Radical::from(*c))
+            .into_iter()
+            .find(|m| m.value().is_real(1e-12) && m.value().re > 0.0),
+        _ => None,
+    }
+}
+
+/// Whether `root` satisfies the polynomial up to rounding errors.
+pub(super) fn is_root(poly: &[f64], root: Complex) -> bool {
+    let scale = poly
+        .iter()
+        .enumerate()
+        .map(|(k, c)| c.abs() * root.abs().powi(k as i32))
+        .sum::<f64>();
+    evaluate_complex(poly, root).abs() <= 1e-8 * scale.max(1.0)
+}
+
+/// Refines an approximate root with Newton's method and keeps it only if it
+/// then satisfies the polynomial. Aberth's method in `f64` can lose roots
+/// that are tiny next to the others, such as `1e-21` next to `1e21`.
+fn polish(poly: &[f64], mut root: Complex) -> Option<Complex> {
+    let derivative: Vec<f64> = (1..poly.len()).map(|k| poly[k] * k as f64).collect();
+    for _ in 0..POLISH_STEPS {
+        let value = evaluate_complex(poly, root);
+        let step = value / evaluate_complex(&derivative, root);
+        let next = root - step;
+        if !(next.re.is_finite() && next.im.is_finite())
+            || evaluate_complex(poly, next).abs() >= value.abs()
+        {
+            break;
+        }
+        root = next;
+    }
+    is_root(poly, root).then_some(root)
+}
+
+/// Approximates the roots of a polynomial, dropping any that fail to check
+/// out against it.
+fn numeric_roots(poly: &[f64]) -> Vec<Radical> {
+    aberth(poly)
+        .into_iter()
+        .filter_map(|root| polish(poly, root))
+        .map(Radical::approximate)
+        .collect()
+}
+
+/// Closed forms for a factor without rational roots, falling back to
+/// numeric roots when there is none or it fails to check out.
+fn radical_roots(poly: &[Rational]) -> Vec<Radical> {
+    let leading = *poly.last().unwrap();
+    let monic: Option<Vec<Rational>> = poly.iter().map(|c| c.checked_div(&leading)).collect();
+
+    let roots = match monic.as_deref() {
+        Some([c, b, _]) => Some(quadratic(&Radical::from(*b), &Radical::from(*c))),
+        Some([d, c, b, _]) => cubic(*b, *c, *d),
+        Some([e, d, c, b, _]) => quartic(*b, *c, *d, *e),
+        _ => None,
+    };
+
+    let approximate: Vec<f64> = poly.iter().map(|c| c.to_f64()).collect();
+    match roots {
+        Some(roots) if roots.iter().all(|root| is_root(&approximate, root.value())) => roots,
+        _ => numeric_roots(&approximate),
+    }
+}
+
+/// Rational roots first, then closed forms or approximations for what is
+/// left. When a coefficient overflows, the rest is solved numerically.
+pub(super) fn exact_roots(mut poly: Vec<Rational>) -> Vec<Radical> {
+    let mut roots = Vec::new();
+    while poly.len() > 1 {
+        let Some(root) = rational_root(&poly) else {
+            break;
+        };
+        let Some(quotient) = deflate(&poly, root) else {
+            break;
+        };
+        roots.push(Radical::from(root));
+        poly = quotient;
+    }
+
+    match poly.as_slice() {
+        [] | [_] => {}
+        [c, b] => match c.checked_div(b) {
+            Some(root) => roots.push(Radical::from(-root)),
+            None => roots.extend(numeric_roots(&[c.to_f64(), b.to_f64()])),
+        },
+        _ => roots.extend(radical_roots(&poly)),
+    }
+    roots
+}
+
+/// Real roots in ascending order first, then complex ones by real and
+/// imaginary part. Repeated roots are only kept once.
+fn sort_roots(mut roots: Vec<Radical>) -> Vec<Expr> {
+    let key = |root: &Radical| {
+        let value = root.value();
+        (!value.is_real(1e-12), value.re, value.im)
+    };
+    roots.sort_by(|a, b| {
+        let (a, b) = (key(a), key(b));
+        a.0.cmp(&b.0)
+            .then(a.1.total_cmp(&b.1))
+            .then(a.2.total_cmp(&b.2))
+    });
+    roots.dedup_by(|a, b| (a.value() - b.value()).abs() <= 1e-9 * b.value().abs().max(1.0));
+    roots.into_iter().map(|root| root.expr().clone()).collect()
+}
+
+/// Solves `lhs = rhs` when both sides are polynomials in `variable`. Up to
+/// degree four the roots are given in radicals whenever the coefficients
+/// are rational, higher degrees are approximated numerically.
+pub fn solve_polynomial(lhs: &Expr, rhs: &Expr, variable: &str) -> Result<Solution> {
+    let difference = Expr::BinOp {
+        lhs: Box::new(lhs.clone()),
+        op: Op::Subtract,
+        rhs: Box::new(rhs.clone()),
+    };
+
+    let roots = if let Some(poly) = coefficients::<Rational>(&difference, variable) {
+        let poly = trim(poly);
+        if poly.len() == 1 {
+            return Ok(constant_solution(poly[0].is_zero()));
+        }
+        exact_roots(poly)
+    } else if let Some(poly) = coefficients::<f64>(&difference, variable) {
+        let poly = trim(poly);
+        if poly.len() == 1 {
+            return Ok(constant_solution(poly[0] == 0.0));
+        }
+        numeric_roots(&poly)
+    } else if let Some(degree) = degree_bound(&difference, variable) {
+        bail!(SolverError::DegreeTooHigh(degree, MAX_DEGREE))
+    } else {
+        bail!(SolverError::NonPolynomial(variable.to_string()))
+    };
+
+    Ok(Solution::Finite(sort_roots(roots)))
+}
+
+fn constant_solution(holds: bool) -> Solution {
+    if holds {
+        Solution::All
+    } else {
+        Solution::Empty
+    }
+}
@@ -0,0 +1,330 @@
+use crate::math::{round, Complex, Rational};
+use crate::parser::{Expr, Op};
+
+/// Largest radicand whose square and cube factors are pulled out.
+const FACTOR_LIMIT: u128 = 1_000_000_000_000;
+
+/// The imaginary unit, printed as `i`. It has no real value, so evaluating
+/// it yields NaN.
+pub fn imaginary_unit() -> Expr {
+    Expr::Constant {
+        name: "i".to_string(),
+        value: f64::NAN,
+    }
+}
+
+/// Prints an approximate complex value, rounded so that floating point noise
+/// does not show up in the output. Values below one keep 12 significant
+/// digits, so a root such as `1e-21` is not printed as `0`.
+pub fn complex_expr(value: Complex) -> Expr {
+    let magnitude = value.abs();
+    let round = |x: f64| {
+        if magnitude > 0.0 && magnitude < 1.0 {
+            let decimals = (11 - magnitude.log10().floor() as i32) as usize;
+            format!("{x:.decimals$}").parse().unwrap_or(x) + 0.0
+        } else {
+            round(x, 12) + 0.0
+        }
+    };
+    let (re, im) = (round(value.re), round(value.im));
+    let imaginary = || {
+        if im.abs() == 1.0 {
+            imaginary_unit()
+        } else {
+            Expr::BinOp {
+                lhs: Box::new(Expr::Number(im.abs())),
+                op: Op::Multiply,
+                rhs: Box::new(imaginary_unit()),
+            }
+        }
+    };
+
+    if im == 0.0 {
+        Expr::Number(re)
+    } else if re == 0.0 && im < 0.0 {
+        Expr::UnaryMinus(Box::new(imaginary()))
+    } else if re == 0.0 {
+        imaginary()
+    } else {
+        Expr::BinOp {
+            lhs: Box::new(Expr::Number(re)),
+            op: if im < 0.0 { Op::Subtract } else { Op::Add },
+            rhs: Box::new(imaginary()),
+        }
+    }
+}
+
+/// Splits `n` into `k^power * m` with `m` free of `power`-th powers.
+fn extract_power(n: u128, power: u32) -> (u128, u128) {
+    if n == 0 {
+        return (0, 1);
+    }
+
+    if n > FACTOR_LIMIT {
+        return (1, n);
+    }
+
+    let (mut outside, mut inside) = (1, n);
+    let mut factor = 2u128;
+    while factor.pow(power) <= inside {
+        while inside % factor.pow(power) == 0 {
+            inside /= factor.pow(power);
+            outside *= factor;
+        }
+        factor += 1;
+    }
+    (outside, inside)
+}
+
+/// A closed-form value built from rationals with `+ - * /`, square and cube
+/// roots, `i`, and the `cos` and `atan` (in degrees) of three real cube
+/// roots. The numeric value is carried along so roots can be sorted
+/// and checked against the polynomial they came from.
+#[derive(Debug, Clone)]
+pub struct Radical {
+    rational: Option<Rational>,
+    expr: Expr,
+    value: Complex,
+}
+
+impl From<Rational> for Radical {
+    fn from(value: Rational) -> Self {
+        Radical {
+            rational: Some(value),
+            expr: Expr::from(value),
+            value: Complex::real(value.to_f64()),
+        }
+    }
+}
+
+impl Radical {
+    /// An approximation that is only known numerically.
+    pub fn approximate(value: Complex) -> Radical {
+        Radical {
+            rational: None,
+            expr: complex_expr(value),
+            value,
+        }
+    }
+
+    fn imaginary() -> Radical {
+        Radical {
+            rational: None,
+            expr: imaginary_unit(),
+            value: Complex::I,
+        }
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    pub fn value(&self) -> Complex {
+        self.value
+    }
+
+    /// The exact value, if the radical is a plain rational.
+    pub fn rational(&self) -> Option<Rational> {
+        self.rational
+    }
+
+    fn is(&self, value: i128) -> bool {
+        self.rational == Some(Rational::integer(value))
+    }
+
+    fn binary(&self, op: Op, other: &Radical, value: Complex) -> Radical {
+        Radical {
+            rational: None,
+            expr: Expr::BinOp {
+                lhs: Box::new(self.expr.clone()),
+                op,
+                rhs: Box::new(other.expr.clone()),
+            },
+            value,
+        }
+    }
+
+    fn function(name: &str, arg: &Radical, value: Complex) -> Radical {
+        Radical {
+            rational: None,
+            expr: Expr::Function {
+                name: name.to_string(),
+                args: vec![Box::new(arg.expr.clone())],
+            },
+            value,
+        }
+    }
+
+    pub fn neg(&self) -> Radical {
+        match (self.rational, &self.expr) {
+            (Some(value), _) => Radical::from(-value),
+            (None, Expr::UnaryMinus(inner)) => Radical {
+                rational: None,
+                expr: *inner.clone(),
+                value: -self.value,
+            },
+            (None, expr) => Radical {
+                rational: None,
+                expr: Expr::UnaryMinus(Box::new(expr.clone())),
+                value: -self.value,
+            },
+        }
+    }
+
+    pub fn add(&self, other: &Radical) -> Radical {
+        if let (Some(a), Some(b)) = (self.rational, other.rational) {
+            if let Some(sum) = a.checked_add(&b) {
+                return Radical::from(sum);
+            }
+        }
+
+        match (self, other) {
+            (zero, other) | (other, zero) if zero.is(0) => other.clone(),
+            (
+                _,
+                Radical {
+                    expr: Expr::UnaryMinus(inner),
+                    ..
+                },
+            ) => self.binary(
+                Op::Subtract,
+                &Radical {
+                    rational: None,
+                    expr: *inner.clone(),
+                    value: -other.value,
+                },
+                self.value + other.value,
+            ),
+            _ => self.binary(Op::Add, other, self.value + other.value),
+        }
+    }
+
+    pub fn sub(&self, other: &Radical) -> Radical {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Radical) -> Radical {
+        if let (Some(a), Some(b)) = (self.rational, other.rational) {
+            if let Some(product) = a.checked_mul(&b) {
+                return Radical::from(product);
+            }
+        }
+
+        match (self, other) {
+            (zero, _) | (_, zero) if zero.is(0) => Radical::from(Rational::ZERO),
+            (one, other) | (other, one) if one.is(1) => other.clone(),
+            (minus_one, other) | (other, minus_one) if minus_one.is(-1) => other.neg(),
+            // Coefficients go first, as in `2*sqrt(3)`
+            (symbolic, coefficient) if coefficient.rational.is_some() => {
+                coefficient.binary(Op::Multiply, symbolic, self.value * other.value)
+            }
+            _ => self.binary(Op::Multiply, other, self.value * other.value),
+        }
+    }
+
+    pub fn div(&self, other: &Radical) -> Radical {
+        if let (Some(a), Some(b)) = (self.rational, other.rational) {
+            if let Some(quotient) = a.checked_div(&b) {
+                return Radical::from(quotient);
+            }
+        }
+
+        match other.rational.and_then(|value| value.recip()) {
+            // Keeps `sqrt(2)/2` as `(1/2)*sqrt(2)` next to other coefficients
+            Some(inverse) if !self.is(0) => Radical::from(inverse).mul(self),
+            _ => self.binary(Op::Divide, other, self.value / other.value),
+        }
+    }
+
+    /// Principal square root. Square factors of rationals are pulled out and
+    /// negative values become multiples of `i`.
+    pub fn sqrt(&self) -> Radical {
+        if let Some(value) = self.rational {
+            if value.is_negative() {
+                return Radical::from(-value).sqrt().mul(&Radical::imaginary());
+            }
+
+            // sqrt(p/q) = sqrt(p*q)/q
+            let radicand = value.numerator().checked_mul(value.denominator());
+            if let Some(radicand) = radicand {
+                let (outside, inside) = extract_power(radicand as u128, 2);
+                let coefficient = Rational::new(outside as i128, value.denominator()).unwrap();
+                if inside == 1 {
+                    return Radical::from(coefficient);
+                }
+
+                let root = Radical::function(
+                    "sqrt",
+                    &Radical::from(Rational::integer(inside as i128)),
+                    Complex::real((inside as f64).sqrt()),
+                );
+                return Radical::from(coefficient).mul(&root);
+            }
+        }
+
+        if self.value.is_real(1e-12) && self.value.re < 0.0 {
+            let negated = self.neg();
+            let root = Radical::function("sqrt", &negated, Complex::real((-self.value.re).sqrt()));
+            return root.mul(&Radical::imaginary());
+        }
+
+        Radical::function("sqrt", self, self.value.sqrt())
+    }
+
+    /// Cube root, real for real values. Cube factors of rationals are pulled
+    /// out.
+    pub fn cbrt(&self) -> Radical {
+        if let Some(value) = self.rational {
+            if value.is_negative() {
+                return Radical::from(-value).cbrt().neg();
+            }
+
+            // cbrt(p/q) = cbrt(p*q^2)/q
+            let radicand = value
+                .denominator()
+                .checked_mul(value.denominator())
+                .and_then(|square| value.numerator().checked_mul(square));
+            if let Some(radicand) = radicand {
+                let (outside, inside) = extract_power(radicand as u128, 3);
+                let coefficient = Rational::new(outside as i128, value.denominator()).unwrap();
+                if inside == 1 {
+                    return Radical::from(coefficient);
+                }
+
+                let root = Radical::function(
+                    "cbrt",
+                    &Radical::from(Rational::integer(inside as i128)),
+                    Complex::real((inside as f64).cbrt()),
+                );
+                return Radical::from(coefficient).mul(&root);
+            }
+        }
+
+        let value = if self.value.is_real(1e-12) {
+            Complex::real(self.value.re.cbrt())
+        } else {
+            self.value.cbrt()
+        };
+        Radical::function("cbrt", self, value)
+    }
+
+    /// Cosine of an angle in degrees, like the evaluator's `cos`.
+    pub fn cos(&self) -> Radical {
+        if self.is(0) {
+            return Radical::from(Rational::ONE);
+        }
+        Radical::function("cos", self, Complex::real(self.value.re.to_radians().cos()))
+    }
+
+    /// Arctangent in degrees, like the evaluator's `atan`.
+    pub fn atan(&self) -> Radical {
+        if self.is(0) {
+            return Radical::from(Rational::ZERO);
+        }
+        Radical::function(
+            "atan",
+            self,
+            Complex::real(self.value.re.atan().to_degrees()),
+        )
+    }
+}
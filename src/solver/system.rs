@@ -0,0 +1,275 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::error::{SolverError, SymbolicError};
+use crate::math::Rational;
+use crate::parser::{Expr, Op};
+use crate::polynomial::{Monomial, MonomialOrder, Polynomial};
+
+use super::groebner::solve_groebner;
+use super::numeric::nsolve_system;
+use super::sides;
+
+/// The solution set of a system of equations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemSolution {
+    /// Finitely many solutions, each with one value per variable.
+    Finite(Vec<Vec<(String, Expr)>>),
+    /// Infinitely many solutions: the `free` variables take any value and
+    /// the others are given in terms of them.
+    Parametric {
+        values: Vec<(String, Expr)>,
+        free: Vec<String>,
+    },
+    Empty,
+}
+
+/// How [`solve_system_with`] solves a system.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemStrategy {
+    /// Gaussian elimination over the rationals, for linear systems.
+    Elimination,
+    /// Back substitution through a lexicographic Gröbner basis, for
+    /// polynomial systems. Only real solutions are kept.
+    Groebner,
+    /// Newton's method from one starting value per variable.
+    Newton(Vec<f64>),
+}
+
+/// `[X = 1, Y = 2]` for one solution.
+fn equations(values: &[(String, Expr)]) -> Expr {
+    Expr::List(
+        values
+            .iter()
+            .map(|(variable, value)| {
+                Box::new(Expr::BinOp {
+                    lhs: Box::new(Polynomial::variable(variable).to_expr()),
+                    op: Op::Equals,
+                    rhs: Box::new(value.clone()),
+                })
+            })
+            .collect(),
+    )
+}
+
+impl SystemSolution {
+    /// The solution as a list of `variable = value` equations, or a list of
+    /// such lists when there are several. `None` when there is none.
+    pub fn to_expr(&self) -> Option<Expr> {
+        match self {
+            SystemSolution::Finite(solutions) => match solutions.as_slice() {
+                [solution] => Some(equations(solution)),
+                solutions => Some(Expr::List(
+                    solutions
+                        .iter()
+                        .map(|solution| Box::new(equations(solution)))
+                        .collect(),
+                )),
+            },
+            SystemSolution::Parametric { values, .. } => Some(equations(values)),
+            SystemSolution::Empty => None,
+        }
+    }
+}
+
+impl fmt::Display for SystemSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = |values: &[(String, Expr)]| {
+            let values = values
+                .iter()
+                .map(|(variable, value)| format!("{variable} = {}", value.to_string()))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("{{{values}}}")
+        };
+        match self {
+            SystemSolution::Finite(solutions) => {
+                let solutions: Vec<String> = solutions.iter().map(|s| values(s)).collect();
+                write!(f, "{}", solutions.join(", "))
+            }
+            SystemSolution::Parametric {
+                values: solution,
+                free,
+            } => write!(f, "{} for any {}", values(solution), free.join(", ")),
+            SystemSolution::Empty => write!(f, "no solution"),
+        }
+    }
+}
+
+/// Reads the items of a `[a, b, ...]` argument.
+fn list_items(expr: &Expr) -> Result<&[Box<Expr>]> {
+    match expr {
+        Expr::List(items) => Ok(items),
+        _ => bail!(SolverError::ExpectedList(expr.to_string())),
+    }
+}
+
+/// `lhs - rhs` of `equation` as a polynomial in `variables`.
+fn polynomial_of(equation: &Expr, variables: &[String]) -> Result<Polynomial> {
+    let (lhs, rhs) = sides(equation)?;
+    let difference = Polynomial::from_expr(lhs)? - Polynomial::from_expr(rhs)?;
+    if let Some(variable) = difference
+        .variables()
+        .into_iter()
+        .find(|variable| !variables.contains(variable))
+    {
+        bail!(SolverError::UnlistedVariable(variable));
+    }
+    Ok(difference)
+}
+
+/// The coefficients of `equation` moved into `a1 * X1 + ... + an * Xn = b`,
+/// with `b` last.
+fn row(equation: &Expr, variables: &[String]) -> Result<Vec<Rational>> {
+    let difference = polynomial_of(equation, variables)?;
+    let mut row = vec![Rational::ZERO; variables.len() + 1];
+    for (monomial, coefficient) in difference.terms(MonomialOrder::Lex) {
+        let Some((variable, power)) = monomial.powers().next() else {
+            row[variables.len()] = -coefficient;
+            continue;
+        };
+        if power != 1 || monomial.degree() != 1 {
+            bail!(SolverError::NonLinear(variable.to_string()));
+        }
+        let index = variables.iter().position(|name| name == variable).unwrap();
+        row[index] = coefficient;
+    }
+    Ok(row)
+}
+
+/// Brings the augmented matrix into reduced row echelon form and returns
+/// the pivot column of each nonzero row.
+fn reduce(matrix: &mut [Vec<Rational>], columns: usize) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for column in 0..columns {
+        let row = pivots.len();
+        let Some(pivot) = (row..matrix.len()).find(|i| !matrix[*i][column].is_zero()) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+
+        let scale = Rational::ONE / matrix[row][column];
+        for value in matrix[row].iter_mut() {
+            *value = *value * scale;
+        }
+        for other in 0..matrix.len() {
+            let factor = matrix[other][column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for j in column..matrix[other].len() {
+                matrix[other][j] = matrix[other][j] - factor * matrix[row][j];
+            }
+        }
+        pivots.push(column);
+    }
+    pivots
+}
+
+/// Reads a `[X, Y, ...]` argument.
+fn variables_of(variables: &Expr) -> Result<Vec<String>> {
+    list_items(variables)?
+        .iter()
+        .map(|variable| match variable.as_ref() {
+            Expr::Monomial {
+                coefficient,
+                variable,
+                exponent,
+            } if *coefficient == 1.0 && *exponent == 1.0 => Ok(variable.to_owned()),
+            other => bail!(SymbolicError::ExpectedVariable(
+                "solve".to_string(),
+                other.to_string()
+            )),
+        })
+        .collect()
+}
+
+/// Gaussian elimination over exact rationals. Inconsistent systems have no
+/// solution, while the variables without a pivot in an underdetermined
+/// system stay free and the others are solved in terms of them.
+fn eliminate(equations: &[Box<Expr>], variables: &[String]) -> Result<SystemSolution> {
+    let mut matrix = equations
+        .iter()
+        .map(|equation| row(equation, variables))
+        .collect::<Result<Vec<Vec<Rational>>>>()?;
+    let columns = variables.len();
+    let pivots = reduce(&mut matrix, columns);
+
+    // A row reduced to 0 = b with b nonzero
+    if matrix[pivots.len()..]
+        .iter()
+        .any(|row| !row[columns].is_zero())
+    {
+        return Ok(SystemSolution::Empty);
+    }
+
+    let free: Vec<usize> = (0..columns).filter(|j| !pivots.contains(j)).collect();
+    let values = pivots
+        .iter()
+        .zip(&matrix)
+        .map(|(&column, row)| {
+            let value = free
+                .iter()
+                .fold(Polynomial::constant(row[columns]), |value, &j| {
+                    value - Polynomial::term(row[j], Monomial::variable(&variables[j], 1))
+                });
+            (variables[column].to_owned(), value.to_expr())
+        })
+        .collect();
+
+    Ok(if free.is_empty() {
+        SystemSolution::Finite(vec![values])
+    } else {
+        SystemSolution::Parametric {
+            values,
+            free: free.into_iter().map(|j| variables[j].to_owned()).collect(),
+        }
+    })
+}
+
+/// Solves a list of equations such as `[2X + 3Y = 5, X - Y = 1]` for a
+/// list of variables `[X, Y]` with the given strategy.
+pub fn solve_system_with(
+    equations: &Expr,
+    variables: &Expr,
+    strategy: &SystemStrategy,
+) -> Result<SystemSolution> {
+    let variables = variables_of(variables)?;
+    let equations = list_items(equations)?;
+    match strategy {
+        SystemStrategy::Elimination => eliminate(equations, &variables),
+        SystemStrategy::Groebner => {
+            let polynomials = equations
+                .iter()
+                .map(|equation| polynomial_of(equation, &variables))
+                .collect::<Result<Vec<Polynomial>>>()?;
+            let solutions = solve_groebner(&polynomials, &variables)?;
+            Ok(if solutions.is_empty() {
+                SystemSolution::Empty
+            } else {
+                SystemSolution::Finite(solutions)
+            })
+        }
+        SystemStrategy::Newton(guess) => {
+            let equations: Vec<Expr> = equations.iter().map(|eq| eq.as_ref().clone()).collect();
+            let root = nsolve_system(&equations, &variables, guess)?;
+            let values = variables
+                .into_iter()
+                .zip(root.into_iter().map(Expr::Number));
+            Ok(SystemSolution::Finite(vec![values.collect()]))
+        }
+    }
+}
+
+/// Solves a list of equations for a list of variables exactly: linear
+/// systems by Gaussian elimination, which also describes underdetermined
+/// ones, and other polynomial systems through a Gröbner basis.
+pub fn solve_system(equations: &Expr, variables: &Expr) -> Result<SystemSolution> {
+    match solve_system_with(equations, variables, &SystemStrategy::Elimination) {
+        Err(err) if matches!(err.downcast_ref(), Some(SolverError::NonLinear(_))) => {
+            solve_system_with(equations, variables, &SystemStrategy::Groebner)
+        }
+        solution => solution,
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{collect, evaluate, expand};
+    use crate::parser::parse;
+
+    fn setup_expand(expression: &str) -> String {
+        expand(&parse(expression).unwrap()).to_string()
+    }
+
+    fn setup_collect(expression: &str) -> String {
+        collect(&parse(expression).unwrap(), "X").to_string()
+    }
+
+    fn setup_evaluate(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_expand_products() {
+        assert_eq!("(2X^(1)+1)", setup_expand("(X+1)^2 - X^2"));
+        assert_eq!("(1X^(2)-1)", setup_expand("(X+1)*(X-1)"));
+        assert_eq!("(2X^(1)+6)", setup_expand("2*(X+3)"));
+        assert_eq!("(-1X^(1)+1)", setup_expand("-(X-1)"));
+        assert_eq!("0.5X^(1)", setup_expand("X/3 + X/6"));
+        assert_eq!(
+            "((((1A^(1)*1C^(1))+(1A^(1)*1D^(1)))+(1B^(1)*1C^(1)))+(1B^(1)*1D^(1)))",
+            setup_expand("(A+B)*(C+D)")
+        );
+    }
+
+    #[test]
+    fn can_expand_powers() {
+        assert_eq!(
+            "(((1X^(3)+(3X^(2)*1Y^(1)))+(3X^(1)*1Y^(2)))+1Y^(3))",
+            setup_expand("(X+Y)^3")
+        );
+        assert_eq!("((1X^(2)-2X^(1))+1)", setup_expand("(1 - X)^2"));
+        assert_eq!("8X^(1)", setup_expand("2^3*X"));
+    }
+
+    #[test]
+    fn can_keep_overflowing_powers() {
+        // The coefficients of (3X+7)^60 don't fit into 128 bits
+        assert_eq!("(((3X^(1)+7)^60)+1)", setup_expand("(3X+7)^60 + 1"));
+    }
+
+    #[test]
+    fn can_expand_around_functions() {
+        assert_eq!(
+            "((1X^(1)*sin(((1X^(2)+2X^(1))+1)))+sin(((1X^(2)+2X^(1))+1)))",
+            setup_expand("sin((X+1)^2)*(X+1)")
+        );
+        assert_eq!("((1X^(1)/1Y^(1))+(1/1Y^(1)))", setup_expand("(X+1)/Y"));
+        assert_eq!("((1X^(1)*pi)+pi)", setup_expand("pi*(X+1)"));
+    }
+
+    #[test]
+    fn can_collect_powers() {
+        assert_eq!("(((1Y^(1)+1)*1X^(1))+2)", setup_collect("X*Y + X + 2"));
+        assert_eq!(
+            "(((1Y^(1)-1)*1X^(2))+((1Y^(2)+3)*1X^(1)))",
+            setup_collect("X^2*Y - X^2 + X*Y^2 + 3X")
+        );
+        assert_eq!("(-1X^(2)+1Y^(1))", setup_collect("2X^2 - 3X^2 + Y"));
+        assert_eq!("((sin(1Y^(1))+1)*1X^(1))", setup_collect("sin(Y)*X + X"));
+    }
+
+    #[test]
+    fn can_call_symbolic_functions() {
+        assert_eq!(
+            "(((1X^(2)+2X^(1))+1)+1)",
+            setup_evaluate("expand((X+1)^2) + 1")
+        );
+        assert_eq!("((1Y^(1)+1)*1X^(1))", setup_evaluate("collect(X*Y + X, X)"));
+        assert_eq!(
+            "sin(((1X^(2)+2X^(1))+1))",
+            setup_evaluate("sin(expand((X+1)^2))")
+        );
+        assert_eq!(
+            "Syntax error: 'collect' expects a variable, found '2'",
+            setup_evaluate("collect(X, 2)")
+        );
+        assert_eq!(
+            "Syntax error: 'expand' takes 1 arguments but 2 were given",
+            setup_evaluate("expand(X, Y)")
+        );
+    }
+}
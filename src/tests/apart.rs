@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{apart, evaluate};
+    use crate::parser::parse;
+
+    fn setup(expression: &str) -> String {
+        match apart(&parse(expression).unwrap(), "X") {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_split_linear_factors() {
+        assert_eq!("(-((1/(1X^(1)+1)))+(2/(1X^(1)-1)))", setup("(X+3)/(X^2-1)"));
+        assert_eq!(
+            "(-((1/(2*(1X^(1)+1))))+(1/(2*(1X^(1)-1))))",
+            setup("1/(X^2-1)")
+        );
+        assert_eq!("((1/1X^(1))-(1/(1X^(1)+1)))", setup("1/(X*(X+1))"));
+    }
+
+    #[test]
+    fn can_split_repeated_factors() {
+        assert_eq!(
+            "((1/(1X^(1)+1))+(1/((1X^(1)+1)^2)))",
+            setup("(X+2)/(X+1)^2")
+        );
+        assert_eq!(
+            "((-((1/1X^(1)))-(1/1X^(2)))+(1/(1X^(1)-1)))",
+            setup("1/(X^2*(X-1))")
+        );
+    }
+
+    #[test]
+    fn can_split_quadratic_factors() {
+        assert_eq!("((1/1X^(1))-(1X^(1)/(1X^(2)+1)))", setup("1/(X^3+X)"));
+        assert_eq!(
+            "(((3/(4*(1X^(1)-1)))-((3X^(1)+3)/(4*(1X^(2)+1))))-((1X^(1)+1)/(2*((1X^(2)+1)^2))))",
+            setup("(X^2+2)/((X^2+1)^2*(X-1))")
+        );
+    }
+
+    #[test]
+    fn can_keep_polynomial_part() {
+        assert_eq!(
+            "((1X^(1)+(7/(4*(1X^(1)+2))))+(9/(4*(1X^(1)-2))))",
+            setup("(X^3+1)/(X^2-4)")
+        );
+        assert_eq!("(1X^(1)+1)", setup("(X^2-1)/(X-1)"));
+        assert_eq!("(1X^(2)+1)", setup("X^2 + 1"));
+    }
+
+    #[test]
+    fn can_split_with_parameters() {
+        assert_eq!(
+            "(-((1Y^(1)/(2*(1X^(1)+1))))+(1Y^(1)/(2*(1X^(1)-1))))",
+            setup("Y/(X^2-1)")
+        );
+        assert_eq!(
+            "Polynomial error: 'X^2 - Y' depends on variables other than 'X'",
+            setup("1/(X^2-Y)")
+        );
+    }
+
+    #[test]
+    fn can_evaluate_apart() {
+        match evaluate(&parse("apart(1/(X^2-X))").unwrap()) {
+            Ok(expr) => assert_eq!("(-((1/1X^(1)))+(1/(1X^(1)-1)))", expr.to_string()),
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod test {
+    use crate::parser::{func, num, parse, pow, var};
+
+    #[test]
+    fn can_build_variables() {
+        assert_eq!(parse("X").unwrap(), var("X"));
+        assert_eq!(parse("X^2").unwrap(), var("X").pow(2));
+        assert_eq!(parse("X^2").unwrap(), pow(var("X"), 2));
+    }
+
+    #[test]
+    fn can_build_numbers() {
+        assert_eq!(parse("3.5").unwrap(), num(3.5));
+        assert_eq!(parse("-2").unwrap(), num(-2.0));
+    }
+
+    #[test]
+    fn can_build_operators() {
+        assert_eq!(parse("X+1").unwrap(), var("X") + 1);
+        assert_eq!(parse("X-1").unwrap(), var("X") - 1);
+        assert_eq!(parse("2*X").unwrap(), 2 * var("X"));
+        assert_eq!(parse("X/2.5").unwrap(), var("X") / 2.5);
+        assert_eq!(parse("X%2").unwrap(), var("X") % 2);
+        assert_eq!(parse("-(X)").unwrap(), -var("X"));
+        // Equality is structural, so operand order matters
+        assert_ne!(parse("X+1").unwrap(), 1 + var("X"));
+    }
+
+    #[test]
+    fn can_build_powers() {
+        assert_eq!(parse("(2*X)^3").unwrap(), (2 * var("X")).pow(3));
+        assert_eq!(parse("X^(-2)").unwrap(), var("X").pow(-2));
+        assert_eq!(parse("2^X").unwrap(), pow(2, var("X")));
+    }
+
+    #[test]
+    fn can_build_functions() {
+        assert_eq!(parse("sin(X)").unwrap(), func("sin", [var("X")]));
+        assert_eq!(
+            parse("max(1, X^2)").unwrap(),
+            func("max", [num(1.0), var("X").pow(2)])
+        );
+    }
+
+    #[test]
+    fn can_build_like_parser() {
+        assert_eq!(parse("X^2 + 3*X").unwrap(), var("X").pow(2) + 3 * var("X"));
+        assert_eq!(
+            parse("sin(X)^2 + cos(X)^2").unwrap(),
+            func("sin", [var("X")]).pow(2) + func("cos", [var("X")]).pow(2)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{cancel, evaluate, gcd, quo, rem};
+    use crate::parser::{parse, Optimize};
+
+    fn setup_quo(dividend: &str, divisor: &str) -> String {
+        quo(&parse(dividend).unwrap(), &parse(divisor).unwrap(), "X")
+            .unwrap()
+            .to_string()
+    }
+
+    fn setup_rem(dividend: &str, divisor: &str) -> String {
+        rem(&parse(dividend).unwrap(), &parse(divisor).unwrap(), "X")
+            .unwrap()
+            .to_string()
+    }
+
+    fn setup_gcd(a: &str, b: &str) -> String {
+        gcd(&parse(a).unwrap(), &parse(b).unwrap())
+            .unwrap()
+            .to_string()
+    }
+
+    fn setup_cancel(expression: &str) -> String {
+        match cancel(&parse(expression).unwrap()) {
+            Ok(cancelled) => cancelled.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_evaluate(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_divide_polynomials() {
+        assert_eq!("(1X^(1)+1)", setup_quo("X^2 + 1", "X - 1"));
+        assert_eq!("2", setup_rem("X^2 + 1", "X - 1"));
+        assert_eq!("(0.5X^(1)-0.25)", setup_quo("X^2 + 1", "2X + 1"));
+        assert_eq!("1.25", setup_rem("X^2 + 1", "2X + 1"));
+        assert_eq!("0", setup_quo("X + 1", "X^2"));
+        assert_eq!("(1X^(1)+1)", setup_rem("X + 1", "X^2"));
+    }
+
+    #[test]
+    fn can_divide_with_other_variables() {
+        assert_eq!("(1X^(1)+1Y^(1))", setup_quo("X^2 - Y^2", "X - Y"));
+        assert_eq!("0", setup_rem("X^2 - Y^2", "X - Y"));
+        assert_eq!(
+            "Polynomial error: division by zero",
+            rem(&parse("X").unwrap(), &parse("0").unwrap(), "X")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn can_find_gcd() {
+        assert_eq!("(1X^(1)+1)", setup_gcd("X^2 - 1", "X^2 + 2X + 1"));
+        assert_eq!("(2X^(1)+2)", setup_gcd("2X + 2", "4X + 4"));
+        assert_eq!("1", setup_gcd("X^2 + 1", "X - 1"));
+        assert_eq!("(1X^(1)-1)", setup_gcd("X - 1", "0"));
+        assert_eq!("6", setup_gcd("12", "18"));
+        assert_eq!(
+            "(1X^(1)+1Y^(1))",
+            setup_gcd("X^2 - Y^2", "X^2 + 2X*Y + Y^2")
+        );
+        assert_eq!(
+            "((1X^(1)+1Y^(1))+1)",
+            setup_gcd("(X + Y + 1)*(X + 2)", "(X + Y + 1)*(X - Y)")
+        );
+    }
+
+    #[test]
+    fn can_cancel_fractions() {
+        assert_eq!("(1X^(1)+1), X≠1", setup_cancel("(X^2-1)/(X-1)"));
+        assert_eq!("(1/(1X^(1)-1)), X≠-1", setup_cancel("(X+1)/(X^2-1)"));
+        assert_eq!("(1/(1X^(1)-1))", setup_cancel("(X-1)/(X-1)^2"));
+        assert_eq!("(0.5X^(1)/(1X^(1)+1))", setup_cancel("2X/(4X+4)"));
+        assert_eq!("1, X≠-(i), X≠i", setup_cancel("(X^2+1)/(X^2+1)"));
+        assert_eq!(
+            "(1X^(1)+1Y^(1)), (1X^(1)-1Y^(1))≠0",
+            setup_cancel("(X^2-Y^2)/(X-Y)")
+        );
+        assert_eq!(
+            "Polynomial error: 'sin(1X^(1))' is not a polynomial",
+            setup_cancel("sin(X)/X")
+        );
+    }
+
+    #[test]
+    fn can_cancel_sums_of_fractions() {
+        assert_eq!(
+            "((1X^(1)+1)/1X^(1)), X≠-1",
+            setup_cancel("1 + 1/X + X/(X+1) - X/(X+1)")
+        );
+    }
+
+    #[test]
+    fn can_evaluate_division_functions() {
+        assert_eq!("(1X^(1)+1)", setup_evaluate("quo(X^2 - 1, X - 1)"));
+        assert_eq!("1", setup_evaluate("rem(Y^2, Y - 1)"));
+        assert_eq!("(1X^(1)+1)", setup_evaluate("cancel((X^2-1)/(X-1))"));
+        assert_eq!("(1X^(1)-1)", setup_evaluate("gcd(X^2 - 1, X^2 - 2X + 1)"));
+        assert_eq!(
+            "Syntax error: 'quo' needs a variable when there are several",
+            setup_evaluate("quo(X^2, Y)")
+        );
+        assert_eq!("1Y^(1)", setup_evaluate("quo(X*Y, X, X)"));
+    }
+
+    #[test]
+    fn can_keep_exclusions_when_optimizing() {
+        // Only cancel records that X≠1, so the optimizer leaves it alone
+        assert_eq!(
+            "((1X^(2)-1)/(1X^(1)-1))",
+            parse("(X^2-1)/(X-1)")
+                .unwrap()
+                .optimize_expression()
+                .to_string()
+        );
+        assert_eq!("1", parse("X/X").unwrap().optimize_expression().to_string());
+    }
+}
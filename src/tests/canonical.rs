@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod test {
+    use crate::parser::{parse, parse_equation};
+
+    fn setup(expression: &str) -> String {
+        parse(expression).unwrap().canonicalize().to_string()
+    }
+
+    fn assert_same(a: &str, b: &str) {
+        let (a, b) = (parse(a).unwrap(), parse(b).unwrap());
+        assert!(a.canonical_eq(&b));
+        assert_eq!(a.canonicalize(), b.canonicalize());
+        // `==` compares the exact shape
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn can_flatten_sums() {
+        assert_eq!("((1+2)+3)", setup("1+(2+3)"));
+        assert_eq!("((1-2)+3)", setup("3-(2-1)"));
+    }
+
+    #[test]
+    fn can_flatten_products() {
+        assert_eq!("((2*3)/(4*5))", setup("2/(4*5)*3"));
+        assert_eq!("-((2*3))", setup("(-2)*3"));
+    }
+
+    #[test]
+    fn can_normalize_numbers() {
+        assert_eq!("0", setup("-0"));
+        assert_eq!("-2", setup("-(2)"));
+        assert_eq!("0", setup("0X^3"));
+        assert_eq!("5", setup("5X^0"));
+        assert_eq!("-3X^(1)", setup("-(3X)"));
+    }
+
+    #[test]
+    fn can_compare_commutative_forms() {
+        assert_same("X*2", "2*X");
+        assert_same("A+B", "B+A");
+        assert_same("(A+B)*(C+D)", "(D+C)*(B+A)");
+        assert_same("A-B+C", "C-B+A");
+        assert_same("-(A*B)", "-(A)*B");
+        assert_same("-(A+B)", "-(A)-B");
+    }
+
+    #[test]
+    fn can_compare_equations() {
+        assert!(parse_equation("X=2")
+            .unwrap()
+            .canonical_eq(&parse_equation("2=X").unwrap()));
+    }
+
+    #[test]
+    fn can_compare_associative_forms() {
+        assert_same("(A+B)+C", "A+(B+C)");
+        assert_same("(A*B)*C", "A*(B*C)");
+        assert_same("A/B/C", "A/(B*C)");
+    }
+
+    #[test]
+    fn can_distinguish_different_expressions() {
+        let distinct = |a: &str, b: &str| !parse(a).unwrap().canonical_eq(&parse(b).unwrap());
+        assert!(distinct("A-B", "B-A"));
+        assert!(distinct("A/B", "B/A"));
+        assert!(distinct("A^B", "B^A"));
+        assert!(distinct("2+1", "3"));
+    }
+
+    #[test]
+    fn can_order_expressions() {
+        let mut expressions: Vec<_> = ["sin(X)", "X", "pi", "2", "X+1"]
+            .iter()
+            .map(|e| parse(e).unwrap())
+            .collect();
+        expressions.sort_by(|a, b| a.canonical_cmp(b));
+
+        let expressions: Vec<_> = expressions.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec!["2", "pi", "1X^(1)", "sin(1X^(1))", "(1X^(1)+1)"],
+            expressions
+        );
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test {
+    use crate::dag::{ExprPool, Node};
+    use crate::parser::parse;
+
+    fn setup(expression: &str) -> (ExprPool, crate::dag::ExprId) {
+        let mut pool = ExprPool::new();
+        let id = pool.intern(&parse(expression).unwrap());
+        (pool, id)
+    }
+
+    #[test]
+    fn can_roundtrip_expressions() {
+        let expression = "sin(2X^3 + pi) * max(1, -(4)) % 3";
+        let (pool, id) = setup(expression);
+        assert_eq!(parse(expression).unwrap(), pool.to_expr(id));
+    }
+
+    #[test]
+    fn can_share_identical_subterms() {
+        // (X+1), X, 1 and the product are the only distinct nodes
+        let (pool, id) = setup("(X+1)*(X+1)");
+        assert_eq!(4, pool.len());
+
+        match pool.get(id) {
+            Node::BinOp { lhs, rhs, .. } => assert_eq!(lhs, rhs),
+            node => panic!("expected a product, found {node:?}"),
+        }
+    }
+
+    #[test]
+    fn can_compare_by_id() {
+        let mut pool = ExprPool::new();
+        let a = pool.intern(&parse("3*(X+sin(4))").unwrap());
+        let b = pool.intern(&parse("3*(X+sin(4))").unwrap());
+        let c = pool.intern(&parse("3*(X+sin(5))").unwrap());
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn can_distinguish_numbers() {
+        let mut pool = ExprPool::new();
+        let a = pool.intern(&parse("0.1+0.2").unwrap());
+        let b = pool.intern(&parse("0.2+0.1").unwrap());
+
+        assert_ne!(a, b);
+        assert_eq!(4, pool.len());
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::algebra::{diff, evaluate};
+    use crate::numeric_evaluator::evaluate_with;
+    use crate::parser::{func, parse, parse_equation, var};
+
+    fn setup(expression: &str) -> String {
+        match diff(&parse(expression).unwrap(), "X") {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_evaluate(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_differentiate_polynomials() {
+        assert_eq!("(6X^(1)+2)", setup("3X^2 + 2X + 1"));
+        assert_eq!("3X^(2)", setup("X^3"));
+        assert_eq!("1Y^(1)", setup("X*Y"));
+        assert_eq!("0", setup("Y^2"));
+        assert_eq!("pi", setup("pi*X"));
+    }
+
+    #[test]
+    fn can_apply_product_and_quotient_rules() {
+        assert_eq!(
+            "((2X^(1)*sin(1X^(1)))+(1X^(2)*(cos(1X^(1))*(pi/180))))",
+            setup("X^2*sin(X)")
+        );
+        assert_eq!(
+            "(((1X^(1)-1)-(1X^(1)+1))/((1X^(1)-1)^2))",
+            setup("(X+1)/(X-1)")
+        );
+        assert_eq!("(2X^(1)/1X^(2))", setup("ln(X^2)"));
+    }
+
+    #[test]
+    fn can_differentiate_exponentials_and_logarithms() {
+        assert_eq!("(e^1X^(1))", setup("exp(X)"));
+        assert_eq!("(1/(1X^(1)*ln(10)))", setup("log(X)"));
+        assert_eq!("(1/(1X^(1)*ln(2)))", setup("log2(X)"));
+        assert_eq!("(2X^(1)/(1X^(2)*ln(10)))", setup("log10(X^2)"));
+        assert_eq!("(1/(1X^(1)*ln(2)))", setup("log(2, X)"));
+        assert_eq!("-((ln(8)/(1X^(1)*(ln(1X^(1))^2))))", setup("log(X, 8)"));
+    }
+
+    #[test]
+    fn can_apply_chain_rule() {
+        assert_eq!("(-((sin(2X^(1))*(pi/180)))*2)", setup("cos(2X)"));
+        assert_eq!(
+            "((2*sin(1X^(1)))*(cos(1X^(1))*(pi/180)))",
+            setup("sin(X)^2")
+        );
+        assert_eq!("((pi/180)/(cos(1X^(1))^2))", setup("tan(X)"));
+        assert_eq!("(1/(2*sqrt(1X^(1))))", setup("sqrt(X)"));
+    }
+
+    #[test]
+    fn can_differentiate_symbolic_exponents() {
+        assert_eq!("((2^1X^(1))*ln(2))", setup("2^X"));
+        assert_eq!("(e^1X^(1))", setup("e^X"));
+        assert_eq!("((1X^(1)^1X^(1))*(ln(1X^(1))+1))", setup("X^X"));
+        assert_eq!("(3*1X^(2))", setup("pow(X, 3)"));
+    }
+
+    #[test]
+    fn can_differentiate_piecewise_functions() {
+        assert_eq!("0", setup("floor(X)"));
+        assert_eq!("0", setup("round(2X)"));
+        assert_eq!("1", setup("fract(X)"));
+        assert_eq!("sign(1X^(1))", setup("abs(X)"));
+        assert_eq!("((1-sign((1X^(1)-2)))/2)", setup("min(X, 2)"));
+        assert_eq!(
+            "(((2X^(1)+1)+(sign((1X^(2)-1X^(1)))*(2X^(1)-1)))/2)",
+            setup("max(X^2, X)")
+        );
+        assert_eq!(
+            "Math error: the derivative of 'foo' is unknown",
+            setup("foo(X)")
+        );
+    }
+
+    #[test]
+    fn can_differentiate_modulo() {
+        let derivative = diff(&parse("X % 5").unwrap(), "X").unwrap();
+        let slope = |x: f64| {
+            let variables = HashMap::from([("X".to_string(), x)]);
+            evaluate_with(&derivative, &variables).unwrap()
+        };
+        // -7.5 % 5 = |-7.5 + 5| falls as X grows
+        assert_eq!(-1.0, slope(-7.5));
+        assert_eq!(1.0, slope(7.5));
+    }
+
+    #[test]
+    fn can_differentiate_equations() {
+        match diff(&parse_equation("X^2 = 2X").unwrap(), "X") {
+            Ok(expr) => assert_eq!("(2X^(1)=2)", expr.to_string()),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[test]
+    fn can_parse_derivative_syntax() {
+        assert_eq!(
+            func("diff", [var("X").pow(3), var("X")]),
+            parse("d/dX(X^3)").unwrap()
+        );
+        assert_eq!("3X^(2)", setup_evaluate("d/dX(X^3)"));
+        assert_eq!("(1X^(1)*2Y^(1))", setup_evaluate("d/dY(X*Y^2)"));
+        assert_eq!("6X^(1)", setup_evaluate("diff(d/dX(X^3), X)"));
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test {
+    use crate::optimizer::{
+        simplify, CostFunction, EGraph, ENode, SaturationConfig, Strategy, Symbol,
+    };
+    use crate::parser::{parse, parse_pattern};
+
+    fn setup(expression: &str) -> String {
+        let strategy = Strategy::Saturation(SaturationConfig::default());
+        simplify(parse(expression).unwrap(), &strategy).to_string()
+    }
+
+    #[test]
+    fn can_share_equivalent_terms() {
+        let mut graph = EGraph::new();
+        let a = graph.add_expr(&parse("X+1").unwrap()).unwrap();
+        let b = graph.add_expr(&parse("(X+1)*(X+1)").unwrap()).unwrap();
+        assert_ne!(graph.find(a), graph.find(b));
+        assert_eq!(4, graph.node_count());
+
+        let c = graph.add_expr(&parse("1+X").unwrap()).unwrap();
+        graph.union(a, c);
+        assert_eq!(graph.find(a), graph.find(c));
+    }
+
+    #[test]
+    fn can_simplify_like_greedy() {
+        assert_eq!("25", setup("-(-25)"));
+        assert_eq!("0", setup("(53*88*(52-52))"));
+        assert_eq!("8X^(8)", setup("2X^8+6X^8"));
+    }
+
+    #[test]
+    fn can_escape_local_optima() {
+        // Greedy rewriting never sees that the A terms cancel
+        assert_eq!(
+            "((1A^(1)+1B^(1))-1A^(1))",
+            simplify(parse("A+B-A").unwrap(), &Strategy::Greedy).to_string()
+        );
+        assert_eq!("1B^(1)", setup("A+B-A"));
+    }
+
+    #[test]
+    fn can_factor_common_terms() {
+        assert_eq!("5X^(1)", setup("2*X + 3*X"));
+        assert_eq!("0", setup("X*Y - Y*X"));
+    }
+
+    #[test]
+    fn can_fold_numbers() {
+        assert_eq!("10", setup("1+2+3+4"));
+        assert_eq!("(sin(1X^(1))*6)", setup("2*sin(X)*3"));
+    }
+
+    #[test]
+    fn can_use_custom_cost() {
+        // Penalises powers so that the product form is extracted instead
+        struct NoPowers;
+        impl CostFunction for NoPowers {
+            fn cost(&self, node: &ENode, children: &[f64]) -> f64 {
+                let own = match node.symbol {
+                    Symbol::BinOp(crate::parser::Op::Power) => 100.0,
+                    _ => 1.0,
+                };
+                own + children.iter().sum::<f64>()
+            }
+        }
+
+        let config = SaturationConfig {
+            cost: Box::new(NoPowers),
+            ..SaturationConfig::default()
+        };
+        let expr = parse("sin(X)*sin(X)").unwrap();
+        assert_eq!(
+            "(sin(1X^(1))^2)",
+            simplify(expr.clone(), &Strategy::Greedy).to_string()
+        );
+        assert_eq!(
+            "(sin(1X^(1))*sin(1X^(1)))",
+            simplify(expr, &Strategy::Saturation(config)).to_string()
+        );
+    }
+
+    #[test]
+    fn can_respect_budget() {
+        let config = SaturationConfig {
+            node_limit: 50,
+            ..SaturationConfig::default()
+        };
+        let expr = parse("(A+B+C+D+E)*(F+G+H+I+J)").unwrap();
+        let mut graph = EGraph::new();
+        graph.add_expr(&expr).unwrap();
+        graph.saturate(&crate::optimizer::algebraic_rules(), &config);
+        assert!(graph.node_count() < 1000);
+    }
+
+    #[test]
+    fn can_reject_wildcards() {
+        let mut graph = EGraph::new();
+        let pattern = parse_pattern("?a + 1").unwrap();
+        assert_eq!(
+            "Rule error: the wildcard '?a' can't be added to an e-graph",
+            graph.add_expr(&pattern).unwrap_err().to_string()
+        );
+
+        let strategy = Strategy::Saturation(SaturationConfig::default());
+        assert_eq!("(?a+1)", simplify(pattern, &strategy).to_string());
+    }
+}
@@ -79,12 +79,17 @@ mod test {
         assert_eq!(0.5, evaluate("sin(30)").unwrap());
         assert_eq!(1.0, evaluate("tan(45)").unwrap());
         assert_eq!(1.0, evaluate("tan(45)").unwrap());
+        assert_eq!(45.0, evaluate("atan(1)").unwrap());
         assert_eq!(4.0, evaluate("floor(4.5)").unwrap());
         assert_eq!(5.0, evaluate("ceil(4.5)").unwrap());
         assert_eq!(5.0, evaluate("round(4.6)").unwrap());
         assert_eq!(1.0, evaluate("trunc(1.128)").unwrap());
         assert_eq!(0.128, evaluate("fract(1.128)").unwrap());
         assert_eq!(2.0, evaluate("sqrt(4)").unwrap());
+        assert_eq!(2.5, evaluate("abs(0-2.5)").unwrap());
+        assert_eq!(-1.0, evaluate("sign(0-2.5)").unwrap());
+        assert_eq!(0.0, evaluate("sign(0)").unwrap());
+        assert_eq!(1.0, evaluate("ln(e)").unwrap());
         assert_eq!(16.0, evaluate("pow(4, 2)").unwrap());
         assert_eq!(2.0, evaluate("min(4, 2)").unwrap());
         assert_eq!(4.0, evaluate("max(4, 2)").unwrap());
@@ -97,6 +102,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn can_reject_logarithms_outside_their_domain() {
+        assert_eq!(3.0, evaluate("log(2, 8)").unwrap());
+        assert_eq!(
+            "Math error: 'log' is not defined for base 1",
+            evaluate("log(1, 5)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Math error: 'log' is not defined for base -2",
+            evaluate("log(0-2, 4)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Math error: 'ln' is not defined for -1",
+            evaluate("ln(0-1)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Math error: 'log10' is not defined for 0",
+            evaluate("log10(0)").unwrap_err().to_string()
+        );
+        assert!(evaluate("log2(0-4)").is_err());
+        assert!(evaluate("log(0)").is_err());
+    }
+
+    #[test]
+    fn can_reject_wrong_arity() {
+        assert_eq!(
+            "Syntax error: 'sin' takes 1 arguments but 2 were given",
+            evaluate("sin(1, 2)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Syntax error: 'max' takes 2 arguments but 1 were given",
+            evaluate("max(1)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Syntax error: 'sin' takes 1 arguments but 2 were given",
+            evaluate("nsolve(sin(X, 1) = 0, X, 1)")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
     #[test]
     fn can_parse_constants() {
         assert_eq!(3.141592653589793, evaluate("pi").unwrap());
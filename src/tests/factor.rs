@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{evaluate, factor};
+    use crate::parser::parse;
+    use crate::polynomial::Polynomial;
+
+    fn setup(expression: &str) -> String {
+        match factor(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_polynomial(expression: &str) -> String {
+        let polynomial = Polynomial::from_expr(&parse(expression).unwrap()).unwrap();
+        let factorization = polynomial.factor();
+        assert_eq!(polynomial, factorization.expand());
+        factorization.to_string()
+    }
+
+    #[test]
+    fn can_factor_quadratics() {
+        assert_eq!("((1X^(1)-2)*(1X^(1)-3))", setup("X^2 - 5X + 6"));
+        assert_eq!("((1X^(1)+1)*(1X^(1)-1))", setup("X^2 - 1"));
+        assert_eq!("((2X^(1)-1)*(1X^(1)+1))", setup("2X^2 + X - 1"));
+        assert_eq!("(1X^(2)+1)", setup("X^2 + 1"));
+    }
+
+    #[test]
+    fn can_pull_out_content() {
+        assert_eq!("(2*(1X^(1)+2))", setup("2X + 4"));
+        assert_eq!("-((1X^(1)-6))", setup("6 - X"));
+        assert_eq!("(0.5*(1X^(1)+1))", setup("X/2 + 1/2"));
+        assert_eq!("(3*(1X^(2)*(1X^(1)-1)))", setup("3X^3 - 3X^2"));
+        assert_eq!("12", setup("12"));
+    }
+
+    #[test]
+    fn can_find_repeated_factors() {
+        assert_eq!("((1X^(1)+1)^2)", setup("X^2 + 2X + 1"));
+        assert_eq!("(X + 1)^3(X - 2)", setup_polynomial("(X+1)^3*(X-2)"));
+        assert_eq!("(X - 1)(X^2 + 1)^2", setup_polynomial("(X^2+1)^2*(X-1)"));
+    }
+
+    #[test]
+    fn can_factor_higher_degrees() {
+        assert_eq!(
+            "(X + 1)(X - 1)(X^2 + X + 1)(X^2 - X + 1)",
+            setup_polynomial("X^6 - 1")
+        );
+        assert_eq!("(X^4 + 1)", setup_polynomial("X^4 + 1"));
+        // Splits modulo every prime, so only recombination rules it out
+        assert_eq!("(X^4 - 10*X^2 + 1)", setup_polynomial("X^4 - 10X^2 + 1"));
+        assert_eq!(
+            "(X^2 + X + 1)(X^3 - X + 2)",
+            setup_polynomial("(X^2 + X + 1)*(X^3 - X + 2)")
+        );
+        assert_eq!(
+            "(3*X - 2)(5*X^2 + 7)(X^3 - 4*X + 11)",
+            setup_polynomial("(3X - 2)*(5X^2 + 7)*(X^3 - 4X + 11)")
+        );
+    }
+
+    #[test]
+    fn can_factor_multivariate() {
+        assert_eq!("(X + Y)(X - Y)", setup_polynomial("X^2 - Y^2"));
+        assert_eq!("(X)(Y)(X + Y)", setup_polynomial("X^2*Y + X*Y^2"));
+        assert_eq!("(X - Y)^2", setup_polynomial("X^2 - 2X*Y + Y^2"));
+        assert_eq!("(X^2 + Y + 1)", setup_polynomial("X^2 + Y + 1"));
+    }
+
+    #[test]
+    fn can_evaluate_factor() {
+        match evaluate(&parse("factor(X^2 - 4)").unwrap()) {
+            Ok(expr) => assert_eq!("((1X^(1)+2)*(1X^(1)-2))", expr.to_string()),
+            Err(err) => panic!("{err}"),
+        }
+        assert_eq!(
+            "Polynomial error: 'sin(1X^(1))' is not a polynomial",
+            setup("sin(X)")
+        );
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod test {
+    use crate::parser::{parse, Optimize};
+    use crate::solver::{solve, solve_inequality};
+
+    fn setup(inequality: &str) -> String {
+        match solve(&parse(inequality).unwrap(), "X") {
+            Ok(solution) => solution.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_solve_polynomial_inequalities() {
+        assert_eq!("(-2, 2)", setup("X^2 - 4 < 0"));
+        assert_eq!("(-inf, -2] ∪ [2, inf)", setup("X^2 >= 4"));
+        assert_eq!("(-inf, 1)", setup("2X + 3 < 5"));
+        assert_eq!("[-1, inf)", setup("1 - X <= 2"));
+        assert_eq!("(-inf, 1) ∪ (2, 3)", setup("(X - 1)*(X - 2)*(X - 3) < 0"));
+    }
+
+    #[test]
+    fn can_solve_rational_inequalities() {
+        assert_eq!("(-inf, -1) ∪ [1, inf)", setup("(X - 1)/(X + 1) >= 0"));
+        assert_eq!("(0, inf)", setup("1/X > 0"));
+        // The hole at 1 stays excluded
+        assert_eq!("(-inf, 1)", setup("(X^2 - 1)/(X - 1) <= 2"));
+    }
+
+    #[test]
+    fn can_handle_repeated_roots() {
+        assert_eq!("{1}", setup("(X - 1)^2 <= 0"));
+        assert_eq!("no solution", setup("(X - 1)^2 < 0"));
+        assert_eq!("(-inf, 1) ∪ (1, inf)", setup("(X - 1)^2 > 0"));
+        assert_eq!("(-inf, 1) ∪ (1, inf)", setup("X != 1"));
+    }
+
+    #[test]
+    fn can_solve_inequalities_without_real_roots() {
+        assert_eq!("(-inf, inf)", setup("X^2 + 1 > 0"));
+        assert_eq!("no solution", setup("X^2 + 1 < 0"));
+        assert_eq!("(-inf, inf)", setup("X - X <= 0"));
+        assert_eq!("no solution", setup("X + 1 < X"));
+    }
+
+    #[test]
+    fn can_keep_irrational_endpoints_exact() {
+        assert_eq!("(-(sqrt(2)), sqrt(2))", setup("X^2 < 2"));
+    }
+
+    #[test]
+    fn can_test_membership() {
+        let intervals = solve_inequality(&parse("X^2 >= 4").unwrap(), "X").unwrap();
+        assert!(intervals.contains(-2.0));
+        assert!(intervals.contains(5.0));
+        assert!(!intervals.contains(0.0));
+        assert_eq!(2, intervals.intervals().len());
+    }
+
+    #[test]
+    fn can_reject_invalid_inequalities() {
+        assert_eq!(
+            "Solver error: the inequality for 'X' also depends on 'Y'",
+            setup("X < Y")
+        );
+        assert_eq!(
+            "Solver error: expected an inequality such as 'X < 1'",
+            solve_inequality(&parse("X = 1").unwrap(), "X")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Solver error: expected an equation",
+            parse("X < 1")
+                .unwrap()
+                .optimize_equation("X")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
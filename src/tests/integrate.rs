@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{evaluate, integrate};
+    use crate::numeric_evaluator::quadrature;
+    use crate::parser::parse;
+
+    fn setup(expression: &str) -> String {
+        match integrate(&parse(expression).unwrap(), "X") {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_evaluate(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_number(expression: &str) -> f64 {
+        setup_evaluate(expression).parse().unwrap()
+    }
+
+    #[test]
+    fn can_integrate_polynomials() {
+        assert_eq!("((1X^(3)+1X^(2))+1X^(1))", setup("3X^2 + 2X + 1"));
+        assert_eq!("(5*1X^(1))", setup("5"));
+        assert_eq!("(1Y^(1)*0.5X^(2))", setup("Y*X"));
+        assert_eq!("ln(abs(1X^(1)))", setup("1/X"));
+        assert_eq!("(((1X^(1)+1)^4)/4)", setup("(X+1)^3"));
+    }
+
+    #[test]
+    fn can_integrate_standard_functions() {
+        assert_eq!("-((cos(1X^(1))*(180/pi)))", setup("sin(X)"));
+        assert_eq!("((sin((2X^(1)+1))*(180/pi))/2)", setup("cos(2X+1)"));
+        assert_eq!("(e^1X^(1))", setup("e^X"));
+        assert_eq!("((2^1X^(1))/ln(2))", setup("2^X"));
+        assert_eq!("((2*(sqrt(1X^(1))^3))/3)", setup("sqrt(X)"));
+        assert_eq!("((1X^(1)*ln(1X^(1)))-1X^(1))", setup("ln(X)"));
+        assert_eq!("((e^2X^(1))/2)", setup("exp(2X)"));
+        assert_eq!("((1X^(1)*log(1X^(1)))-(1X^(1)/ln(10)))", setup("log(X)"));
+        assert_eq!("((1X^(1)*log2(1X^(1)))-(1X^(1)/ln(2)))", setup("log2(X)"));
+        assert_eq!(
+            "((1X^(1)*log(3, 1X^(1)))-(1X^(1)/ln(3)))",
+            setup("log(3, X)")
+        );
+    }
+
+    #[test]
+    fn can_substitute() {
+        assert_eq!("(sin(1X^(2))*(180/pi))", setup("2X*cos(X^2)"));
+        assert_eq!("(0.5*(e^1X^(2)))", setup("X*e^(X^2)"));
+        assert_eq!(
+            "((1/(pi*(1/180)))*ln(abs(sin(1X^(1)))))",
+            setup("cos(X)/sin(X)")
+        );
+    }
+
+    #[test]
+    fn can_integrate_rational_functions() {
+        assert_eq!(
+            "(ln(abs((1X^(1)+1)))+ln(abs((1X^(1)+2))))",
+            setup("(2X+3)/(X^2+3X+2)")
+        );
+        assert_eq!("(0.5*ln((1X^(2)+1)))", setup("X/(X^2+1)"));
+        assert_eq!("((2*atan((2X^(1)/2)))/(2*(180/pi)))", setup("1/(X^2+1)"));
+        assert_eq!(
+            "(ln(abs(((2X^(1)-sqrt(8))/(2X^(1)+sqrt(8)))))/sqrt(8))",
+            setup("1/(X^2-2)")
+        );
+    }
+
+    #[test]
+    fn can_integrate_by_parts() {
+        assert_eq!("((1X^(1)*(e^1X^(1)))-(e^1X^(1)))", setup("X*e^X"));
+        assert_eq!("((ln(1X^(1))*0.5X^(2))-0.25X^(2))", setup("X*ln(X)"));
+        assert_eq!(
+            "((1X^(2)*(e^1X^(1)))-((2X^(1)*(e^1X^(1)))-(2*(e^1X^(1)))))",
+            setup("X^2*e^X")
+        );
+    }
+
+    #[test]
+    fn fails_without_antiderivative() {
+        assert_eq!(
+            "Math error: no antiderivative of 'sin(1X^(2))' was found",
+            setup("sin(X^2)")
+        );
+        assert_eq!(
+            "Syntax error: 'integrate' needs a variable when there are several",
+            setup_evaluate("integrate(X*Y)")
+        );
+    }
+
+    #[test]
+    fn can_integrate_definite() {
+        assert_eq!("9", setup_evaluate("integrate(X^2, X, 0, 3)"));
+        assert_eq!(
+            "((0.5*1A^(2))-(0.5*(0^2)))",
+            setup_evaluate("integrate(X, X, 0, A)")
+        );
+        let quarter = setup_number("integrate(1/(X^2+1), X, 0, 1)");
+        assert!((quarter - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+        let tan = setup_number("integrate(1/(X^2+1)^2, X, 0, 1)");
+        assert!((tan - (std::f64::consts::FRAC_PI_8 + 0.25)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn falls_back_to_quadrature() {
+        let gauss = setup_number("integrate(e^(-(X^2)), X, 0, 1)");
+        assert!((gauss - 0.746_824_132_812_427).abs() < 1e-9);
+        assert_eq!(
+            "Math error: the bound '1A^(1)' is not a number",
+            setup_evaluate("integrate(sin(X^2), X, 0, A)")
+        );
+        assert!(quadrature(|x| Ok(1.0 / x), 0.0, 1.0, 1e-10).is_err());
+    }
+
+    #[test]
+    fn can_detect_poles_in_the_interval() {
+        // The antiderivative -1/X alone would give -2
+        assert_eq!(
+            "Math error: the integral doesn't converge",
+            setup_evaluate("integrate(1/X^2, X, -1, 1)")
+        );
+        assert_eq!(
+            "Math error: the integral doesn't converge",
+            setup_evaluate("integrate(1/(X-2), X, 0, 3)")
+        );
+        // ln|X| also holds left of the pole
+        assert_eq!(
+            "-(0.693147180559945)",
+            setup_evaluate("integrate(1/X, X, -2, -1)")
+        );
+        assert_eq!("0.5", setup_evaluate("integrate(1/X^2, X, 1, 2)"));
+        // The poles of tan and ln|cos| aren't roots of a polynomial
+        assert_eq!(
+            "Math error: the integral doesn't converge",
+            setup_evaluate("integrate(tan(X), X, 0, 180)")
+        );
+        assert_eq!(
+            "Math error: the integral doesn't converge",
+            setup_evaluate("integrate(tan(X), X, 0, 100)")
+        );
+        assert!((setup_number("integrate(tan(X), X, 0, 45)") - 19.857204013738).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::{evaluate, limit, Direction, Limit};
+    use crate::parser::{parse, var};
+
+    fn setup(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_resolve_indeterminate_forms() {
+        assert_eq!("((1/180)*pi)", setup("limit(sin(X)/X, X, 0)"));
+        assert_eq!("((1/64800)*(pi^2))", setup("limit((1 - cos(X))/X^2, X, 0)"));
+        assert_eq!("2", setup("limit((X^2 - 1)/(X - 1), X, 1)"));
+        assert_eq!("1", setup("limit((e^X - 1)/X, X, 0)"));
+        assert_eq!("0", setup("limit(X*ln(X), X, 0, 1)"));
+        assert_eq!("1", setup("limit(X^X, X, 0, 1)"));
+        assert_eq!("e", setup("limit((1 + X)^(1/X), X, 0)"));
+    }
+
+    #[test]
+    fn can_take_limits_at_infinity() {
+        assert_eq!("2", setup("limit((2X^2 + 1)/(X^2 + 3), X, inf)"));
+        assert_eq!("inf", setup("limit(e^X/X^3, X, inf)"));
+        assert_eq!("0", setup("limit(ln(X)/X, X, inf)"));
+        assert_eq!("e", setup("limit((1 + 1/X)^X, X, inf)"));
+        assert_eq!("90", setup("limit(atan(X), X, inf)"));
+        assert_eq!("-(90)", setup("limit(atan(X), X, -(inf))"));
+        assert_eq!("0", setup("limit(sin(X)/X, X, inf)"));
+        assert_eq!("0.5", setup("limit(sqrt(X^2 + X) - X, X, inf)"));
+    }
+
+    #[test]
+    fn can_take_one_sided_limits() {
+        assert_eq!("inf", setup("limit(1/X, X, 0, 1)"));
+        assert_eq!("-(inf)", setup("limit(1/X, X, 0, -1)"));
+        assert_eq!("inf", setup("limit(1/X^2, X, 0)"));
+        assert_eq!("1", setup("limit(abs(X)/X, X, 0, 1)"));
+        assert_eq!("-(1)", setup("limit(abs(X)/X, X, 0, -1)"));
+        assert_eq!("0", setup("limit(floor(X), X, 1, -1)"));
+        assert_eq!("1", setup("limit(floor(X), X, 1, 1)"));
+    }
+
+    #[test]
+    fn limit_does_not_exist() {
+        let limit = |expression: &str| {
+            let expr = parse(expression).unwrap();
+            limit(&expr, "X", &parse("0").unwrap(), Direction::Both).unwrap()
+        };
+        assert_eq!(Limit::DoesNotExist, limit("1/X"));
+        assert_eq!(Limit::DoesNotExist, limit("abs(X)/X"));
+        assert_eq!(Limit::DoesNotExist, limit("sin(1/X)"));
+        assert_eq!("0", limit("X*sin(1/X)").to_string());
+        assert_eq!(
+            "Math error: the limit of 'floor(1X^(1))' does not exist",
+            setup("limit(floor(X), X, 1)")
+        );
+        assert_eq!(
+            "Math error: the limit of 'sin(1X^(1))' does not exist",
+            setup("limit(sin(X), X, inf)")
+        );
+        assert_eq!(
+            Err("Math error: the limit of '(1/1X^(1))' does not exist".to_string()),
+            crate::limit("1/X", "X", "0", 0)
+        );
+        assert_eq!(Ok("inf".to_string()), crate::limit("1/X^2", "X", "0", 0));
+    }
+
+    #[test]
+    fn limit_checks_arguments() {
+        assert_eq!(
+            "Math error: a limit can't be taken at '1X^(1)', which depends on its variable",
+            setup("limit(X, X, X)")
+        );
+        assert_eq!(
+            "Math error: the direction of a limit must be 1 (from above), -1 (from below) or 0 (both sides), found '2'",
+            setup("limit(1/X, X, 0, 2)")
+        );
+        assert_eq!("inf", setup("limit(1/X^2, X, 0, 0)"));
+        assert!(limit(&var("X"), "X", &parse("Y").unwrap(), Direction::Above).is_ok());
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::evaluate;
+    use crate::numeric_evaluator;
+    use crate::parser::{parse, Optimize};
+
+    fn setup(expression: &str) -> String {
+        parse(expression).unwrap().optimize_expression().to_string()
+    }
+
+    fn setup_function(expression: &str) -> String {
+        evaluate(&parse(expression).unwrap()).unwrap().to_string()
+    }
+
+    fn setup_numeric(expression: &str) -> f64 {
+        numeric_evaluator::evaluate(expression).unwrap()
+    }
+
+    #[test]
+    fn can_evaluate_logarithms() {
+        assert_eq!(2.0, setup_numeric("log(100)"));
+        assert_eq!(3.0, setup_numeric("log(2, 8)"));
+        assert_eq!(10.0, setup_numeric("log2(1024)"));
+        assert_eq!(-3.0, setup_numeric("log10(0.001)"));
+        assert_eq!(1.0, setup_numeric("ln(e)"));
+        assert_eq!(2.095903274289385, setup_numeric("log(3, 10)"));
+    }
+
+    #[test]
+    fn can_evaluate_exponentials() {
+        assert_eq!(std::f64::consts::E, setup_numeric("exp(1)"));
+        assert_eq!(1.0, setup_numeric("exp(2)*exp(0-2)"));
+        assert_eq!(1.0, setup_numeric("exp(0)"));
+    }
+
+    #[test]
+    fn can_cancel_inverse_functions() {
+        assert_eq!("1X^(1)", setup("ln(e^X)"));
+        assert_eq!("1X^(1)", setup("e^ln(X)"));
+        assert_eq!("1X^(1)", setup("exp(ln(X))"));
+        assert_eq!("(1X^(1)+1)", setup("ln(exp(X+1))"));
+        assert_eq!("1Y^(1)", setup("10^log10(Y)"));
+        assert_eq!("1X^(1)", setup("log(2, 2^X)"));
+        // ln is undefined for negative numbers
+        assert_eq!("(e^ln(-(2)))", setup("e^ln(0-2)"));
+    }
+
+    #[test]
+    fn can_evaluate_exact_logarithms() {
+        assert_eq!("0", setup("ln(1)"));
+        // A symbolic base is assumed to be positive and other than 1
+        assert_eq!("1", setup("log(X, X)"));
+        assert_eq!("log(1, 1)", setup("log(1, 1)"));
+        assert_eq!("log(-(2), -(2))", setup("log(-2, -2)"));
+        assert_eq!("3", setup("log2(8)"));
+        assert_eq!("4", setup("log(3, 81)"));
+        assert_eq!("3", setup("log(10, 1000)"));
+        assert_eq!("ln(5)", setup("ln(5)"));
+        assert_eq!("log2(6)", setup("log2(6)"));
+    }
+
+    #[test]
+    fn can_change_base() {
+        assert_eq!("log(1Y^(1), 1X^(1))", setup("ln(X)/ln(Y)"));
+        assert_eq!("log2(1X^(1))", setup("ln(X)/ln(2)"));
+        assert_eq!("log(3, 1X^(1))", setup("log10(X)/log10(3)"));
+        assert_eq!("ln(1X^(1))", setup("log(e, X)"));
+    }
+
+    #[test]
+    fn can_expand_logarithms() {
+        assert_eq!(
+            "(ln(1X^(1))+ln(1Y^(1)))",
+            setup_function("expandlog(ln(X*Y))")
+        );
+        assert_eq!(
+            "(ln(1X^(1))-ln(1Y^(1)))",
+            setup_function("expandlog(ln(X/Y))")
+        );
+        assert_eq!(
+            "((2*ln(1X^(1)))+ln(1Y^(1)))",
+            setup_function("expandlog(ln(X^2*Y))")
+        );
+        assert_eq!(
+            "(ln(3)+(2*ln(1X^(1))))",
+            setup_function("expandlog(ln(3X^2))")
+        );
+        assert_eq!(
+            "(2+log10(1X^(1)))",
+            setup_function("expandlog(log10(100X))")
+        );
+        assert_eq!(
+            "(log2(1X^(1))+log2(1Y^(1)))",
+            setup_function("expandlog(log(2, X*Y))")
+        );
+        assert_eq!("(ln(1X^(1))/2)", setup_function("expandlog(ln(sqrt(X)))"));
+        // ln(a*b) = ln(a) + ln(b) needs positive factors
+        assert_eq!(
+            "ln((-(2)*-(3)))",
+            setup_function("expandlog(ln((-2)*(-3)))")
+        );
+        assert_eq!("ln((-(2)^2))", setup_function("expandlog(ln((-2)^2))"));
+        assert_eq!(
+            "((3*ln((1X^(1)+1)))-ln((1X^(1)-1)))",
+            setup_function("expandlog(ln((X+1)^3/(X-1)))")
+        );
+    }
+
+    #[test]
+    fn can_combine_logarithms() {
+        assert_eq!(
+            "ln((1X^(1)*1Y^(1)))",
+            setup_function("combinelog(ln(X) + ln(Y))")
+        );
+        assert_eq!(
+            "ln((1X^(2)*1Y^(1)))",
+            setup_function("combinelog(2*ln(X) + ln(Y))")
+        );
+        assert_eq!(
+            "log10(((1X^(1)*1Y^(1))/1Z^(1)))",
+            setup_function("combinelog(log10(X) + log10(Y) - log10(Z))")
+        );
+        assert_eq!(
+            "log2((1X^(1)*1Y^(1)))",
+            setup_function("combinelog(log(2, X) + log(2, Y))")
+        );
+        assert_eq!("log10(1X^(3))", setup_function("combinelog(3*log10(X))"));
+        // Different bases stay apart
+        assert_eq!(
+            "(ln(1X^(1))+log2(1Y^(1)))",
+            setup_function("combinelog(ln(X) + log2(Y))")
+        );
+    }
+
+    #[test]
+    fn combine_undoes_expand() {
+        assert_eq!(
+            "ln((1X^(2)*1Y^(1)))",
+            setup_function("combinelog(expandlog(ln(X^2*Y)))")
+        );
+    }
+}
@@ -1,4 +1,27 @@
-mod parser;
+mod algebra;
+mod apart;
+mod builder;
+mod cancel;
+mod canonical;
+mod dag;
+mod diff;
+mod egraph;
+mod evaluator;
+mod factor;
+mod inequality;
+mod integrate;
+mod limit;
+mod log;
+mod nintegrate;
+mod nsolve;
 mod optimizer;
+mod parser;
+mod polynomial;
+mod rational;
 mod round;
-mod evaluator;
\ No newline at end of file
+mod rules;
+mod series;
+mod solver;
+mod summation;
+mod system;
+mod trig;
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::f64::consts::PI;
+
+    use crate::numeric_evaluator::{evaluate, evaluate_with, nintegrate, quadrature};
+    use crate::parser::parse;
+
+    fn assert_close(expected: f64, expression: &str) {
+        let value = evaluate(expression).unwrap();
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "{expression} = {value}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn can_eval_bound_variables() {
+        let variables = HashMap::from([("X".to_string(), 2.0), ("Y".to_string(), 3.0)]);
+        let expr = parse("3X^2 + Y").unwrap();
+        assert_eq!(15.0, evaluate_with(&expr, &variables).unwrap());
+        assert!(evaluate_with(&parse("Z").unwrap(), &variables).is_err());
+    }
+
+    #[test]
+    fn can_integrate_finite_intervals() {
+        assert_close(9.0, "nintegrate(X^2, X, 0, 3)");
+        assert_close(-0.5, "nintegrate(X, X, 1, 0)");
+        assert_close(360.0 / PI, "nintegrate(sin(X), X, 0, 180)");
+        assert_close(0.125, "nintegrate(nintegrate(X*Y, Y, 0, X), X, 0, 1)");
+    }
+
+    #[test]
+    fn can_integrate_endpoint_singularities() {
+        assert_close(2.0, "nintegrate(1/sqrt(X), X, 0, 1)");
+        assert_close(-1.0, "nintegrate(ln(X), X, 0, 1)");
+    }
+
+    #[test]
+    fn can_integrate_infinite_bounds() {
+        assert_close(1.0, "nintegrate(1/X^2, X, 1, inf)");
+        assert_close(PI.sqrt(), "nintegrate(e^(-(X^2)), X, -(inf), inf)");
+        assert_close(PI, "nintegrate(1/(1+X^2), X, -(inf), inf)");
+        assert_close(1.0, "nintegrate(e^(0-X), X, 0, inf)");
+    }
+
+    #[test]
+    fn estimates_error() {
+        let result = nintegrate(&parse("e^X").unwrap(), "X", 0.0, 1.0).unwrap();
+        assert!((result.value - (std::f64::consts::E - 1.0)).abs() <= result.error.max(1e-15));
+        assert!(result.error < 1e-10);
+
+        let result = quadrature(|x| Ok(x.cos()), 0.0, PI / 2.0, 1e-6).unwrap();
+        assert!((result.value - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fails_on_divergence() {
+        assert_eq!(
+            "Math error: the integral doesn't converge",
+            evaluate("nintegrate(1/X, X, 0, 1)")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Can't evaluate the free variable 'X'",
+            evaluate("nintegrate(X, Y, 0, 1)").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Syntax error: 'nintegrate' takes 4 arguments but 2 were given",
+            evaluate("nintegrate(X, X)").unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod test {
+    use crate::numeric_evaluator::evaluate;
+    use crate::parser::parse;
+    use crate::solver::{nsolve, roots};
+
+    fn setup(equation: &str, guess: f64) -> String {
+        match nsolve(&parse(equation).unwrap(), "X", guess) {
+            Ok(root) => root.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_roots(equation: &str, lower: f64, upper: f64) -> Vec<f64> {
+        roots(&parse(equation).unwrap(), "X", lower, upper).unwrap()
+    }
+
+    #[test]
+    fn can_parse_equations_in_arguments() {
+        assert_eq!(
+            "nsolve((1X^(2)=2), 1X^(1), 1)",
+            parse("nsolve(X^2 = 2, X, 1)").unwrap().to_string()
+        );
+        assert_eq!(
+            "((1X^(1)+1)=(2*3))",
+            parse("X + 1 = 2*3").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn can_nsolve_with_newton() {
+        assert_eq!("1.414213562373095", setup("X^2 = 2", 1.0));
+        assert_eq!("-1.414213562373095", setup("X^2 = 2", -1.0));
+        assert_eq!("1.09861228866811", setup("e^X = 3", 0.0));
+        assert_eq!("2.094551481542327", setup("X^3 - 2X - 5", 2.0));
+        assert_eq!("0.999847741531088", setup("cos(X) = X", 0.5));
+    }
+
+    #[test]
+    fn can_nsolve_with_bracketing() {
+        let root: f64 = setup("floor(X) = 2", 0.0).parse().unwrap();
+        assert!((2.0..3.0).contains(&root));
+    }
+
+    #[test]
+    fn fails_without_root() {
+        assert_eq!(
+            "Solver error: no root found starting from 0",
+            setup("X^2 + 1", 0.0)
+        );
+        assert_eq!(
+            "Solver error: no root found starting from 1",
+            setup("1/X", 1.0)
+        );
+    }
+
+    #[test]
+    fn can_find_all_roots() {
+        assert_eq!(
+            vec![-360.0, -180.0, 0.0, 180.0, 360.0],
+            setup_roots("sin(X)", -400.0, 400.0)
+        );
+        assert_eq!(vec![-1.0, 0.0, 1.0], setup_roots("X^3 = X", -2.0, 2.0));
+        let roots = setup_roots("X^2 - 3", 5.0, -5.0);
+        assert_eq!(2, roots.len());
+        assert!((roots[0] + 3f64.sqrt()).abs() < 1e-14);
+        assert!((roots[1] - 3f64.sqrt()).abs() < 1e-14);
+        assert!(setup_roots("X^2 + 1", -3.0, 3.0).is_empty());
+        // ln is undefined left of 0, which is skipped
+        assert_eq!(vec![1.0], setup_roots("ln(X)", -2.0, 2.0));
+    }
+
+    #[test]
+    fn can_find_touching_roots() {
+        assert_eq!(vec![-2.0, 1.0], setup_roots("(X-1)^2*(X+2)", -5.0, 5.0));
+    }
+
+    #[test]
+    fn skips_poles() {
+        assert!(setup_roots("1/X", -1.0, 1.0).is_empty());
+        assert_eq!(vec![0.0, 180.0, 360.0], setup_roots("tan(X)", 0.0, 360.0));
+    }
+
+    #[test]
+    fn can_eval_nsolve() {
+        assert_eq!(3.0, evaluate("nsolve(X^3 = 27, X, 1)").unwrap());
+        let integral = evaluate("nintegrate(nsolve(X^2 = Y, X, 1), Y, 0, 1)").unwrap();
+        assert!((integral - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            "Syntax error: 'nsolve' expects a variable, found '2'",
+            evaluate("nsolve(X, 2, 1)").unwrap_err().to_string()
+        );
+    }
+}
@@ -34,13 +34,13 @@ mod test {
 
     #[test]
     fn can_optimize_zero_subtraction() {
-        assert_eq!("645", setup_single("0-645"));
+        assert_eq!("-(645)", setup_single("0-645"));
         assert_eq!("645", setup_single("645-0"));
     }
 
     #[test]
     fn can_optimize_zero_subtraction_in_expression() {
-        assert_eq!("(55*645)", setup_single("55*(0-645)"));
+        assert_eq!("(55*-(645))", setup_single("55*(0-645)"));
         assert_eq!("(24*645)", setup_single("24*645-0"));
     }
 
@@ -54,6 +54,12 @@ mod test {
         assert_eq!("0", setup_single("(32894/132)-(32894/132)"));
     }
 
+    #[test]
+    fn can_optimize_commuted_subtraction() {
+        assert_eq!("0", setup_single("X*2-2*X"));
+        assert_eq!("0", setup_single("(A+B)-(B+A)"));
+    }
+
     #[test]
     fn can_optimize_one_multiplication() {
         assert_eq!("645", setup_single("1*645"));
@@ -86,6 +92,11 @@ mod test {
         assert_eq!("1", setup_single("(32894-132)/(32894-132)"));
     }
 
+    #[test]
+    fn can_optimize_commuted_division() {
+        assert_eq!("1", setup_single("(X+1)/(1+X)"));
+    }
+
     #[test]
     fn can_optimize_double_powers() {
         assert_eq!("(3^(5+10))", setup_single("3^5*3^10"));
@@ -108,6 +108,15 @@ mod test {
         assert_eq!("((1+1)=(4-2))", setup_equation("1+1=4-2"))
     }
 
+    #[test]
+    fn can_parse_inequalities() {
+        assert_eq!("((1X^(2)-4)<0)", setup_basic("X^2 - 4 < 0"));
+        assert_eq!("(1X^(1)>=(2*1Y^(1)))", setup_basic("X >= 2*Y"));
+        assert_eq!("(1X^(1)<=1)", setup_basic("X <= 1"));
+        assert_eq!("(1X^(1)>1)", setup_basic("X > 1"));
+        assert_eq!("(1X^(1)!=1)", setup_basic("X != 1"));
+    }
+
     #[test]
     fn can_parse_constants() {
         assert_eq!("pi", setup_basic("pi"));
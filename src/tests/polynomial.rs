@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod test {
+    use crate::math::Rational;
+    use crate::parser::parse;
+    use crate::polynomial::{groebner_basis, Monomial, MonomialOrder, Polynomial};
+
+    fn setup(expression: &str) -> Polynomial {
+        Polynomial::from_expr(&parse(expression).unwrap()).unwrap()
+    }
+
+    fn leading(expression: &str, order: MonomialOrder) -> String {
+        setup(expression).leading_term(order).unwrap().0.to_string()
+    }
+
+    #[test]
+    fn can_convert_from_expr() {
+        assert_eq!("X^2 + 2*X*Y + Y^2", setup("(X + Y)^2").to_string());
+        assert_eq!("3*X*Y^2", setup("3*X*Y^2").to_string());
+        assert_eq!("X^2 - 1", setup("(X - 1)*(X + 1)").to_string());
+        assert_eq!("1/3*X + 1", setup("X/3 + 1").to_string());
+        assert_eq!("0", setup("X*Y - Y*X").to_string());
+    }
+
+    #[test]
+    fn can_reject_non_polynomials() {
+        assert!(Polynomial::from_expr(&parse("sin(X)").unwrap()).is_err());
+        assert!(Polynomial::from_expr(&parse("1/X").unwrap()).is_err());
+        assert!(Polynomial::from_expr(&parse("X^0.5").unwrap()).is_err());
+        assert!(Polynomial::from_expr(&parse("X^Y").unwrap()).is_err());
+    }
+
+    #[test]
+    fn can_report_coefficient_overflow() {
+        let err = Polynomial::from_expr(&parse("(3X+7)^60").unwrap()).unwrap_err();
+        assert_eq!(
+            "Polynomial error: the coefficients of '((3X^(1)+7)^60)' are too large",
+            err.to_string()
+        );
+        assert_eq!(None, setup("3X+7").checked_pow(60));
+        assert!(setup("3X+7").checked_pow(20).is_some());
+    }
+
+    #[test]
+    fn can_convert_to_expr() {
+        assert_eq!(
+            "((1X^(2)-3X^(1))+2)",
+            setup("(X-1)*(X-2)").to_expr().to_string()
+        );
+        assert_eq!("(2X^(1)*1Y^(1))", setup("2*X*Y").to_expr().to_string());
+        assert_eq!("((1/3)*1X^(1))", setup("X/3").to_expr().to_string());
+        assert_eq!("0", Polynomial::zero().to_expr().to_string());
+    }
+
+    #[test]
+    fn can_do_polynomial_arithmetic() {
+        let a = setup("X + Y");
+        let b = setup("X - Y");
+        assert_eq!("X^2 - Y^2", (a.clone() * b.clone()).to_string());
+        assert_eq!("2*X", (a.clone() + b.clone()).to_string());
+        assert_eq!("2*Y", (a.clone() - b).to_string());
+        assert_eq!("X^3 + 3*X^2*Y + 3*X*Y^2 + Y^3", a.pow(3).to_string());
+        assert_eq!(Some(3), a.pow(3).degree());
+        assert_eq!(Some(2), setup("X^2*Y + Y").degree_in("X"));
+        assert_eq!(vec!["X", "Y"], setup("X^2*Y + Y").variables());
+        assert_eq!(
+            Rational::integer(3),
+            a.pow(3).coefficient(&Monomial::new([("X", 2), ("Y", 1)]))
+        );
+    }
+
+    #[test]
+    fn can_order_monomials() {
+        let expression = "X*Y^2 + X^2 + Z^3";
+        assert_eq!("X^2", leading(expression, MonomialOrder::Lex));
+        assert_eq!("X*Y^2", leading(expression, MonomialOrder::GrLex));
+
+        // X*Z^2 and Y^3 only differ under grevlex
+        let expression = "X*Z^2 + Y^3";
+        assert_eq!("X*Z^2", leading(expression, MonomialOrder::GrLex));
+        assert_eq!("Y^3", leading(expression, MonomialOrder::GrevLex));
+    }
+
+    fn setup_basis(polynomials: &[&str], order: MonomialOrder) -> String {
+        let polynomials: Vec<Polynomial> = polynomials.iter().map(|p| setup(p)).collect();
+        groebner_basis(&polynomials, order)
+            .iter()
+            .map(|polynomial| polynomial.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn can_compute_groebner_bases() {
+        assert_eq!(
+            "X^2 - Y, Y^2 + Y - 1",
+            setup_basis(&["X^2 + Y^2 - 1", "Y - X^2"], MonomialOrder::Lex)
+        );
+        assert_eq!(
+            "X^2, X*Y, Y^2 - 1/2*X",
+            setup_basis(&["X^3 - 2*X*Y", "X^2*Y - 2*Y^2 + X"], MonomialOrder::GrLex)
+        );
+        assert_eq!("1", setup_basis(&["X*Y - 1", "X"], MonomialOrder::Lex));
+        assert_eq!(
+            "X*Y + 1/2*Y",
+            setup_basis(&["2*X*Y + Y", "4*X*Y + 2*Y"], MonomialOrder::Lex)
+        );
+    }
+
+    #[test]
+    fn can_reduce_by_several_divisors() {
+        let divisors = [setup("X*Y - 1"), setup("Y^2 - 1")];
+        assert_eq!(
+            "Y + 1",
+            setup("X*Y^2 + X*Y")
+                .reduce(&divisors, MonomialOrder::Lex)
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod test {
+    use crate::math::{Complex, Rational};
+
+    fn ratio(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn can_normalize_fractions() {
+        assert_eq!(ratio(1, 2), ratio(2, 4));
+        assert_eq!(ratio(-1, 2), ratio(1, -2));
+        assert_eq!("-3/4", ratio(6, -8).to_string());
+        assert_eq!(None, Rational::new(1, 0));
+    }
+
+    #[test]
+    fn can_convert_decimals() {
+        assert_eq!(Some(ratio(1, 10)), Rational::from_f64(0.1));
+        assert_eq!(Some(ratio(-5, 2)), Rational::from_f64(-2.5));
+        assert_eq!(None, Rational::from_f64(f64::NAN));
+    }
+
+    #[test]
+    fn can_do_rational_arithmetic() {
+        assert_eq!(ratio(5, 6), ratio(1, 2) + ratio(1, 3));
+        assert_eq!(ratio(1, 6), ratio(1, 2) - ratio(1, 3));
+        assert_eq!(ratio(1, 6), ratio(1, 2) * ratio(1, 3));
+        assert_eq!(ratio(3, 2), ratio(1, 2) / ratio(1, 3));
+        assert_eq!(Some(ratio(8, 27)), ratio(2, 3).checked_pow(3));
+        assert_eq!(Some(ratio(9, 4)), ratio(2, 3).checked_pow(-2));
+        assert_eq!(
+            None,
+            Rational::integer(i128::MAX).checked_add(&Rational::ONE)
+        );
+        assert!(ratio(1, 3) < ratio(1, 2));
+    }
+
+    #[test]
+    fn can_do_complex_arithmetic() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(3.0, -1.0);
+        assert_eq!(Complex::new(5.0, 5.0), a * b);
+        assert_eq!(a, (a * b) / b);
+        assert_eq!(Complex::new(0.0, 2.0), Complex::real(-4.0).sqrt());
+        assert_eq!(Complex::real(-2.0), Complex::real(-8.0).cbrt());
+        assert_eq!("1-2i", a.conj().to_string());
+    }
+}
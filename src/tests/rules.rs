@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::dag::ExprPool;
+    use crate::optimizer::{standard_rules, Rule, RuleSet};
+    use crate::parser::{parse, parse_pattern, Expr};
+    use crate::rule;
+
+    fn setup(rules: &RuleSet, expression: &str) -> String {
+        rules.rewrite(parse(expression).unwrap()).to_string()
+    }
+
+    #[test]
+    fn can_parse_patterns() {
+        assert_eq!("(?a*1)", parse_pattern("?a * 1").unwrap().to_string());
+        assert_eq!("-(?abc)", parse_pattern("-?abc").unwrap().to_string());
+        assert!(parse("?a + 1").is_err());
+    }
+
+    #[test]
+    fn can_apply_rule() {
+        let rule = rule!("?a * 1" => "?a");
+        assert_eq!(
+            "(2+3)",
+            rule.apply(&parse("(2+3)*1").unwrap()).unwrap().to_string()
+        );
+        assert!(rule.apply(&parse("(2+3)*2").unwrap()).is_none());
+    }
+
+    #[test]
+    fn can_match_repeated_wildcards() {
+        let rule = rule!("?a - ?a" => "0");
+        assert!(rule.apply(&parse("sin(X)-sin(X)").unwrap()).is_some());
+        assert!(rule.apply(&parse("(A+B)-(B+A)").unwrap()).is_some());
+        assert!(rule.apply(&parse("sin(X)-cos(X)").unwrap()).is_none());
+    }
+
+    #[test]
+    fn can_check_conditions() {
+        let rule = rule!("?a % ?b" => "0", if |b| b["a"] == b["b"]);
+        assert!(rule.apply(&parse("X % X").unwrap()).is_some());
+        assert!(rule.apply(&parse("X % Y").unwrap()).is_none());
+    }
+
+    #[test]
+    fn can_compute_rewrites() {
+        let rule = Rule::computed("?a + ?b", |b| match (&b["a"], &b["b"]) {
+            (Expr::Number(a), Expr::Number(b)) => Some(Expr::Number(a + b)),
+            _ => None,
+        })
+        .unwrap();
+        let mut rules = RuleSet::new();
+        rules.add(rule).unwrap();
+
+        assert_eq!("10", setup(&rules, "1+2+3+4"));
+        assert_eq!("(3+1X^(1))", setup(&rules, "1+2+X"));
+    }
+
+    #[test]
+    fn can_add_user_rules() {
+        let mut rules = standard_rules();
+        rules.add(rule!("?a % ?a" => "0")).unwrap();
+
+        assert_eq!("0", setup(&rules, "(X*1) % X"));
+        assert_eq!("(1X^(1)%1X^(1))", setup(&standard_rules(), "(X*1) % X"));
+    }
+
+    #[test]
+    fn can_respect_rule_order() {
+        let mut rules = RuleSet::new();
+        rules.add(rule!("?a * 0" => "0")).unwrap();
+        rules.add(rule!("?a * ?b" => "?b / ?a")).unwrap();
+
+        assert_eq!("0", setup(&rules, "X*0"));
+    }
+
+    #[test]
+    fn can_reject_non_terminating_rules() {
+        let mut rules = RuleSet::new();
+        assert!(rules.add(rule!("?a" => "?a + 0")).is_err());
+        assert!(rules.add(rule!("?a + ?b" => "?b + ?a")).is_err());
+        assert!(rules.add(rule!("?a * 1" => "?b")).is_err());
+        assert!(rules.rules().is_empty());
+    }
+
+    #[test]
+    fn can_stop_on_cycles() {
+        let mut rules = RuleSet::new();
+        rules.add(rule!("?a * 2" => "?a + ?a")).unwrap();
+        rules.add(rule!("?a + ?a" => "?a * 2")).unwrap();
+
+        assert_eq!("(sin(1)*2)", setup(&rules, "sin(1)*2"));
+    }
+
+    #[test]
+    fn can_rewrite_shared_subterms_once() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let mut rules = RuleSet::new();
+        let rule = Rule::computed("sin(?a)", |b| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            Some(Expr::Function {
+                name: "cos".to_string(),
+                args: vec![Box::new(b["a"].clone())],
+            })
+        })
+        .unwrap();
+        rules.add(rule).unwrap();
+
+        let mut pool = ExprPool::new();
+        let root = pool.intern(&parse("sin(X)*sin(X) + sin(X)").unwrap());
+        let root = rules.rewrite_id(&mut pool, root);
+
+        assert_eq!(
+            "((cos(1X^(1))*cos(1X^(1)))+cos(1X^(1)))",
+            pool.to_expr(root).to_string()
+        );
+        assert_eq!(1, CALLS.load(Ordering::SeqCst));
+    }
+}
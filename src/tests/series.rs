@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::algebra::{evaluate, series};
+    use crate::numeric_evaluator::evaluate_with;
+    use crate::parser::{parse, Expr};
+
+    fn setup(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_expand_elementary_functions() {
+        assert_eq!(
+            "((((1+1X^(1))+0.5X^(2))+((1/6)*1X^(3)))+O(1X^(4)))",
+            setup("series(e^X, X, 0, 4)")
+        );
+        assert_eq!(
+            "(((1X^(1)-0.5X^(2))+((1/3)*1X^(3)))+O(1X^(4)))",
+            setup("series(ln(1 + X), X, 0, 4)")
+        );
+        assert_eq!(
+            "(((1+0.5X^(1))-0.125X^(2))+O(1X^(3)))",
+            setup("series(sqrt(1 + X), X, 0, 3)")
+        );
+        assert_eq!(
+            "(((2+((1/12)*1X^(1)))-((1/288)*1X^(2)))+O(1X^(3)))",
+            setup("series(cbrt(8 + X), X, 0, 3)")
+        );
+        assert_eq!(
+            "(((1+(1X^(1)*ln(2)))+(0.5X^(2)*(ln(2)^2)))+O(1X^(3)))",
+            setup("series(2^X, X, 0, 3)")
+        );
+        assert_eq!(
+            "((((1+1X^(1))+0.5X^(2))+((1/6)*1X^(3)))+O(1X^(4)))",
+            setup("series(exp(X), X, 0, 4)")
+        );
+    }
+
+    #[test]
+    fn can_expand_trigonometry_in_degrees() {
+        assert_eq!(
+            "((((((1/180)*1X^(1))*pi)-(((1/34992000)*1X^(3))*(pi^3)))+(((1/22674816000000)*1X^(5))*(pi^5)))+O(1X^(6)))",
+            setup("series(sin(X), X, 0, 6)")
+        );
+        assert_eq!(
+            "(((180X^(1)/pi)-(60X^(3)/pi))+O(1X^(4)))",
+            setup("series(atan(X), X, 0, 4)")
+        );
+    }
+
+    #[test]
+    fn can_expand_around_other_points() {
+        assert_eq!(
+            "((((1X^(1)-1)-(0.5*((1X^(1)-1)^2)))+((1/3)*((1X^(1)-1)^3)))+O(((1X^(1)-1)^4)))",
+            setup("series(ln(X), X, 1, 4)")
+        );
+        assert_eq!(
+            "(((4+(5*(1X^(1)-1)))+((1X^(1)-1)^2))+O(((1X^(1)-1)^4)))",
+            setup("series(X^2 + 3X, X, 1, 4)")
+        );
+        assert_eq!(
+            "(((1+(1X^(1)-1))+((1X^(1)-1)^2))+O(((1X^(1)-1)^3)))",
+            setup("series(X^X, X, 1, 3)")
+        );
+    }
+
+    #[test]
+    fn can_expand_products_and_quotients() {
+        assert_eq!(
+            "((((1+1X^(1))+1X^(2))+1X^(3))+O(1X^(4)))",
+            setup("series(1/(1 - X), X, 0, 4)")
+        );
+        assert_eq!(
+            "((0.5+(((1/129600)*1X^(2))*(pi^2)))+O(1X^(3)))",
+            setup("series(sin(X)/sin(2X), X, 0, 3)")
+        );
+        assert_eq!(
+            "((1Y^(1)+(1X^(2)*1Y^(1)))+O(1X^(3)))",
+            setup("series(Y*X^2 + Y, X, 0, 3)")
+        );
+    }
+
+    #[test]
+    fn can_expand_poles_into_negative_powers() {
+        assert_eq!("(1X^(-2)+O(1X^(2)))", setup("series(1/X^2, X, 0, 2)"));
+        assert_eq!(
+            "((1X^(-1)-(((1/64800)*1X^(1))*(pi^2)))+O(1X^(3)))",
+            setup("series(cos(X)/X, X, 0, 3)")
+        );
+    }
+
+    #[test]
+    fn can_approximate_near_the_point() {
+        let expr = parse("e^(sin(X)) * cos(X)").unwrap();
+        let approximation = match series(&expr, "X", &parse("0").unwrap(), 6).unwrap() {
+            // Drops the O term
+            Expr::BinOp { lhs, .. } => *lhs,
+            other => other,
+        };
+        let at =
+            |expr: &Expr| evaluate_with(expr, &HashMap::from([("X".to_string(), 0.5)])).unwrap();
+        assert!((at(&expr) - at(&approximation)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn can_reject_singular_points() {
+        assert_eq!(
+            "Math error: 'abs(1X^(1))' has no power series around 0",
+            setup("series(abs(X), X, 0, 3)")
+        );
+        assert_eq!(
+            "Math error: 'sqrt(1X^(1))' has no power series around 0",
+            setup("series(sqrt(X), X, 0, 3)")
+        );
+        assert_eq!(
+            "Math error: '(e^(1/1X^(1)))' has no power series around 0",
+            setup("series(e^(1/X), X, 0, 3)")
+        );
+        assert_eq!(
+            "Math error: 'tan(1X^(1))' has no power series around 90",
+            setup("series(tan(X), X, 90, 2)")
+        );
+        assert_eq!(
+            "Math error: 'tan(1X^(1))' has no power series around 270",
+            setup("series(tan(X), X, 270, 2)")
+        );
+        assert_eq!(
+            "((2+(1X^(1)-2))+O(((1X^(1)-2)^3)))",
+            setup("series(abs(X), X, 2, 3)")
+        );
+    }
+
+    #[test]
+    fn can_reject_invalid_arguments() {
+        assert_eq!(
+            "Math error: the order of a series must be a whole number from 0 to 30, found '2.5'",
+            setup("series(X, X, 0, 2.5)")
+        );
+        assert_eq!(
+            "Math error: a series can't be expanded around '1X^(1)', which depends on its variable",
+            setup("series(X, X, X, 2)")
+        );
+        assert_eq!(
+            "Syntax error: 'series' takes 4 arguments but 2 were given",
+            setup("series(X, X)")
+        );
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod test {
+    use crate::numeric_evaluator::evaluate_expr;
+    use crate::parser::{parse, parse_equation, Optimize};
+    use crate::solver::{all_roots, solve};
+
+    fn evaluate(expression: &str) -> f64 {
+        evaluate_expr(&parse(expression).unwrap()).unwrap()
+    }
+
+    fn setup(equation: &str) -> String {
+        solve(&parse_equation(equation).unwrap(), "X")
+            .unwrap()
+            .to_string()
+    }
+
+    fn setup_roots(expression: &str, digits: u32) -> String {
+        match all_roots(&parse(expression).unwrap(), "X", digits) {
+            Ok(roots) => roots
+                .iter()
+                .map(|root| root.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn setup_equation(equation: &str) -> String {
+        match parse_equation(equation).unwrap().optimize_equation("X") {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_solve_linear_equation() {
+        assert_eq!("{-12}", setup("3X + 5 = 2X - 7"));
+        assert_eq!("{4}", setup("2X = 8"));
+        assert_eq!("{0}", setup("5X = 0"));
+        assert_eq!("{2.5}", setup("2*(X + 1) = 7"));
+        assert_eq!("{-3}", setup("X/3 + 1 = 0"));
+    }
+
+    #[test]
+    fn can_isolate_variable() {
+        assert_eq!("(1X^(1)=-12)", setup_equation("3X + 5 = 2X - 7"));
+        assert_eq!("(1X^(1)=1.5)", setup_equation("4X - 2 = 2X + 1"));
+    }
+
+    #[test]
+    fn can_solve_with_other_variables() {
+        assert_eq!("{(-(1Y^(1))/2)}", setup("2X + Y = 0"));
+    }
+
+    #[test]
+    fn can_detect_degenerate_equations() {
+        assert_eq!("no solution", setup("X + 1 = X + 2"));
+        assert_eq!("infinitely many solutions", setup("2X + 2 = 2*(X + 1)"));
+        assert_eq!(
+            "Solver error: the equation has no solution",
+            setup_equation("X = X + 1")
+        );
+        assert_eq!(
+            "Solver error: the equation has infinitely many solutions",
+            setup_equation("X = X")
+        );
+    }
+
+    #[test]
+    fn can_reject_non_polynomial_equations() {
+        let equation = parse_equation("sin(X) = 0").unwrap();
+        assert!(solve(&equation, "X").is_err());
+        let equation = parse_equation("X^0.5 = 2").unwrap();
+        assert!(solve(&equation, "X").is_err());
+    }
+
+    #[test]
+    fn can_solve_quadratic_equation() {
+        assert_eq!("{2, 3}", setup("X^2 - 5X + 6 = 0"));
+        assert_eq!("{-2, 2}", setup("X*X = 4"));
+        assert_eq!("{2}", setup("(X - 2)^2 = 0"));
+        assert_eq!("{-(sqrt(2)), sqrt(2)}", setup("X^2 - 2 = 0"));
+        assert_eq!(
+            "{(-0.75-(0.25*sqrt(17))), (-0.75+(0.25*sqrt(17)))}",
+            setup("2X^2 + 3X - 1 = 0")
+        );
+        assert_eq!("{(-1/3), 1}", setup("3X^2 - 2X - 1 = 0"));
+    }
+
+    #[test]
+    fn can_find_complex_roots() {
+        assert_eq!("{-(i), i}", setup("X^2 + 1 = 0"));
+        assert_eq!("{(1-(2*i)), (1+(2*i))}", setup("X^2 - 2X + 5 = 0"));
+    }
+
+    #[test]
+    fn can_solve_cubic_equation() {
+        assert_eq!("{1, 2, 3}", setup("X^3 - 6X^2 + 11X - 6 = 0"));
+        assert_eq!(
+            "{cbrt(2), ((-0.5*cbrt(2))-(((0.5*sqrt(3))*i)*cbrt(2))), ((-0.5*cbrt(2))+(((0.5*sqrt(3))*i)*cbrt(2)))}",
+            setup("X^3 - 2 = 0")
+        );
+        // Three irrational real roots have no real radical form
+        let roots = "{(2*cos((-210-((1/3)*atan(-(((1/3)*sqrt(3)))))))), (2*cos((-90-((1/3)*atan(-(((1/3)*sqrt(3)))))))), (2*cos((30-((1/3)*atan(-(((1/3)*sqrt(3))))))))}";
+        assert_eq!(roots, setup("X^3 - 3X + 1 = 0"));
+        let values = ["0-2*cos(20)", "2*cos(80)", "2*cos(40)"];
+        for (root, value) in roots[1..roots.len() - 1].split(", ").zip(values) {
+            assert_eq!(evaluate(root), evaluate(value));
+        }
+    }
+
+    #[test]
+    fn can_solve_quartic_equation() {
+        assert_eq!(
+            "{-(sqrt(3)), -(sqrt(2)), sqrt(2), sqrt(3)}",
+            setup("X^4 - 5X^2 + 6 = 0")
+        );
+        assert_eq!("{-2, -1, 1, 2}", setup("(X^2 - 1)*(X^2 - 4) = 0"));
+        assert_eq!(
+            "{(-(sqrt((0.75+(0.5*sqrt(13)))))+0.5), (sqrt((0.75+(0.5*sqrt(13))))+0.5), (-((sqrt(-((0.75-(0.5*sqrt(13)))))*i))+0.5), ((sqrt(-((0.75-(0.5*sqrt(13)))))*i)+0.5)}",
+            setup("X^4 - 2X^3 + X - 3 = 0")
+        );
+        // The resolvent cubic has three irrational real roots
+        let roots = setup("X^4 + X + 1 = 0");
+        assert!(roots.contains("cos((30-((1/3)*atan(((3/229)*sqrt(687))))))"));
+        assert_eq!(4, roots.matches("), (").count() + 1);
+    }
+
+    #[test]
+    fn can_approximate_higher_degrees() {
+        assert_eq!(
+            "{1.167303978261, (-0.764884433601-(0.352471546032*i)), (-0.764884433601+(0.352471546032*i)), (0.18123244447-(1.083954101318*i)), (0.18123244447+(1.083954101318*i))}",
+            setup("X^5 - X - 1 = 0")
+        );
+        assert_eq!("{-1.772453850906, 1.772453850906}", setup("X^2 - pi = 0"));
+    }
+
+    #[test]
+    fn can_verify_numeric_roots() {
+        assert_eq!(
+            "{0.000000000000000000001, 1000000000000000000000}",
+            setup("X^2 - 1000000000000000000000X + 1 = 0")
+        );
+    }
+
+    #[test]
+    fn can_reject_high_degrees() {
+        let equation = parse_equation("X^70 = 1").unwrap();
+        assert_eq!(
+            "Solver error: the polynomial has degree 70, at most 64 is supported",
+            solve(&equation, "X").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn can_report_multiple_solutions() {
+        assert_eq!(
+            "Solver error: the equation has 2 solutions, which can't be written as one equation",
+            setup_equation("X^2 = 1")
+        );
+    }
+
+    #[test]
+    fn can_find_all_roots() {
+        assert_eq!(
+            "-0.5610700072, 0.599241028, 1.3480469413, (-0.693108981-(1.3137579757*i)), (-0.693108981+(1.3137579757*i))",
+            setup_roots("X^5 - 3X^2 + 1", 10)
+        );
+        assert_eq!(
+            "1.46459189, (-0.73229594-(1.26837378*i)), (-0.73229594+(1.26837378*i))",
+            setup_roots("X^3 = pi", 8)
+        );
+        assert_eq!("", setup_roots("3", 4));
+    }
+
+    #[test]
+    fn can_detect_multiplicities() {
+        assert_eq!("-2 (×2), 1 (×3)", setup_roots("(X-1)^3*(X+2)^2", 12));
+        assert_eq!("3, -(i) (×2), i (×2)", setup_roots("(X^2+1)^2*(X-3)", 10));
+        assert_eq!("0 (×4)", setup_roots("X^4", 5));
+        assert_eq!("3.141593 (×2)", setup_roots("(X-pi)^2", 6));
+    }
+
+    #[test]
+    fn rejects_invalid_all_roots() {
+        assert_eq!(
+            "Solver error: roots are only accurate to 15 digits, 16 were requested",
+            setup_roots("X^2 - 2", 16)
+        );
+        assert_eq!(
+            "Solver error: the equation is not a polynomial in 'X'",
+            setup_roots("sin(X)", 4)
+        );
+        assert_eq!(
+            "Solver error: the equation has infinitely many solutions",
+            setup_roots("X - X", 4)
+        );
+    }
+
+    #[test]
+    fn can_reject_wrong_arity() {
+        // sin(1, 2) can't be evaluated, so isn't a coefficient
+        let equation = parse_equation("X^2 + sin(1, 2) = 0").unwrap();
+        assert_eq!(
+            "Solver error: the equation is not a polynomial in 'X'",
+            solve(&equation, "X").unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::algebra::evaluate;
+    use crate::numeric_evaluator;
+    use crate::parser::parse;
+
+    fn setup(expression: &str) -> String {
+        match evaluate(&parse(expression).unwrap()) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn assert_close(expected: f64, expression: &str) {
+        let value = numeric_evaluator::evaluate(expression).unwrap();
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "{expression} = {value}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn can_evaluate_sums_numerically() {
+        assert_close(5050.0, "sum(K, K, 1, 100)");
+        assert_close(0.0, "sum(K, K, 5, 1)");
+        assert_close(25.0, "sum(sum(J*K, J, 1, K), K, 1, 3)");
+        assert_close(PI * PI / 6.0, "sum(1/K^2, K, 1, inf)");
+        assert_close(2.612375348685488, "sum(1/K^1.5, K, 1, inf)");
+        assert_close(-(2f64.ln()), "sum(cos(180K)/K, K, 1, inf)");
+        assert_close(3.0, "sum(1/2^abs(K), K, -(inf), inf)");
+        assert_close(1.772637204826652, "sum(e^(0-K^2), K, -(inf), inf)");
+    }
+
+    #[test]
+    fn can_evaluate_products_numerically() {
+        assert_close(120.0, "product(K, K, 1, 5)");
+        assert_close(1.0, "product(K, K, 1, 0)");
+        assert_close(0.5, "product(1 - 1/K^2, K, 2, inf)");
+    }
+
+    #[test]
+    fn numeric_sums_check_convergence() {
+        let error = |expression: &str| {
+            numeric_evaluator::evaluate(expression)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            "Math error: the series doesn't converge",
+            error("sum(1/K, K, 1, inf)")
+        );
+        assert_eq!(
+            "Math error: the series doesn't converge",
+            error("sum(2^K, K, 0, inf)")
+        );
+        assert_eq!(
+            "Math error: the series doesn't converge",
+            error("sum(cos(180K), K, 0, inf)")
+        );
+        assert_eq!(
+            "Math error: the bound '1.5' of a sum or product is not a whole number",
+            error("sum(K, K, 1.5, 3)")
+        );
+    }
+
+    #[test]
+    fn can_sum_polynomials() {
+        assert_eq!("(0.5N^(2)+0.5N^(1))", setup("sum(K, K, 1, N)"));
+        assert_eq!(
+            "((((1/3)*1N^(3))+0.5N^(2))+((1/6)*1N^(1)))",
+            setup("sum(K^2, K, 1, N)")
+        );
+        assert_eq!("((1N^(2)+2N^(1))+1)", setup("sum(2K + 1, K, 0, N)"));
+        assert_eq!("3025", setup("sum(K^3, K, 0, 10)"));
+        assert_eq!(
+            "((0.5A^(1)*1N^(2))+(0.5A^(1)*1N^(1)))",
+            setup("sum(A*K, K, 1, N)")
+        );
+    }
+
+    #[test]
+    fn can_sum_geometric_series() {
+        assert_eq!("255", setup("sum(2^K, K, 0, 7)"));
+        assert_eq!("2", setup("sum(0.5^K, K, 0, inf)"));
+        assert_eq!("3", setup("sum(3*0.5^K, K, 1, inf)"));
+        assert_eq!(
+            "((1-(1X^(1)^(1N^(1)+1)))/(-1X^(1)+1))",
+            setup("sum(X^K, K, 0, N)")
+        );
+        assert_eq!(
+            "Math error: the series doesn't converge",
+            setup("sum(2^K, K, 0, inf)")
+        );
+    }
+
+    #[test]
+    fn can_sum_telescoping_series() {
+        assert_eq!(
+            "sqrt((1N^(1)+1))",
+            setup("sum(sqrt(K+1) - sqrt(K), K, 0, N)")
+        );
+        assert_eq!("1", setup("sum(1/(K*(K+1)), K, 1, inf)"));
+        assert_eq!("1", setup("sum(1/(K^2 + 3K + 2), K, 0, inf)"));
+        assert_eq!("0.75", setup("sum(1/(K*(K+2)), K, 1, inf)"));
+    }
+
+    #[test]
+    fn can_take_products() {
+        assert_eq!("(1N^(1)+1)", setup("product((K+1)/K, K, 1, N)"));
+        assert_eq!("(2^(0.5N^(2)+0.5N^(1)))", setup("product(2^K, K, 1, N)"));
+        assert_eq!("0.5", setup("product(1 - 1/K^2, K, 2, inf)"));
+        assert_eq!("120", setup("product(K, K, 1, 5)"));
+    }
+
+    #[test]
+    fn sums_without_closed_form_stay() {
+        assert_eq!(
+            "sum((1/1K^(2)), 1K^(1), 1, 1N^(1))",
+            setup("sum(1/K^2, K, 1, N)")
+        );
+        assert_eq!("1.644934066848228", setup("sum(1/K^2, K, 1, inf)"));
+        assert_eq!(
+            "Math error: the series doesn't converge",
+            setup("sum(1/K, K, 1, inf)")
+        );
+    }
+}
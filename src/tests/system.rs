@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::evaluate;
+    use crate::parser::parse;
+    use crate::solver::{solve_system, solve_system_with, SystemStrategy};
+
+    fn setup(equations: &str, variables: &str) -> String {
+        let (equations, variables) = (parse(equations).unwrap(), parse(variables).unwrap());
+        match solve_system(&equations, &variables) {
+            Ok(solution) => solution.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_parse_lists() {
+        assert_eq!(
+            "[1, [1X^(1), 2], f(1X^(1))]",
+            parse("[1, [X, 2], f(X)]").unwrap().to_string()
+        );
+        assert_eq!("[]", parse("[]").unwrap().to_string());
+        assert!(parse("[X + 1, 2]")
+            .unwrap()
+            .canonical_eq(&parse("[1 + X, 2]").unwrap()));
+        assert!(parse("[X, 2").is_err());
+    }
+
+    #[test]
+    fn can_solve_unique_systems() {
+        assert_eq!(
+            "{X = 1.6, Y = 0.6}",
+            setup("[2X + 3Y = 5, X - Y = 1]", "[X, Y]")
+        );
+        assert_eq!(
+            "{X = (2/3), Y = (1/3)}",
+            setup("[X + Y = 1, X - 2Y = 0]", "[X, Y]")
+        );
+        assert_eq!(
+            "{X = 1, Y = 2, Z = 3}",
+            setup("[X + Y + Z = 6, 2X - Y = 0, Z - X = 2]", "[X, Y, Z]")
+        );
+    }
+
+    #[test]
+    fn can_solve_overdetermined_systems() {
+        assert_eq!(
+            "{X = 2, Y = 1}",
+            setup("[X + Y = 3, X - Y = 1, 2X + 2Y = 6]", "[X, Y]")
+        );
+    }
+
+    #[test]
+    fn detects_inconsistent_systems() {
+        assert_eq!("no solution", setup("[X + Y = 1, 2X + 2Y = 3]", "[X, Y]"));
+        assert_eq!("no solution", setup("[X = 1, X = 2]", "[X]"));
+    }
+
+    #[test]
+    fn can_solve_underdetermined_systems() {
+        assert_eq!(
+            "{X = (1Z^(1)+1), Y = (-2Z^(1)+5)} for any Z",
+            setup("[X + Y + Z = 6, X - Z = 1]", "[X, Y, Z]")
+        );
+        assert_eq!(
+            "{X = (-1Y^(1)+2)} for any Y",
+            setup("[X + Y = 2, 2X + 2Y = 4]", "[X, Y]")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_systems() {
+        assert_eq!(
+            "Polynomial error: 'sin(1X^(1))' is not a polynomial",
+            setup("[sin(X) = Y]", "[X, Y]")
+        );
+        assert_eq!(
+            "Solver error: the system depends on 'A', which is not being solved for",
+            setup("[X + A = 1]", "[X]")
+        );
+        assert_eq!(
+            "Solver error: expected a list such as '[X, Y]', found '((1X^(1)+1Y^(1))=1)'",
+            setup("X + Y = 1", "[X, Y]")
+        );
+        assert_eq!(
+            "Syntax error: 'solve' expects a variable, found '2X^(1)'",
+            setup("[X = 1]", "[2X]")
+        );
+        assert_eq!("Solver error: expected an equation", setup("[X]", "[X]"));
+    }
+
+    fn setup_newton(equations: &str, variables: &str, guess: Vec<f64>) -> String {
+        let (equations, variables) = (parse(equations).unwrap(), parse(variables).unwrap());
+        match solve_system_with(&equations, &variables, &SystemStrategy::Newton(guess)) {
+            Ok(solution) => solution.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_solve_polynomial_systems() {
+        assert_eq!(
+            "{X = 3, Y = 4}, {X = 4, Y = 3}",
+            setup("[X^2 + Y^2 = 25, X + Y = 7]", "[X, Y]")
+        );
+        assert_eq!(
+            "{X = -(sqrt(2)), Y = -(sqrt(2))}, {X = sqrt(2), Y = sqrt(2)}",
+            setup("[X^2 = 2, Y = X]", "[X, Y]")
+        );
+        assert_eq!(
+            "{X = -(sqrt((-0.5+(0.5*sqrt(5))))), Y = (-0.5+(0.5*sqrt(5)))}, \
+             {X = sqrt((-0.5+(0.5*sqrt(5)))), Y = (-0.5+(0.5*sqrt(5)))}",
+            setup("[X^2 + Y^2 = 1, Y = X^2]", "[X, Y]")
+        );
+        assert_eq!(
+            "{X = -1, Y = -1, Z = -1}, {X = 1, Y = 1, Z = 1}",
+            setup("[X^2 + Y^2 + Z^2 = 3, X = Y, Y = Z]", "[X, Y, Z]")
+        );
+        assert_eq!("{X = 1}", setup("[X^2 = 1, X^2 - 2X + 1 = 0]", "[X]"));
+    }
+
+    #[test]
+    fn detects_degenerate_polynomial_systems() {
+        assert_eq!("no solution", setup("[X^2 + Y^2 = -1, X = Y]", "[X, Y]"));
+        assert_eq!("no solution", setup("[X*Y = 1, X = 0]", "[X, Y]"));
+        assert_eq!(
+            "Solver error: the equation has infinitely many solutions",
+            setup("[X*Y = 0]", "[X, Y]")
+        );
+    }
+
+    #[test]
+    fn can_solve_with_newton() {
+        assert_eq!(
+            "{X = 0.786151377757423, Y = 0.618033988749895}",
+            setup_newton("[X^2 + Y^2 = 1, Y = X^2]", "[X, Y]", vec![1.0, 1.0])
+        );
+        assert_eq!(
+            "{X = 0.792059968430677, Y = 0.792059968430677}",
+            setup_newton("[e^X + Y = 3, X - Y = 0]", "[X, Y]", vec![1.0, 1.0])
+        );
+        assert_eq!(
+            "Solver error: no root found starting from [1, 1]",
+            setup_newton("[X^2 + Y^2 = -1, X = Y]", "[X, Y]", vec![1.0, 1.0])
+        );
+        assert_eq!(
+            "Solver error: Newton's method needs as many equations as variables, found 1 for 2",
+            setup_newton("[X = Y]", "[X, Y]", vec![1.0, 1.0])
+        );
+        assert_eq!(
+            "Solver error: expected 2 starting values, found 1",
+            setup_newton("[X = Y, X + Y = 1]", "[X, Y]", vec![1.0])
+        );
+    }
+
+    #[test]
+    fn can_eval_solve() {
+        assert_eq!(
+            "[(1X^(1)=1.6), (1Y^(1)=0.6)]",
+            evaluate(&parse("solve([2X + 3Y = 5, X - Y = 1], [X, Y])").unwrap())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "[[(1X^(1)=3), (1Y^(1)=4)], [(1X^(1)=4), (1Y^(1)=3)]]",
+            evaluate(&parse("solve([X^2 + Y^2 = 25, X + Y = 7], [X, Y])").unwrap())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "[(1X^(1)=0.786151377757423), (1Y^(1)=0.618033988749895)]",
+            evaluate(&parse("solve([X^2 + Y^2 = 1, Y = X^2], [X, Y], [1, 1])").unwrap())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "Solver error: the equation has no solution",
+            evaluate(&parse("solve([X = 1, X = 2], [X])").unwrap())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
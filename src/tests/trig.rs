@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::evaluate;
+    use crate::parser::{parse, Optimize};
+
+    fn setup(expression: &str) -> String {
+        parse(expression).unwrap().optimize_expression().to_string()
+    }
+
+    fn setup_function(expression: &str) -> String {
+        evaluate(&parse(expression).unwrap()).unwrap().to_string()
+    }
+
+    #[test]
+    fn can_evaluate_special_angles() {
+        assert_eq!("(1/2)", setup("sin(30)"));
+        assert_eq!("(1/2)", setup("cos(60)"));
+        assert_eq!("(sqrt(2)/2)", setup("sin(45)"));
+        assert_eq!("-((sqrt(3)/2))", setup("cos(150)"));
+        assert_eq!("-((sqrt(3)/2))", setup("sin(240)"));
+        assert_eq!("sqrt(3)", setup("tan(60)"));
+        assert_eq!("-(1)", setup("tan(135)"));
+        assert_eq!("0", setup("sin(180)"));
+        assert_eq!("1", setup("cos(360)"));
+        assert_eq!("tan(90)", setup("tan(90)"));
+        assert_eq!("sin(10)", setup("sin(10)"));
+    }
+
+    #[test]
+    fn can_use_parity() {
+        assert_eq!("-((1/2))", setup("sin(-30)"));
+        assert_eq!("cos(1X^(1))", setup("cos(-(X))"));
+        assert_eq!("-(tan(2X^(1)))", setup("tan(0-2X)"));
+    }
+
+    #[test]
+    fn can_use_pythagorean_identity() {
+        assert_eq!("1", setup("sin(X)^2 + cos(X)^2"));
+        assert_eq!("1", setup("cos(2X)^2 + sin(2X)^2"));
+        assert_eq!("1", setup("sin(X)*sin(X) + cos(X)*cos(X)"));
+        assert_eq!("(1Y^(1)+1)", setup("Y + sin(X)^2 + cos(X)^2"));
+        assert_eq!("(sin(1X^(1))^2)", setup("1 - cos(X)^2"));
+        assert_eq!("(cos(1X^(1))^2)", setup("1 - sin(X)^2"));
+        assert_eq!(
+            "((sin(1X^(1))^2)+(cos(1Y^(1))^2))",
+            setup("sin(X)^2 + cos(Y)^2")
+        );
+    }
+
+    #[test]
+    fn can_expand_double_angles() {
+        assert_eq!(
+            "((2*sin(1X^(1)))*cos(1X^(1)))",
+            setup_function("trigexpand(sin(2X))")
+        );
+        assert_eq!(
+            "((cos(1X^(1))^2)-(sin(1X^(1))^2))",
+            setup_function("trigexpand(cos(2X))")
+        );
+        assert_eq!(
+            "((sin(1X^(1))*((cos(1X^(1))^2)-(sin(1X^(1))^2)))+(cos(1X^(1))*((2*sin(1X^(1)))*cos(1X^(1)))))",
+            setup_function("trigexpand(sin(3X))")
+        );
+    }
+
+    #[test]
+    fn can_expand_angle_sums() {
+        assert_eq!(
+            "((cos(1X^(1))*cos(1Y^(1)))-(sin(1X^(1))*sin(1Y^(1))))",
+            setup_function("trigexpand(cos(X+Y))")
+        );
+        assert_eq!(
+            "((sin(1X^(1))*cos(1Y^(1)))-(cos(1X^(1))*sin(1Y^(1))))",
+            setup_function("trigexpand(sin(X-Y))")
+        );
+        assert_eq!(
+            "((sin(1X^(1))*(sqrt(3)/2))+(cos(1X^(1))*(1/2)))",
+            setup_function("trigexpand(sin(X+30))")
+        );
+        assert_eq!("-(sin(1X^(1)))", setup_function("trigexpand(cos(X+90))"));
+        assert_eq!(
+            "(sin(1X^(1))/cos(1X^(1)))",
+            setup_function("trigexpand(tan(X))")
+        );
+    }
+
+    #[test]
+    fn can_reduce_products_and_powers() {
+        assert_eq!("sin(2X^(1))", setup_function("trigreduce(2*sin(X)*cos(X))"));
+        assert_eq!(
+            "((1/2)*sin(2X^(1)))",
+            setup_function("trigreduce(sin(X)*cos(X))")
+        );
+        assert_eq!(
+            "cos(2X^(1))",
+            setup_function("trigreduce(cos(X)^2 - sin(X)^2)")
+        );
+        assert_eq!("tan(1X^(1))", setup_function("trigreduce(sin(X)/cos(X))"));
+        assert_eq!(
+            "((1-cos(2X^(1)))/2)",
+            setup_function("trigreduce(sin(X)^2)")
+        );
+        assert_eq!(
+            "((1+cos(1X^(1)))/2)",
+            setup_function("trigreduce(cos(X/2)^2)")
+        );
+        assert_eq!(
+            "(((3*cos(1X^(1)))+cos(3X^(1)))/4)",
+            setup_function("trigreduce(cos(X)^3)")
+        );
+        assert_eq!(
+            "(((3*sin(1X^(1)))-sin(3X^(1)))/4)",
+            setup_function("trigreduce(sin(X)^3)")
+        );
+        assert_eq!(
+            "(((3-(4*cos(2X^(1))))+cos(4X^(1)))/8)",
+            setup_function("trigreduce(sin(X)^4)")
+        );
+    }
+
+    #[test]
+    fn can_turn_sums_into_products() {
+        assert_eq!(
+            "((2*sin(2X^(1)))*cos(1X^(1)))",
+            setup_function("trigreduce(sin(X) + sin(3X))")
+        );
+        assert_eq!(
+            "-(((2*sin((0.5X^(1)+0.5Y^(1))))*sin((0.5X^(1)-0.5Y^(1)))))",
+            setup_function("trigreduce(cos(X) - cos(Y))")
+        );
+    }
+
+    #[test]
+    fn reduce_undoes_expand() {
+        for expression in ["sin(2X)", "cos(2X)", "tan(X)"] {
+            let expanded = format!("trigreduce(trigexpand({expression}))");
+            assert_eq!(setup(expression), setup_function(&expanded));
+        }
+    }
+}